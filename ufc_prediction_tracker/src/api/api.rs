@@ -1,4 +1,6 @@
+use super::pick_cards::{Png, PngImage, Svg, SvgImage};
 use crate::db;
+use dioxus::prelude::server_fn::codec::GetUrl;
 use dioxus::prelude::*;
 use std::sync::OnceLock;
 use tracing;
@@ -9,111 +11,112 @@ pub fn set_store(store: db::Store) {
     STORE.set(store).ok();
 }
 
-fn get_store() -> &'static db::Store {
+pub(super) fn get_store() -> &'static db::Store {
     STORE.get().expect("Store not initialized")
 }
 
 #[server]
-pub async fn get_upcoming_events() -> Result<Vec<(String, String, usize)>, ServerFnError> {
-    // Simulate fetching upcoming events from a database or an API
-    // get from http://ufcstats.com/statistics/events/upcoming?page=all
-    let response = reqwest::get("http://ufcstats.com/statistics/events/upcoming?page=all")
-        .await?
-        .text()
-        .await?;
-    let doc: scraper::Html = scraper::Html::parse_document(&response);
-
-    let result = (doc
-        .select(&scraper::Selector::parse("tr.b-statistics__table-row").unwrap())
-        .filter_map(|event| {
-            event
-                .select(&scraper::Selector::parse("td.b-statistics__table-col").unwrap())
-                .next()
-                .map(|col| col.text().collect::<String>())
-        })
-        .collect::<Vec<_>>());
-    let links = (doc
-        .select(&scraper::Selector::parse("tr.b-statistics__table-row").unwrap())
-        .filter_map(|event| {
-            event
-                .select(&scraper::Selector::parse("a.b-link.b-link_style_black").unwrap())
-                .next()
-                .map(|link| {
-                    //println!("link {}", link.value().attr("href").unwrap().to_string());
-                    link.value().attr("href").unwrap().to_string()
-                })
-        })
-        .collect::<Vec<String>>());
-    let res = result
-        .clone()
-        .into_iter()
-        .map(|event| {
-            event
-                .trim()
-                .split('\n')
-                .filter(|s| !s.trim().is_empty())
-                .map(|s| "|".to_string() + s.trim() + "|")
-                .collect()
-        })
-        .collect::<Vec<String>>();
-    let zipped = res
-        .iter()
-        .zip(links.iter())
-        .collect::<Vec<(&String, &String)>>();
-    for i in zipped.clone() {
-        //println!("{}{}", i.0, i.1);
-    }
-    //println!("Upcoming Events:");
-    let store = get_store();
-    let mut ids: Vec<usize> = vec![];
-    &result.iter().zip(links.iter()).for_each(|(event, link)| {
-        let mut split: Vec<&str> = event
-            .trim()
-            .split('\n')
-            .filter(|s| !s.trim().is_empty())
-            .map(|s| s.trim())
-            .collect();
-        let s1: &str = &split[0];
-        let s2: &str = &split[1];
-        let id = store.add_event(s1, s2, link).expect("");
-        ids.push(id.clone());
-    });
-    Ok(zipped
-        .into_iter()
-        .zip(ids.iter())
-        .map(|((a, b), c)| (a.clone(), b.clone(), c.clone()))
-        .collect())
+pub async fn get_upcoming_events() -> Result<Vec<db::Event>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    super::source::upcoming_events(store).await
 }
 
 #[server]
-pub async fn get_fights(link: String) -> Result<Vec<(String, String)>, ServerFnError> {
-    // Fetch the event page
-    let response = reqwest::get(&link)
-        .await
-        .map_err(|e| ServerFnError::new(format!("Failed to fetch event page: {}", e)))?
-        .text()
-        .await
-        .map_err(|e| ServerFnError::new(format!("Failed to get event page text: {}", e)))?;
-    let doc = scraper::Html::parse_document(&response);
-    let row_selector = scraper::Selector::parse("tr.b-fight-details__table-row").unwrap();
-    let name_selector = scraper::Selector::parse("a.b-link.b-link_style_black").unwrap();
-
-    let fights = doc
-        .select(&row_selector)
-        .filter_map(|row| {
-            let mut names = row
-                .select(&name_selector)
-                .map(|el| el.text().collect::<String>().trim().to_string())
-                .filter(|s| s.clone().trim() != "View Matchup" && !s.is_empty())
-                .collect::<Vec<_>>();
-            if names.len() == 2 {
-                Some((names[0].clone(), names[1].clone()))
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<(String, String)>>();
-    Ok(fights)
+pub async fn get_event(id: usize) -> Result<db::Event, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_event(id)?)
+}
+
+#[server]
+pub async fn get_promotions() -> Result<Vec<String>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_promotions()?)
+}
+
+/// Stores the events in a card file, for promotions ufcstats doesn't cover. Returns how many
+/// events were stored, and what was left out of the file.
+#[server]
+pub async fn import_card_file(text: String) -> Result<(usize, Vec<String>), ServerFnError> {
+    let store = get_store();
+    super::auth::require_admin(&super::auth::authenticate(
+        store,
+        &server_context().request_parts().headers,
+    )?)?;
+    let imported = super::source::import_card_file(store, &text)?;
+    Ok((
+        imported.value.len(),
+        imported
+            .diagnostics
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+    ))
+}
+
+#[server]
+pub async fn get_event_countries() -> Result<Vec<String>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_event_countries()?)
+}
+
+/// How the last parse of each kind of ufcstats page went, so users can tell when the site has
+/// changed under the scraper.
+#[server]
+pub async fn get_parser_health() -> Result<Vec<db::ParserHealth>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_parser_health()?)
+}
+
+fn parse_time_arg(time: &str) -> Result<Option<chrono::NaiveTime>, ServerFnError> {
+    if time.trim().is_empty() {
+        return Ok(None);
+    }
+    db::dates::parse_start_time(time)
+        .map(Some)
+        .map_err(|e| ServerFnError::new(format!("Invalid time {:?}: {}", time, e)))
+}
+
+/// Sets when the event's prelims and main card start and where it's held, for admins only.
+/// ufcstats publishes neither start times nor venues, and the times decide when picks lock and what
+/// the calendar feed shows. Blank clears.
+#[server]
+pub async fn set_event_times(
+    event_id: usize,
+    prelims: String,
+    main: String,
+    timezone: String,
+    venue: String,
+) -> Result<db::Event, ServerFnError> {
+    let store = get_store();
+    super::auth::require_admin(&super::auth::authenticate(
+        store,
+        &server_context().request_parts().headers,
+    )?)?;
+    let timezone = Some(timezone.trim()).filter(|tz| !tz.is_empty());
+    store.set_event_start(event_id, parse_time_arg(&main)?, timezone)?;
+    store.set_prelims_start(event_id, parse_time_arg(&prelims)?)?;
+    store.set_event_venue(event_id, Some(venue.trim()).filter(|v| !v.is_empty()))?;
+    Ok(store.get_event(event_id)?)
+}
+
+/// The event's card as (fighter1, fighter2), read from the page recorded for the event.
+#[server]
+pub async fn get_fights(event_id: usize) -> Result<Vec<(String, String)>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let link = store.get_event(event_id)?.link;
+    let bouts = super::source::EventSource::of_link(&link)
+        .card(store, event_id, &link)
+        .await?;
+    Ok(bouts
+        .into_iter()
+        .map(|bout| (bout.fighter1, bout.fighter2))
+        .collect())
 }
 
 #[server]
@@ -123,82 +126,922 @@ pub async fn predict(
     loser: String,
 ) -> Result<bool, ServerFnError> {
     let store = get_store();
-    match store.add_or_update_prediction(event_id, &winner, &loser) {
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    check_unlocked(store, event_id)?;
+    match store.add_or_update_prediction_for_user(event_id, &winner, &loser, &user.name) {
         Ok(_) => Ok(true),
         Err(e) => Err(e.into()),
     }
 }
 
+/// Saves several (winner, loser) picks at once and un-picks the `removed` bouts, all or none, so a
+/// whole card can be entered without racing one request per bout.
 #[server]
-pub async fn get_predictions(event_id: usize) -> Result<Vec<(String, String)>, ServerFnError> {
+pub async fn predict_many(
+    event_id: usize,
+    picks: Vec<(String, String)>,
+    removed: Vec<(String, String)>,
+) -> Result<usize, ServerFnError> {
     let store = get_store();
-    Ok(store.get_predictions(event_id)?)
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    check_unlocked(store, event_id)?;
+    if let Some((winner, _)) = picks.iter().find(|(winner, loser)| winner == loser) {
+        return Err(ServerFnError::new(format!(
+            "{} can't fight themselves",
+            winner
+        )));
+    }
+    Ok(store.save_predictions_for_user(event_id, &picks, &removed, &user.name)?)
 }
 
+/// Refuses to change picks on an event that has started or has results in.
+pub(super) fn check_unlocked(store: &db::Store, event_id: usize) -> Result<(), ServerFnError> {
+    if store.are_predictions_locked(event_id, chrono::Utc::now())? {
+        return Err(ServerFnError::new(
+            "This event has started, so its picks can't be changed",
+        ));
+    }
+    Ok(())
+}
+
+/// Refuses a pick of a fighter over themselves, or of a bout that isn't on the event's card. Names
+/// are compared under the spelling they'd be stored as.
+pub(super) fn check_pick(
+    store: &db::Store,
+    event_id: usize,
+    winner: &str,
+    loser: &str,
+) -> Result<(), ServerFnError> {
+    if winner.trim() == loser.trim() {
+        return Err(ServerFnError::new(format!(
+            "{} can't fight themselves",
+            winner
+        )));
+    }
+    let (resolved_winner, resolved_loser) = (
+        store.resolve_fighter_name(Some(event_id), winner)?,
+        store.resolve_fighter_name(Some(event_id), loser)?,
+    );
+    if !store
+        .get_bouts(event_id)?
+        .iter()
+        .any(|bout| bout.involves(&resolved_winner) && bout.involves(&resolved_loser))
+    {
+        return Err(ServerFnError::new(format!(
+            "{} vs {} isn't on this card",
+            winner, loser
+        )));
+    }
+    Ok(())
+}
+
+/// Un-picks the bout between the two fighters, until the event locks.
 #[server]
-pub async fn add_result(
+pub async fn delete_prediction(
     event_id: usize,
-    winner: String,
-    loser: String,
+    fighter1: String,
+    fighter2: String,
 ) -> Result<usize, ServerFnError> {
     let store = get_store();
-    match store.add_or_update_result(event_id, &winner, &loser) {
-        Ok(f) => Ok(f),
-        Err(e) => Err(e.into()),
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    check_unlocked(store, event_id)?;
+    Ok(store.delete_prediction_for_user(event_id, &fighter1, &fighter2, &user.name)?)
+}
+
+/// Removes all the caller's picks on the event, until it locks.
+#[server]
+pub async fn clear_predictions(event_id: usize) -> Result<usize, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    check_unlocked(store, event_id)?;
+    Ok(store.clear_predictions_for_user(event_id, &user.name)?)
+}
+
+#[server]
+pub async fn get_predictions(event_id: usize) -> Result<Vec<(String, String)>, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_predictions_for_user(event_id, &user.name)?)
+}
+
+/// The caller's pick sheet for the event, or their results card once results are in, as SVG.
+#[server(endpoint = "pick_card.svg", input = GetUrl, output = Svg)]
+pub async fn get_pick_card_svg(event_id: usize) -> Result<SvgImage, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(SvgImage(super::pick_cards::event_card(
+        store, event_id, &user.name,
+    )?))
+}
+
+/// [`get_pick_card_svg`] as PNG, for chats that don't show SVG.
+#[server(endpoint = "pick_card.png", input = GetUrl, output = Png)]
+pub async fn get_pick_card_png(event_id: usize) -> Result<PngImage, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let svg = super::pick_cards::event_card(store, event_id, &user.name)?;
+    Ok(PngImage(super::pick_cards::render_png(&svg)?))
+}
+
+/// The event's bouts as (winner, loser), or in card order where there's no winner. A finished event's
+/// results are fetched from ufcstats the first time they're asked for.
+#[server]
+pub async fn get_event_results(event_id: usize) -> Result<Vec<(String, String)>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let finished = db::dates::EventTiming::of(store.get_event_date(event_id)?, db::dates::today())
+        == db::dates::EventTiming::Past;
+    if finished
+        && store
+            .get_bouts(event_id)?
+            .iter()
+            .all(|bout| bout.outcome.is_none())
+    {
+        super::backfill::sync_event(store, event_id).await?;
     }
+    Ok(store
+        .get_bouts(event_id)?
+        .into_iter()
+        .map(|bout| match bout.loser().map(str::to_string) {
+            Some(loser) => (bout.winner.unwrap_or_default(), loser),
+            None => (bout.fighter1, bout.fighter2),
+        })
+        .collect())
+}
+
+#[server]
+pub async fn get_total_prediction_correctness() -> Result<(i64, i64), ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let score = store.score(&db::ScoreFilter::for_user(&user.name))?;
+    Ok((score.correct, score.incorrect))
+}
+
+#[server]
+pub async fn get_prediction_correctness_for_event(id: usize) -> Result<(i64, i64), ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let score = store.score(&db::ScoreFilter::for_user(&user.name).event(id))?;
+    Ok((score.correct, score.incorrect))
+}
+
+/// The current user's stats over one promotion's events or all of them.
+#[server]
+pub async fn get_stats(promotion: Option<String>) -> Result<db::Stats, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let filter = db::ScoreFilter {
+        promotion,
+        ..db::ScoreFilter::for_user(&user.name)
+    };
+    Ok(store.stats(&filter)?)
 }
 
 #[server]
-pub async fn get_events_with_predictions(
-) -> Result<Vec<(usize, String, String, String)>, ServerFnError> {
+pub async fn get_event_scores() -> Result<Vec<(usize, db::Score)>, ServerFnError> {
     let store = get_store();
-    Ok(store.get_past_events_with_predictions()?)
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.scores_by_event(&db::ScoreFilter::for_user(&user.name))?)
 }
 
 #[server]
-pub async fn scrape_results(
-    event_link: String,
+pub async fn get_past_events(
+    query: db::PastEventQuery,
+) -> Result<db::PastEventPage, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let query = db::PastEventQuery {
+        user: query.user.or(Some(user.name)),
+        ..query
+    };
+    Ok(store.get_past_events_page(&query)?)
+}
+
+#[server]
+pub async fn get_past_event_years() -> Result<Vec<i32>, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_past_event_years(Some(&user.name))?)
+}
+
+fn parse_date_arg(date: &str) -> Result<chrono::NaiveDate, ServerFnError> {
+    db::dates::parse_event_date(date)
+        .map_err(|e| ServerFnError::new(format!("Invalid date {:?}: {}", date, e)))
+}
+
+/// Starts importing completed events between `from` and `to` in the background; poll
+/// [`get_backfill_progress`] to follow it.
+#[server]
+pub async fn start_backfill(from: String, to: String) -> Result<(), ServerFnError> {
+    let store = get_store();
+    super::auth::require_admin(&super::auth::authenticate(
+        store,
+        &server_context().request_parts().headers,
+    )?)?;
+    let (from, to) = (parse_date_arg(&from)?, parse_date_arg(&to)?);
+    if super::backfill::is_running() {
+        return Err(ServerFnError::new("A backfill is already running"));
+    }
+    tokio::spawn(async move {
+        if let Err(e) = super::backfill::run_backfill(store, from, to).await {
+            log::error!("Backfill failed: {}", e);
+        }
+    });
+    Ok(())
+}
+
+#[server]
+pub async fn get_backfill_progress(
+    from: String,
+    to: String,
+) -> Result<(db::ImportProgress, bool), ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let (from, to) = (parse_date_arg(&from)?, parse_date_arg(&to)?);
+    Ok((
+        store.get_import_progress(from, to)?,
+        super::backfill::is_running(),
+    ))
+}
+
+/// Request counts, retries and failures for each site scraped.
+#[server]
+pub async fn get_http_metrics() -> Result<Vec<db::HttpMetrics>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_http_metrics()?)
+}
+
+/// Fighter name to ufcstats fighter id for everyone on the event's card.
+#[server]
+pub async fn get_fighter_ids(
     event_id: usize,
-) -> Result<Vec<(String, String)>, ServerFnError> {
-    // This function still fetches from the web, but you can use store if you need DB access
-    let response = reqwest::get(&event_link)
-        .await
-        .map_err(|e| ServerFnError::new(format!("Failed to fetch event page: {}", e)))?
-        .text()
-        .await
-        .map_err(|e| ServerFnError::new(format!("Failed to get event page text: {}", e)))?;
-    let doc = scraper::Html::parse_document(&response);
-    let row_selector = scraper::Selector::parse("tr.b-fight-details__table-row").unwrap();
-    let name_selector = scraper::Selector::parse("a.b-link.b-link_style_black").unwrap();
-
-    let fights = doc
-        .select(&row_selector)
-        .filter_map(|row| {
-            let mut names = row
-                .select(&name_selector)
-                .map(|el| el.text().collect::<String>().trim().to_string())
-                .filter(|s| s.clone().trim() != "View Matchup" && !s.is_empty())
-                .collect::<Vec<_>>();
-            if names.len() == 2 {
-                // Ensure the winner is the first element in the tuple
-                Some((names[0].clone(), names[1].clone()))
-            } else {
-                None
-            }
+) -> Result<std::collections::HashMap<String, String>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_event_fighter_ids(event_id)?.into_iter().collect())
+}
+
+#[server]
+pub async fn get_fighter_profile(
+    id: String,
+) -> Result<
+    (
+        db::Fighter,
+        Vec<(db::Event, db::Bout)>,
+        db::FighterPickStats,
+    ),
+    ServerFnError,
+> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let fighter = super::scrape::fetch_fighter(store, &id).await?;
+    let bouts = store.get_fighter_bouts(&fighter)?;
+    let picks = store.get_fighter_pick_stats(&fighter.name, &user.name)?;
+    Ok((fighter, bouts, picks))
+}
+
+/// Both fighters of a bout for a side-by-side comparison, from the fighter cache where it's fresh.
+#[server]
+pub async fn get_matchup(
+    fighter1_id: String,
+    fighter2_id: String,
+) -> Result<(db::Fighter, db::Fighter), ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let fighter1 = super::scrape::fetch_fighter(store, &fighter1_id).await?;
+    let fighter2 = super::scrape::fetch_fighter(store, &fighter2_id).await?;
+    Ok((fighter1, fighter2))
+}
+
+/// The baseline model's win probabilities for the event's card. Nothing is stored; running the
+/// backtests records every model's picks on upcoming events under its pseudo-user.
+#[server]
+pub async fn get_model_picks(
+    event_id: usize,
+) -> Result<Vec<crate::model::ModelPick>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(crate::model::picks(
+        store,
+        event_id,
+        &mut crate::model::EloRatings::default(),
+    )?)
+}
+
+/// Who's favoured in each bout on the card. ufcstats has no betting lines, so it's whoever has the
+/// better record going in.
+#[server]
+pub async fn get_favourites(
+    event_id: usize,
+) -> Result<Vec<crate::model::ModelPick>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(crate::model::picks(
+        store,
+        event_id,
+        &mut crate::model::RecordModel::default(),
+    )?)
+}
+
+/// Every user's overall score, model pseudo-users included, over one promotion's events or all of
+/// them.
+#[server]
+pub async fn get_user_scores(
+    promotion: Option<String>,
+) -> Result<Vec<(String, db::Score)>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let filter = db::ScoreFilter {
+        promotion,
+        ..Default::default()
+    };
+    Ok(store.scores_by_user(&filter)?)
+}
+
+/// Replays every model over all recorded results and stores the reports.
+#[server]
+pub async fn run_backtests() -> Result<Vec<db::BacktestReport>, ServerFnError> {
+    let store = get_store();
+    super::auth::require_admin(&super::auth::authenticate(
+        store,
+        &server_context().request_parts().headers,
+    )?)?;
+    Ok(crate::model::run_backtests(store)?)
+}
+
+/// Each model's latest backtest, with the model's score and ours on the bouts we both picked.
+#[server]
+pub async fn get_backtests(
+) -> Result<Vec<(db::BacktestReport, db::Score, db::Score)>, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    store
+        .get_backtests()?
+        .into_iter()
+        .map(|report| {
+            let model =
+                store.score(&db::ScoreFilter::for_user(&report.model).shared_with(&user.name))?;
+            let ours =
+                store.score(&db::ScoreFilter::for_user(&user.name).shared_with(&report.model))?;
+            Ok((report, model, ours))
         })
-        .collect::<Vec<(String, String)>>();
+        .collect()
+}
 
-    Ok(fights)
+/// Places a ticket for the current user. Every leg has to be a bout on its event's card, which
+/// still takes picks. Each leg is also recorded as a prediction.
+#[server]
+pub async fn create_ticket(stake: f64, legs: Vec<db::TicketLeg>) -> Result<usize, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    if let Some(problem) = db::check_ticket(stake, &legs) {
+        return Err(ServerFnError::new(problem));
+    }
+    for leg in &legs {
+        check_unlocked(store, leg.event_id)?;
+        if !store
+            .get_bouts(leg.event_id)?
+            .iter()
+            .any(|bout| bout.involves(&leg.winner) && bout.involves(&leg.loser))
+        {
+            return Err(ServerFnError::new(format!(
+                "{} vs {} isn't on this card",
+                leg.winner, leg.loser
+            )));
+        }
+    }
+    Ok(store.create_ticket(&user.name, stake, &legs)?)
 }
 
 #[server]
-pub async fn get_total_prediction_correctness() -> Result<(i64, i64), ServerFnError> {
+pub async fn get_tickets() -> Result<Vec<db::Ticket>, ServerFnError> {
     let store = get_store();
-    Ok(store.get_my_predictions_correctness()?)
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_tickets(&user.name)?)
 }
 
 #[server]
-pub async fn get_prediction_correctness_for_event(id: usize) -> Result<(i64, i64), ServerFnError> {
+pub async fn create_contest(name: String, kind: db::ContestKind) -> Result<usize, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    if name.trim().is_empty() {
+        return Err(ServerFnError::new("Give the contest a name"));
+    }
+    Ok(store.create_contest(name.trim(), kind)?)
+}
+
+#[server]
+pub async fn get_contests() -> Result<Vec<db::Contest>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_contests()?)
+}
+
+/// The contest with its standings, leaders first.
+#[server]
+pub async fn get_contest(id: usize) -> Result<(db::Contest, Vec<db::Standing>), ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let contest = store
+        .get_contest(id)?
+        .ok_or_else(|| ServerFnError::new("No such contest"))?;
+    let standings = store.get_standings(&contest)?;
+    Ok((contest, standings))
+}
+
+/// The current user's picks in the contest, across every event.
+#[server]
+pub async fn get_my_contest_picks(
+    contest_id: usize,
+) -> Result<Vec<db::ContestPick>, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_user_contest_picks(contest_id, &user.name)?)
+}
+
+#[server]
+pub async fn make_survivor_pick(
+    contest_id: usize,
+    event_id: usize,
+    fighter: String,
+) -> Result<(), ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let pick = super::contests::survivor_pick(
+        store,
+        contest_id,
+        &user.name,
+        event_id,
+        &fighter,
+        chrono::Utc::now(),
+    )?;
+    Ok(store.set_contest_picks(contest_id, &user.name, event_id, &[pick])?)
+}
+
+/// Replaces the current user's confidence picks for the event, given as (winner, loser, confidence).
+#[server]
+pub async fn set_confidence_picks(
+    contest_id: usize,
+    event_id: usize,
+    picks: Vec<(String, String, u32)>,
+) -> Result<(), ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let picks = super::contests::confidence_picks(
+        store,
+        contest_id,
+        &user.name,
+        event_id,
+        &picks,
+        chrono::Utc::now(),
+    )?;
+    Ok(store.set_contest_picks(contest_id, &user.name, event_id, &picks)?)
+}
+
+/// Creates an account and signs it in. The first account is the admin and takes over the picks made
+/// before accounts existed.
+#[server]
+pub async fn register(name: String, password: String) -> Result<db::User, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::register(store, name.trim(), &password)?;
+    let secure = super::auth::is_secure(&server_context().request_parts());
+    let cookie = super::auth::start_session(store, &user, secure)?;
+    server_context()
+        .response_parts_mut()
+        .headers
+        .append(http::header::SET_COOKIE, cookie.parse()?);
+    Ok(user)
+}
+
+#[server]
+pub async fn login(name: String, password: String) -> Result<db::User, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::login(store, name.trim(), &password)?;
+    let secure = super::auth::is_secure(&server_context().request_parts());
+    let cookie = super::auth::start_session(store, &user, secure)?;
+    server_context()
+        .response_parts_mut()
+        .headers
+        .append(http::header::SET_COOKIE, cookie.parse()?);
+    Ok(user)
+}
+
+#[server]
+pub async fn logout() -> Result<(), ServerFnError> {
+    let store = get_store();
+    let parts = server_context().request_parts().clone();
+    if let Some(token) = super::auth::request_token(&parts.headers) {
+        store.delete_session(&super::auth::hash_token(&token))?;
+    }
+    server_context().response_parts_mut().headers.append(
+        http::header::SET_COOKIE,
+        super::auth::end_session_cookie(super::auth::is_secure(&parts)).parse()?,
+    );
+    Ok(())
+}
+
+/// The signed-in user, or `None` when the request isn't signed in.
+#[server]
+pub async fn get_current_user() -> Result<Option<db::User>, ServerFnError> {
+    let store = get_store();
+    Ok(super::auth::authenticate(store, &server_context().request_parts().headers).ok())
+}
+
+/// Creates an API token for scripts. The token is only ever returned here.
+#[server]
+pub async fn create_api_token(label: String) -> Result<String, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    // before the first account exists requests act as the default user, which has no id
+    if user.id == 0 {
+        return Err(ServerFnError::new("Register an account first"));
+    }
+    let token = super::auth::new_token();
+    store.create_token(
+        user.id,
+        db::TokenKind::Api,
+        &super::auth::hash_token(&token),
+        label.trim(),
+        None,
+    )?;
+    Ok(token)
+}
+
+#[server]
+pub async fn get_api_tokens() -> Result<Vec<db::ApiToken>, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_api_tokens(user.id)?)
+}
+
+#[server]
+pub async fn revoke_api_token(id: usize) -> Result<(), ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    if store.delete_api_token(user.id, id)? == 0 {
+        return Err(ServerFnError::new("No such token"));
+    }
+    Ok(())
+}
+
+/// Records the corrected outcome of a bout on the event's card, a win, draw or no contest, with the
+/// reason, for admins only. The bout is found by its fighters, and needn't have a result yet. The
+/// correction survives later syncs from ufcstats.
+#[server]
+pub async fn override_result(result: db::Bout, reason: String) -> Result<usize, ServerFnError> {
+    let store = get_store();
+    let admin = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    super::auth::require_admin(&admin)?;
+    if reason.trim().is_empty() {
+        return Err(ServerFnError::new("Give a reason for the correction"));
+    }
+    let bout = bout_on_card(store, &result)?;
+    let Some(outcome) = result.outcome else {
+        return Err(ServerFnError::new("Say how the bout ended"));
+    };
+    let winner = checked_winner(&bout, outcome, result.winner)?;
+    Ok(store.override_result(
+        &db::Bout {
+            outcome: Some(outcome),
+            winner,
+            ..bout
+        },
+        &admin.name,
+        reason.trim(),
+    )?)
+}
+
+/// The bout on the event's card between `result`'s two fighters, in either order.
+fn bout_on_card(store: &db::Store, result: &db::Bout) -> Result<db::Bout, ServerFnError> {
+    store
+        .get_bouts(result.event_id)?
+        .into_iter()
+        .find(|bout| bout.involves(&result.fighter1) && bout.involves(&result.fighter2))
+        .ok_or_else(|| {
+            ServerFnError::new(format!(
+                "{} vs {} isn't on this card",
+                result.fighter1, result.fighter2
+            ))
+        })
+}
+
+/// The winner of a bout that ended in `outcome`: a win needs one of the bout's fighters, and only
+/// a win has one.
+fn checked_winner(
+    bout: &db::Bout,
+    outcome: db::BoutOutcome,
+    winner: Option<String>,
+) -> Result<Option<String>, ServerFnError> {
+    let winner = winner.filter(|_| outcome == db::BoutOutcome::Win);
+    if (outcome == db::BoutOutcome::Win) != winner.as_deref().is_some_and(|w| bout.involves(w)) {
+        return Err(ServerFnError::new(
+            "A win needs a winner from the bout, and only a win has one",
+        ));
+    }
+    Ok(winner)
+}
+
+/// Fetches the event's results from ufcstats again, for admins only. Corrected bouts keep their
+/// corrections.
+#[server]
+pub async fn resync_event_results(event_id: usize) -> Result<(), ServerFnError> {
+    let store = get_store();
+    super::auth::require_admin(&super::auth::authenticate(
+        store,
+        &server_context().request_parts().headers,
+    )?)?;
+    super::backfill::sync_event(store, event_id).await
+}
+
+#[server]
+pub async fn get_result_overrides(
+    event_id: usize,
+) -> Result<Vec<db::ResultOverride>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_result_overrides(event_id)?)
+}
+
+fn manual_event(store: &db::Store, event_id: usize) -> Result<db::Event, ServerFnError> {
+    let event = store.get_event(event_id)?;
+    if event.link != db::MANUAL_LINK {
+        return Err(ServerFnError::new(
+            "Only events entered by hand can be edited",
+        ));
+    }
+    Ok(event)
+}
+
+/// The event's card as stored, with each bout's result.
+#[server]
+pub async fn get_bouts(event_id: usize) -> Result<Vec<db::Bout>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_bouts(event_id)?)
+}
+
+/// Creates an event by hand, for admins only, for a card that isn't on ufcstats or in a card file
+/// yet.
+#[server]
+pub async fn create_manual_event(
+    name: String,
+    date: String,
+    promotion: String,
+) -> Result<db::Event, ServerFnError> {
+    let store = get_store();
+    super::auth::require_admin(&super::auth::authenticate(
+        store,
+        &server_context().request_parts().headers,
+    )?)?;
+    if name.trim().is_empty() {
+        return Err(ServerFnError::new("Give the event a name"));
+    }
+    db::dates::parse_event_date(&date)
+        .map_err(|_| ServerFnError::new(format!("Unreadable date {:?}", date)))?;
+    if store.find_event(name.trim(), &date)?.is_some() {
+        return Err(ServerFnError::new(format!(
+            "{} on {} already exists",
+            name.trim(),
+            date
+        )));
+    }
+    let promotion = Some(promotion.trim())
+        .filter(|p| !p.is_empty())
+        .unwrap_or(db::DEFAULT_PROMOTION);
+    let id = store.add_manual_event(&name, &date, promotion)?;
+    Ok(store.get_event(id)?)
+}
+
+/// Adds a bout to the bottom of a hand-entered card, for admins only.
+#[server]
+pub async fn add_manual_bout(
+    event_id: usize,
+    fighter1: String,
+    fighter2: String,
+    weight_class: String,
+) -> Result<Vec<db::Bout>, ServerFnError> {
+    let store = get_store();
+    super::auth::require_admin(&super::auth::authenticate(
+        store,
+        &server_context().request_parts().headers,
+    )?)?;
+    manual_event(store, event_id)?;
+    let (fighter1, fighter2) = (fighter1.trim(), fighter2.trim());
+    if fighter1.is_empty() || fighter2.is_empty() || fighter1 == fighter2 {
+        return Err(ServerFnError::new("A bout needs two fighters"));
+    }
+    if store
+        .get_bouts(event_id)?
+        .iter()
+        .any(|bout| bout.involves(fighter1) || bout.involves(fighter2))
+    {
+        return Err(ServerFnError::new(format!(
+            "{} or {} is already on this card",
+            fighter1, fighter2
+        )));
+    }
+    let weight_class = Some(weight_class.trim()).filter(|w| !w.is_empty());
+    store.add_bout(event_id, fighter1, fighter2, weight_class)?;
+    Ok(store.get_bouts(event_id)?)
+}
+
+/// Takes a bout off a hand-entered card, for admins only. Users who picked it are told, as they
+/// are when a bout drops off a scraped card.
+#[server]
+pub async fn remove_manual_bout(
+    event_id: usize,
+    fighter1: String,
+    fighter2: String,
+) -> Result<Vec<db::Bout>, ServerFnError> {
+    let store = get_store();
+    super::auth::require_admin(&super::auth::authenticate(
+        store,
+        &server_context().request_parts().headers,
+    )?)?;
+    manual_event(store, event_id)?;
+    store.remove_bout(event_id, &fighter1, &fighter2)?;
+    let card = store.get_bouts(event_id)?;
+    crate::notify::queue_card_changes(store, event_id, &card)?;
+    Ok(card)
+}
+
+/// Moves a bout on a hand-entered card from one position to another, for admins only.
+#[server]
+pub async fn move_manual_bout(
+    event_id: usize,
+    from: usize,
+    to: usize,
+) -> Result<Vec<db::Bout>, ServerFnError> {
     let store = get_store();
-    Ok(store.get_my_predictions_correctness_for_event(id)?)
+    super::auth::require_admin(&super::auth::authenticate(
+        store,
+        &server_context().request_parts().headers,
+    )?)?;
+    manual_event(store, event_id)?;
+    store.move_bout(event_id, from, to)?;
+    Ok(store.get_bouts(event_id)?)
+}
+
+/// Enters the result of a bout on a hand-entered card, for admins only; the bout is found by its
+/// fighters, and no outcome takes the result back off. Tickets settled on an earlier result are
+/// settled again.
+#[server]
+pub async fn set_manual_result(result: db::Bout) -> Result<Vec<db::Bout>, ServerFnError> {
+    let store = get_store();
+    super::auth::require_admin(&super::auth::authenticate(
+        store,
+        &server_context().request_parts().headers,
+    )?)?;
+    let event_id = result.event_id;
+    manual_event(store, event_id)?;
+    let bout = bout_on_card(store, &result)?;
+    let winner = match result.outcome {
+        Some(outcome) => checked_winner(&bout, outcome, result.winner)?,
+        None => None,
+    };
+    store.clear_bout_result(event_id, &bout.fighter1, &bout.fighter2)?;
+    let Some(outcome) = result.outcome else {
+        return Ok(store.get_bouts(event_id)?);
+    };
+    let text = |raw: Option<String>| {
+        raw.map(|raw| raw.trim().to_string())
+            .filter(|raw| !raw.is_empty())
+    };
+    super::backfill::record_event(
+        store,
+        event_id,
+        &[db::Bout {
+            outcome: Some(outcome),
+            winner,
+            method: text(result.method),
+            round: result.round,
+            time: text(result.time),
+            ..bout
+        }],
+    )?;
+    Ok(store.get_bouts(event_id)?)
+}
+
+/// Scraped events that could be the hand-entered one: held within a day of it, with at least one
+/// of its bouts on their card. Best match first.
+#[server]
+pub async fn get_reconcile_candidates(
+    event_id: usize,
+) -> Result<Vec<(db::Event, db::Reconciliation)>, ServerFnError> {
+    let store = get_store();
+    super::auth::require_admin(&super::auth::authenticate(
+        store,
+        &server_context().request_parts().headers,
+    )?)?;
+    let event = manual_event(store, event_id)?;
+    let date =
+        db::dates::parse_event_date(&event.date).map_err(|e| ServerFnError::new(e.to_string()))?;
+    let day = |date: chrono::NaiveDate| date.format(db::dates::ISO_DATE).to_string();
+    let manual = store.get_bouts(event_id)?;
+    let mut candidates = Vec::new();
+    for scraped in store.get_events(
+        Some(&day(date - chrono::Days::new(1))),
+        Some(&day(date + chrono::Days::new(1))),
+    )? {
+        let source = super::source::EventSource::of_link(&scraped.link);
+        if source != super::source::EventSource::Ufcstats {
+            continue;
+        }
+        let card = source.card(store, scraped.id, &scraped.link).await?;
+        let reconciliation = db::match_bouts(&manual, &card);
+        if !reconciliation.matched.is_empty() {
+            candidates.push((scraped, reconciliation));
+        }
+    }
+    candidates.sort_by_key(|(_, reconciliation)| std::cmp::Reverse(reconciliation.matched.len()));
+    Ok(candidates)
+}
+
+/// Folds a hand-entered event into the scraped event that replaced it, for admins only, moving
+/// its picks, tickets and results across. Returns the scraped event.
+#[server]
+pub async fn reconcile_manual_event(
+    manual_id: usize,
+    scraped_id: usize,
+) -> Result<db::Event, ServerFnError> {
+    let store = get_store();
+    super::auth::require_admin(&super::auth::authenticate(
+        store,
+        &server_context().request_parts().headers,
+    )?)?;
+    manual_event(store, manual_id)?;
+    let scraped = store.get_event(scraped_id)?;
+    if scraped.link == db::MANUAL_LINK {
+        return Err(ServerFnError::new(
+            "Merge into the scraped event, not another one entered by hand",
+        ));
+    }
+    store.reconcile_event(manual_id, scraped_id)?;
+    store.settle_tickets(scraped_id)?;
+    crate::notify::queue_results(store, scraped_id, db::dates::today())?;
+    Ok(scraped)
+}
+
+#[server]
+pub async fn get_fighter_aliases() -> Result<Vec<db::FighterAlias>, ServerFnError> {
+    let store = get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_fighter_aliases()?)
+}
+
+/// Pairs of stored names that look like the same fighter, for admins to merge.
+#[server]
+pub async fn suggest_fighter_merges() -> Result<Vec<(String, String)>, ServerFnError> {
+    let store = get_store();
+    super::auth::require_admin(&super::auth::authenticate(
+        store,
+        &server_context().request_parts().headers,
+    )?)?;
+    Ok(store.suggest_fighter_merges()?)
+}
+
+/// Merges one spelling of a fighter's name into another, for admins only, so results and picks
+/// under either are scored together. Returns how many rows were rewritten.
+#[server]
+pub async fn merge_fighter_names(from: String, to: String) -> Result<usize, ServerFnError> {
+    let store = get_store();
+    super::auth::require_admin(&super::auth::authenticate(
+        store,
+        &server_context().request_parts().headers,
+    )?)?;
+    if from.trim().is_empty() || to.trim().is_empty() || from.trim() == to.trim() {
+        return Err(ServerFnError::new("Give two different spellings to merge"));
+    }
+    Ok(store.merge_fighter_names(&from, &to)?)
+}
+
+#[server]
+pub async fn get_notification_prefs() -> Result<db::NotificationPrefs, ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_notification_prefs(&user.name)?)
+}
+
+/// Saves the current user's notification preferences. Blank addresses turn that sink off.
+#[server]
+pub async fn set_notification_prefs(prefs: db::NotificationPrefs) -> Result<(), ServerFnError> {
+    let store = get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let blank_to_none = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let prefs = db::NotificationPrefs {
+        user: user.name,
+        webhook_url: blank_to_none(prefs.webhook_url),
+        email: blank_to_none(prefs.email),
+        ..prefs
+    };
+    if let Some(problem) = db::check_notification_prefs(&prefs) {
+        return Err(ServerFnError::new(problem));
+    }
+    store.set_notification_prefs(&prefs)?;
+    Ok(())
 }
@@ -0,0 +1,276 @@
+//! Accounts, sign-in and the guard every server function goes through.
+//!
+//! The web UI signs in with a session cookie; scripts send an API token as
+//! `Authorization: Bearer <token>`. Only hashes of either are stored. Until the first account is
+//! registered the server runs open, with every request acting as the default user.
+
+use crate::db::{self, TokenKind, User};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use dioxus::prelude::ServerFnError;
+use http::header::{AUTHORIZATION, COOKIE, FORWARDED};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+pub const SESSION_COOKIE: &str = "ufc_session";
+pub const SESSION_DAYS: i64 = 30;
+const MIN_PASSWORD_LEN: usize = 8;
+
+pub fn hash_password(password: &str) -> Result<String, ServerFnError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| ServerFnError::new(format!("Failed to hash password: {}", e)))
+}
+
+pub fn verify_password(hash: &str, password: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+/// A new random token, as handed to the client.
+pub fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    to_hex(&bytes)
+}
+
+/// What's stored for a token. Tokens are random enough that a plain digest is safe.
+pub fn hash_token(token: &str) -> String {
+    to_hex(&Sha256::digest(token.as_bytes()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// What's wrong with a new account's name and password, if anything. Names can't clash with the
+/// default user or the models' `model:` pseudo-users.
+pub fn check_account(name: &str, password: &str) -> Option<String> {
+    if !(3..=32).contains(&name.chars().count()) {
+        return Some("Names are 3 to 32 characters long".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    {
+        return Some("Names can only use letters, digits, '_' and '-'".to_string());
+    }
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Some(format!(
+            "Passwords need at least {} characters",
+            MIN_PASSWORD_LEN
+        ));
+    }
+    None
+}
+
+pub fn register(store: &db::Store, name: &str, password: &str) -> Result<User, ServerFnError> {
+    if let Some(problem) = check_account(name, password) {
+        return Err(ServerFnError::new(problem));
+    }
+    if store.get_user_credentials(name)?.is_some() {
+        return Err(ServerFnError::new(format!("{} is taken", name)));
+    }
+    Ok(store.create_user(name, &hash_password(password)?)?)
+}
+
+pub fn login(store: &db::Store, name: &str, password: &str) -> Result<User, ServerFnError> {
+    match store.get_user_credentials(name)? {
+        Some((user, hash)) if verify_password(&hash, password) => Ok(user),
+        _ => Err(ServerFnError::new("Wrong name or password")),
+    }
+}
+
+/// Whether the request came over HTTPS, directly or through a proxy that says so, or the server is
+/// configured to always treat it that way with `UFC_SECURE_COOKIES`. Session cookies are then
+/// marked `Secure`.
+pub fn is_secure(parts: &http::request::Parts) -> bool {
+    let forced = std::env::var("UFC_SECURE_COOKIES")
+        .is_ok_and(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"));
+    let header = |name| {
+        parts
+            .headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_lowercase)
+    };
+    forced
+        || parts.uri.scheme() == Some(&http::uri::Scheme::HTTPS)
+        || header("x-forwarded-proto").is_some_and(|proto| proto.trim() == "https")
+        || header(FORWARDED.as_str()).is_some_and(|forwarded| forwarded.contains("proto=https"))
+}
+
+fn cookie_attributes(secure: bool) -> &'static str {
+    if secure {
+        "Path=/; HttpOnly; SameSite=Lax; Secure"
+    } else {
+        "Path=/; HttpOnly; SameSite=Lax"
+    }
+}
+
+/// Starts a session for the user, returning the `Set-Cookie` header value that carries it.
+pub fn start_session(
+    store: &db::Store,
+    user: &User,
+    secure: bool,
+) -> Result<String, ServerFnError> {
+    let token = new_token();
+    store.create_token(
+        user.id,
+        TokenKind::Session,
+        &hash_token(&token),
+        "",
+        Some(SESSION_DAYS),
+    )?;
+    Ok(format!(
+        "{}={}; {}; Max-Age={}",
+        SESSION_COOKIE,
+        token,
+        cookie_attributes(secure),
+        SESSION_DAYS * 24 * 60 * 60
+    ))
+}
+
+/// The `Set-Cookie` header value that clears the session cookie.
+pub fn end_session_cookie(secure: bool) -> String {
+    format!(
+        "{}=; {}; Max-Age=0",
+        SESSION_COOKIE,
+        cookie_attributes(secure)
+    )
+}
+
+/// The token a request carries: a bearer token if there is one, otherwise the session cookie.
+pub fn request_token(headers: &http::HeaderMap) -> Option<String> {
+    let bearer = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string());
+    bearer.or_else(|| {
+        headers
+            .get_all(COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(';'))
+            .filter_map(|cookie| cookie.trim().split_once('='))
+            .find(|(name, _)| *name == SESSION_COOKIE)
+            .map(|(_, token)| token.to_string())
+    })
+}
+
+/// The user making the request. Fails unless the request carries a valid token, except while no
+/// account exists yet.
+pub fn authenticate(store: &db::Store, headers: &http::HeaderMap) -> Result<User, ServerFnError> {
+    authenticate_token(store, request_token(headers))
+}
+
+/// The user `token` belongs to, for requests that carry it somewhere other than the headers, such
+/// as calendar apps that can only be given a URL.
+pub fn authenticate_token(store: &db::Store, token: Option<String>) -> Result<User, ServerFnError> {
+    if store.count_users()? == 0 {
+        return Ok(User {
+            name: db::DEFAULT_USER.to_string(),
+            is_admin: true,
+            ..Default::default()
+        });
+    }
+    match token {
+        Some(token) => store
+            .get_user_by_token(&hash_token(&token))?
+            .ok_or_else(|| ServerFnError::new("Your session has expired, sign in again")),
+        None => Err(ServerFnError::new("Sign in first")),
+    }
+}
+
+pub fn require_admin(user: &User) -> Result<(), ServerFnError> {
+    if user.is_admin {
+        Ok(())
+    } else {
+        Err(ServerFnError::new("Only an admin can do that"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    #[test]
+    fn passwords_verify_against_their_hash_only() {
+        let hash = hash_password("correct horse").unwrap();
+        assert!(verify_password(&hash, "correct horse"));
+        assert!(!verify_password(&hash, "wrong horse"));
+        assert!(!verify_password("not a hash", "correct horse"));
+    }
+
+    #[test]
+    fn tokens_come_from_bearer_header_or_cookie() {
+        let mut headers = http::HeaderMap::new();
+        assert_eq!(request_token(&headers), None);
+        headers.insert(
+            COOKIE,
+            HeaderValue::from_static("theme=dark; ufc_session=abc"),
+        );
+        assert_eq!(request_token(&headers).as_deref(), Some("abc"));
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer xyz"));
+        assert_eq!(request_token(&headers).as_deref(), Some("xyz"));
+    }
+
+    #[test]
+    fn requests_need_a_token_once_an_account_exists() {
+        let store = db::Store::new(":memory:");
+        store.create_tables().unwrap();
+        let headers = http::HeaderMap::new();
+        assert_eq!(
+            authenticate(&store, &headers).unwrap().name,
+            db::DEFAULT_USER
+        );
+
+        assert!(register(&store, "me", "long enough").is_err());
+        assert!(register(&store, "model:elo", "long enough").is_err());
+        assert!(register(&store, "alice", "short").is_err());
+        let alice = register(&store, "alice", "long enough").unwrap();
+        assert!(register(&store, "alice", "long enough").is_err());
+        assert!(authenticate(&store, &headers).is_err());
+
+        assert!(login(&store, "alice", "wrong password").is_err());
+        let user = login(&store, "alice", "long enough").unwrap();
+        let cookie = start_session(&store, &user, false).unwrap();
+        assert!(!cookie.contains("Secure"));
+        let mut headers = http::HeaderMap::new();
+        headers.insert(
+            COOKIE,
+            HeaderValue::from_str(cookie.split(';').next().unwrap()).unwrap(),
+        );
+        assert_eq!(authenticate(&store, &headers).unwrap(), alice);
+        assert!(require_admin(&alice).is_ok());
+
+        let bob = register(&store, "bob", "long enough").unwrap();
+        assert!(require_admin(&bob).is_err());
+    }
+
+    #[test]
+    fn cookies_are_secure_over_https() {
+        let parts = |uri: &str, proto: Option<&str>| {
+            let mut request = http::Request::builder().uri(uri);
+            if let Some(proto) = proto {
+                request = request.header("X-Forwarded-Proto", proto);
+            }
+            request.body(()).unwrap().into_parts().0
+        };
+        assert!(!is_secure(&parts("/api/login", None)));
+        assert!(is_secure(&parts("/api/login", Some("https"))));
+        assert!(is_secure(&parts(
+            "https://picks.example.com/api/login",
+            None
+        )));
+        assert!(end_session_cookie(true).ends_with("Secure; Max-Age=0"));
+    }
+}
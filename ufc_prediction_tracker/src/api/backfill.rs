@@ -0,0 +1,119 @@
+use super::scrape;
+use crate::db;
+use chrono::NaiveDate;
+use dioxus::prelude::ServerFnError;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+pub fn is_running() -> bool {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+/// Imports every completed event between `from` and `to` (inclusive) with its bouts and results.
+///
+/// Events are queued in the store before any are fetched, and each is marked done as soon as it's
+/// stored, so calling this again after an interruption only fetches what's left. Failed events are
+/// retried on the next run. Requests go through the shared rate limit, so a long backfill doesn't
+/// hammer ufcstats.
+pub async fn run_backfill(
+    store: &db::Store,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<db::ImportProgress, ServerFnError> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(ServerFnError::new("A backfill is already running"));
+    }
+    let _running = Running;
+    backfill(store, from, to).await
+}
+
+// clears RUNNING when the backfill ends, however it ends, so a panic doesn't block later runs
+struct Running;
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+async fn backfill(
+    store: &db::Store,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<db::ImportProgress, ServerFnError> {
+    let listed = scrape::fetch_event_listing(store, scrape::COMPLETED_EVENTS_URL)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to fetch completed events: {}", e)))?;
+    // events recorded before locations were scraped pick theirs up from the listing
+    if store.count_past_events_missing_location()? > 0 {
+        scrape::update_known_event_locations(store, &listed)?;
+    }
+    for event in listed.iter().filter(|event| {
+        db::dates::parse_event_date(&event.date).is_ok_and(|date| from <= date && date <= to)
+    }) {
+        let id = store.add_event(&event.name, &event.date, &event.link)?;
+        if !event.location.is_empty() {
+            store.set_event_location(id, &event.location)?;
+        }
+        store.queue_import(&event.link, id)?;
+    }
+
+    for (event_id, link) in store.get_unfinished_imports(from, to)? {
+        match scrape::fetch_event_bouts(store, &link, event_id)
+            .await
+            .map(|parsed| parsed.value)
+        {
+            Ok(bouts) if bouts.is_empty() => {
+                store.mark_import_failed(&link, "No bouts found on event page")?;
+            }
+            Ok(bouts) => {
+                record_event(store, event_id, &bouts)?;
+                store.mark_import_done(&link)?;
+            }
+            Err(e) => {
+                log::warn!("Failed to import {}: {}", link, e);
+                store.mark_import_failed(&link, &e.to_string())?;
+            }
+        }
+    }
+    Ok(store.get_import_progress(from, to)?)
+}
+
+/// Fetches one event's card from the ufcstats page recorded for it and stores its results. The page
+/// is never taken from the client, so results only ever come from ufcstats or an admin override.
+/// Card file events get their results from their card file instead.
+pub async fn sync_event(store: &db::Store, event_id: usize) -> Result<(), ServerFnError> {
+    let event = store.get_event(event_id)?;
+    match super::source::EventSource::of_link(&event.link) {
+        super::source::EventSource::Ufcstats => {}
+        super::source::EventSource::CardFile => {
+            return Err(ServerFnError::new(
+                "This event came from a card file; import the file again to update its results",
+            ))
+        }
+        super::source::EventSource::Manual => {
+            return Err(ServerFnError::new(
+                "This event was entered by hand; enter its results on its card",
+            ))
+        }
+    }
+    let parsed = scrape::fetch_event_bouts(store, &event.link, event_id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to fetch event page: {}", e)))?;
+    Ok(record_event(store, event_id, &parsed.value)?)
+}
+
+/// Stores the bouts along with their results, then settles tickets and lets users know how they did.
+pub(super) fn record_event(
+    store: &db::Store,
+    event_id: usize,
+    bouts: &[db::Bout],
+) -> rusqlite::Result<()> {
+    for bout in bouts {
+        store.record_bout(bout)?;
+    }
+    store.settle_tickets(event_id)?;
+    crate::notify::queue_results(store, event_id, db::dates::today())?;
+    Ok(())
+}
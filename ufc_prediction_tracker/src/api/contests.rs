@@ -0,0 +1,244 @@
+//! Contest rules, checked before any contest pick is stored.
+
+use crate::db::{self, ContestKind, ContestPick};
+use chrono::{DateTime, Utc};
+use dioxus::prelude::ServerFnError;
+
+fn contest_of_kind(
+    store: &db::Store,
+    contest_id: usize,
+    kind: ContestKind,
+) -> Result<db::Contest, ServerFnError> {
+    match store.get_contest(contest_id)? {
+        Some(contest) if contest.kind == kind => Ok(contest),
+        Some(contest) => Err(ServerFnError::new(format!(
+            "{} is a {} pool",
+            contest.name,
+            contest.kind.as_str()
+        ))),
+        None => Err(ServerFnError::new("No such contest")),
+    }
+}
+
+/// The event's card, as long as the event still takes picks: it hasn't started by `now` and has no
+/// results in.
+fn open_card(
+    store: &db::Store,
+    event_id: usize,
+    now: DateTime<Utc>,
+) -> Result<Vec<db::Bout>, ServerFnError> {
+    if store.are_predictions_locked(event_id, now)? {
+        return Err(ServerFnError::new("Picks for this event are closed"));
+    }
+    let card = store.get_bouts(event_id)?;
+    if card.is_empty() {
+        return Err(ServerFnError::new(
+            "This event's card hasn't been loaded yet",
+        ));
+    }
+    Ok(card)
+}
+
+/// Checks a survivor pick: the fighter is on the event's card, the user is still alive, and they
+/// haven't used the fighter for another event. Re-picking the same event replaces the earlier pick.
+pub fn survivor_pick(
+    store: &db::Store,
+    contest_id: usize,
+    user: &str,
+    event_id: usize,
+    fighter: &str,
+    now: DateTime<Utc>,
+) -> Result<ContestPick, ServerFnError> {
+    contest_of_kind(store, contest_id, ContestKind::Survivor)?;
+    let card = open_card(store, event_id, now)?;
+    let bout = card
+        .iter()
+        .find(|bout| bout.involves(fighter))
+        .ok_or_else(|| ServerFnError::new(format!("{} isn't on this card", fighter)))?;
+
+    let picks = store.get_contest_picks(contest_id, Some(user))?;
+    if db::standings(ContestKind::Survivor, &picks)
+        .first()
+        .is_some_and(|standing| standing.eliminated_at.is_some())
+    {
+        return Err(ServerFnError::new("You've been knocked out of this pool"));
+    }
+    if picks
+        .iter()
+        .any(|(pick, _)| pick.event_id != event_id && pick.winner == fighter)
+    {
+        return Err(ServerFnError::new(format!(
+            "You've already used {} in this pool",
+            fighter
+        )));
+    }
+
+    let loser = if bout.fighter1 == fighter {
+        &bout.fighter2
+    } else {
+        &bout.fighter1
+    };
+    Ok(ContestPick {
+        event_id,
+        user: user.to_string(),
+        winner: fighter.to_string(),
+        loser: loser.clone(),
+        confidence: None,
+    })
+}
+
+/// Checks a card of confidence picks, given as (winner, loser, confidence): every pick is a bout on
+/// the card, picked once, with a confidence from 1 to the number of bouts that no other pick uses.
+pub fn confidence_picks(
+    store: &db::Store,
+    contest_id: usize,
+    user: &str,
+    event_id: usize,
+    picks: &[(String, String, u32)],
+    now: DateTime<Utc>,
+) -> Result<Vec<ContestPick>, ServerFnError> {
+    contest_of_kind(store, contest_id, ContestKind::Confidence)?;
+    let card = open_card(store, event_id, now)?;
+
+    let mut checked = Vec::<ContestPick>::new();
+    for (winner, loser, confidence) in picks {
+        if !card
+            .iter()
+            .any(|bout| bout.involves(winner) && bout.involves(loser) && winner != loser)
+        {
+            return Err(ServerFnError::new(format!(
+                "{} vs {} isn't on this card",
+                winner, loser
+            )));
+        }
+        if !(1..=card.len() as u32).contains(confidence) {
+            return Err(ServerFnError::new(format!(
+                "Confidence must be between 1 and {}",
+                card.len()
+            )));
+        }
+        if checked
+            .iter()
+            .any(|pick| pick.confidence == Some(*confidence))
+        {
+            return Err(ServerFnError::new(format!(
+                "Confidence {} is used more than once",
+                confidence
+            )));
+        }
+        if checked
+            .iter()
+            .any(|pick| pick.winner == *winner || pick.winner == *loser)
+        {
+            return Err(ServerFnError::new(format!(
+                "{} vs {} is picked more than once",
+                winner, loser
+            )));
+        }
+        checked.push(ContestPick {
+            event_id,
+            user: user.to_string(),
+            winner: winner.clone(),
+            loser: loser.clone(),
+            confidence: Some(*confidence),
+        });
+    }
+    Ok(checked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Bout, Store, DEFAULT_USER};
+
+    fn at(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn store_with_cards() -> (Store, DateTime<Utc>, usize, usize) {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let first = store.add_event("UFC 1", "2025-01-01", "").unwrap();
+        let second = store.add_event("UFC 2", "2025-02-01", "").unwrap();
+        for event_id in [first, second] {
+            store
+                .set_event_start(
+                    event_id,
+                    db::dates::parse_start_time("20:00").ok(),
+                    Some("UTC"),
+                )
+                .unwrap();
+            for (fighter1, fighter2) in [("A", "B"), ("C", "D")] {
+                store
+                    .upsert_bout(&Bout {
+                        event_id,
+                        fighter1: fighter1.to_string(),
+                        fighter2: fighter2.to_string(),
+                        ..Default::default()
+                    })
+                    .unwrap();
+            }
+        }
+        // fight day, before the first card starts
+        (store, at("2025-01-01T12:00:00Z"), first, second)
+    }
+
+    #[test]
+    fn survivor_rules() {
+        let (store, now, first, second) = store_with_cards();
+        let contest = store
+            .create_contest("Survivor", ContestKind::Survivor)
+            .unwrap();
+
+        assert!(survivor_pick(&store, contest, DEFAULT_USER, first, "Z", now).is_err());
+        let pick = survivor_pick(&store, contest, DEFAULT_USER, first, "A", now).unwrap();
+        assert_eq!(pick.loser, "B");
+        store
+            .set_contest_picks(contest, DEFAULT_USER, first, &[pick])
+            .unwrap();
+        // changing this event's pick is fine, reusing A on the next event isn't
+        assert!(survivor_pick(&store, contest, DEFAULT_USER, first, "C", now).is_ok());
+        assert!(survivor_pick(&store, contest, DEFAULT_USER, second, "A", now).is_err());
+
+        store.add_or_update_result(first, "B", "A").unwrap();
+        assert!(survivor_pick(&store, contest, DEFAULT_USER, second, "C", now).is_err());
+        // closed once the card has started, before any result is in
+        assert!(survivor_pick(
+            &store,
+            contest,
+            "friend",
+            second,
+            "A",
+            at("2025-02-01T20:30:00Z")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn confidence_rules() {
+        let (store, now, first, _) = store_with_cards();
+        let contest = store
+            .create_contest("Confidence", ContestKind::Confidence)
+            .unwrap();
+        let pick = |winner: &str, loser: &str, confidence| {
+            (winner.to_string(), loser.to_string(), confidence)
+        };
+        let check = |picks: &[(String, String, u32)]| {
+            confidence_picks(&store, contest, DEFAULT_USER, first, picks, now)
+        };
+
+        assert_eq!(
+            check(&[pick("B", "A", 2), pick("C", "D", 1)])
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(check(&[pick("B", "A", 3)]).is_err());
+        assert!(check(&[pick("B", "A", 1), pick("C", "D", 1)]).is_err());
+        assert!(check(&[pick("B", "A", 1), pick("A", "B", 2)]).is_err());
+        assert!(check(&[pick("A", "D", 1)]).is_err());
+        assert!(survivor_pick(&store, contest, DEFAULT_USER, first, "A", now).is_err());
+    }
+}
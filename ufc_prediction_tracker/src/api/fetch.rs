@@ -0,0 +1,453 @@
+//! The HTTP client every scrape goes through, so ufcstats sees one well-behaved visitor rather than
+//! a burst of bare requests.
+//!
+//! Requests carry a user agent and a timeout, and are spaced out by a token bucket shared across
+//! the server. Pages a site's robots.txt disallows aren't fetched. Server errors, rate limiting and
+//! timeouts are retried with exponential backoff. Every fetch is counted against its host in the
+//! store, so the Import page can show how syncing has been going.
+
+use crate::db::{self, HttpOutcome};
+use reqwest::{StatusCode, Url};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// How long a site's robots.txt is trusted before it's fetched again.
+pub const ROBOTS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// The longest wait between retries, however many have failed.
+pub const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// How the shared client behaves. See [`FetchConfig::from_env`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchConfig {
+    pub user_agent: String,
+    pub timeout: Duration,
+    /// Retries after the first attempt, for server errors, rate limiting and timeouts.
+    pub retries: u32,
+    /// The wait before the first retry, doubling for each one after.
+    pub backoff: Duration,
+    /// Sustained requests per second.
+    pub rate: f64,
+    /// How many requests can go out at once after a quiet spell.
+    pub burst: f64,
+}
+
+impl Default for FetchConfig {
+    fn default() -> Self {
+        FetchConfig {
+            user_agent: concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION"),
+                " (+",
+                env!("CARGO_PKG_REPOSITORY"),
+                ")"
+            )
+            .to_string(),
+            timeout: Duration::from_secs(20),
+            retries: 3,
+            backoff: Duration::from_secs(1),
+            rate: 1.0,
+            burst: 5.0,
+        }
+    }
+}
+
+impl FetchConfig {
+    /// The defaults, overridden by `UFC_HTTP_USER_AGENT`, `UFC_HTTP_TIMEOUT_SECS`,
+    /// `UFC_HTTP_RETRIES`, `UFC_HTTP_RATE` and `UFC_HTTP_BURST` where they're set and valid.
+    pub fn from_env() -> Self {
+        let var = |name| std::env::var(name).ok().filter(|v: &String| !v.is_empty());
+        let mut config = FetchConfig::default();
+        if let Some(user_agent) = var("UFC_HTTP_USER_AGENT") {
+            config.user_agent = user_agent;
+        }
+        if let Some(secs) = var("UFC_HTTP_TIMEOUT_SECS").and_then(|v| v.parse().ok()) {
+            config.timeout = Duration::from_secs(secs);
+        }
+        if let Some(retries) = var("UFC_HTTP_RETRIES").and_then(|v| v.parse().ok()) {
+            config.retries = retries;
+        }
+        if let Some(rate) = var("UFC_HTTP_RATE")
+            .and_then(|v| v.parse().ok())
+            .filter(|rate: &f64| *rate > 0.0)
+        {
+            config.rate = rate;
+        }
+        if let Some(burst) = var("UFC_HTTP_BURST")
+            .and_then(|v| v.parse().ok())
+            .filter(|burst: &f64| *burst >= 1.0)
+        {
+            config.burst = burst;
+        }
+        config
+    }
+
+    /// How long to wait before retry number `retry`, counting from zero.
+    pub fn backoff_for(&self, retry: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(MAX_BACKOFF)
+    }
+}
+
+/// Why a page couldn't be fetched.
+#[derive(Debug)]
+pub enum FetchError {
+    InvalidUrl(String),
+    /// The site's robots.txt asks us not to fetch the page.
+    Disallowed(String),
+    Status(StatusCode),
+    Request(reqwest::Error),
+}
+
+impl FetchError {
+    /// Whether trying again later might work.
+    fn is_transient(&self) -> bool {
+        match self {
+            FetchError::Status(status) => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            FetchError::Request(e) => e.is_timeout() || e.is_connect(),
+            FetchError::InvalidUrl(_) | FetchError::Disallowed(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::InvalidUrl(url) => write!(f, "Invalid URL {}", url),
+            FetchError::Disallowed(url) => write!(f, "robots.txt disallows fetching {}", url),
+            FetchError::Status(status) => write!(f, "The server responded {}", status),
+            FetchError::Request(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for FetchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FetchError::Request(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Spaces requests out to `rate` a second, letting up to `burst` through at once.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, burst: f64, now: Instant) -> Self {
+        TokenBucket {
+            rate,
+            burst,
+            tokens: burst,
+            updated: now,
+        }
+    }
+
+    /// Takes a token, returning how long to wait before using it. Tokens are handed out even when
+    /// none are left, so callers queue up in turn rather than racing for the next one.
+    fn take(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.updated = self.updated.max(now);
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+/// The user agents a robots.txt group names, and its (allowed, pattern) rules.
+type Group = (Vec<String>, Vec<(bool, String)>);
+
+/// The rules in a robots.txt that apply to our user agent, or to everyone if none name it.
+///
+/// Paths match by prefix, with `*` matching anything and a trailing `$` anchoring the end. The
+/// longest matching rule wins, and `Allow` wins a tie.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Robots {
+    /// (allowed, pattern)
+    rules: Vec<(bool, String)>,
+}
+
+impl Robots {
+    pub fn parse(text: &str, user_agent: &str) -> Self {
+        // the product name, as robots.txt groups are matched on
+        let ours = user_agent
+            .split(['/', ' '])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let mut groups: Vec<Group> = Vec::new();
+        let mut in_rules = true;
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_lowercase().as_str() {
+                "user-agent" => {
+                    // consecutive user agents share the rules that follow them
+                    if in_rules {
+                        groups.push((Vec::new(), Vec::new()));
+                        in_rules = false;
+                    }
+                    if let Some((agents, _)) = groups.last_mut() {
+                        agents.push(value.to_lowercase());
+                    }
+                }
+                rule @ ("allow" | "disallow") => {
+                    in_rules = true;
+                    // an empty Disallow allows everything
+                    if let (Some((_, rules)), false) = (groups.last_mut(), value.is_empty()) {
+                        rules.push((rule == "allow", value.to_string()));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let rules_for = |matches: &dyn Fn(&str) -> bool| {
+            groups
+                .iter()
+                .filter(|(agents, _)| agents.iter().any(|agent| matches(agent)))
+                .flat_map(|(_, rules)| rules.iter().cloned())
+                .collect::<Vec<_>>()
+        };
+        let mut rules = rules_for(&|agent| agent == ours);
+        if rules.is_empty() {
+            rules = rules_for(&|agent| agent == "*");
+        }
+        Robots { rules }
+    }
+
+    /// Whether the path (with any query string) may be fetched.
+    pub fn allows(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| pattern_matches(pattern, path))
+            .max_by_key(|(allowed, pattern)| (pattern.len(), *allowed))
+            .is_none_or(|(allowed, _)| *allowed)
+    }
+}
+
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = path.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    for (i, part) in parts.iter().enumerate() {
+        if anchored && i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+/// Fetches pages politely. Share the one from [`fetcher`] so the rate limit covers every request.
+pub struct Fetcher {
+    config: FetchConfig,
+    client: reqwest::Client,
+    bucket: Mutex<TokenBucket>,
+    /// Each site's robots.txt by origin, with when it was fetched.
+    robots: Mutex<HashMap<String, (Robots, Instant)>>,
+}
+
+static FETCHER: OnceLock<Fetcher> = OnceLock::new();
+
+/// The client shared by every scrape, set up from the environment on first use.
+pub fn fetcher() -> &'static Fetcher {
+    FETCHER.get_or_init(|| Fetcher::new(FetchConfig::from_env()))
+}
+
+impl Fetcher {
+    pub fn new(config: FetchConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent(config.user_agent.clone())
+            .timeout(config.timeout)
+            .build()
+            .expect("Failed to build HTTP client");
+        Fetcher {
+            bucket: Mutex::new(TokenBucket::new(config.rate, config.burst, Instant::now())),
+            robots: Mutex::new(HashMap::new()),
+            config,
+            client,
+        }
+    }
+
+    /// The page's body as text, retrying transient failures. The fetch is counted against the
+    /// page's host in `store`.
+    pub async fn get_text(&self, store: &db::Store, url: &str) -> Result<String, FetchError> {
+        let Ok(parsed) = Url::parse(url) else {
+            return Err(FetchError::InvalidUrl(url.to_string()));
+        };
+        let host = parsed.host_str().unwrap_or_default().to_string();
+        if !self.robots_allow(&parsed).await {
+            record(store, &host, 0, &HttpOutcome::Disallowed);
+            return Err(FetchError::Disallowed(url.to_string()));
+        }
+
+        let mut attempts = 0;
+        let result = loop {
+            self.wait_turn().await;
+            attempts += 1;
+            match self.attempt(url).await {
+                Err(e) if e.is_transient() && attempts <= self.config.retries => {
+                    let wait = self.config.backoff_for(attempts - 1);
+                    log::warn!("Retrying {} in {:?}: {}", url, wait, e);
+                    tokio::time::sleep(wait).await;
+                }
+                result => break result,
+            }
+        };
+        let outcome = match &result {
+            Ok(_) => HttpOutcome::Fetched,
+            Err(e) => HttpOutcome::Failed(e.to_string()),
+        };
+        record(store, &host, attempts as usize, &outcome);
+        result
+    }
+
+    async fn attempt(&self, url: &str) -> Result<String, FetchError> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(FetchError::Request)?;
+        if !response.status().is_success() {
+            return Err(FetchError::Status(response.status()));
+        }
+        response.text().await.map_err(FetchError::Request)
+    }
+
+    async fn wait_turn(&self) {
+        let wait = self.bucket.lock().unwrap().take(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    async fn robots_allow(&self, url: &Url) -> bool {
+        let origin = url.origin().ascii_serialization();
+        let cached = self
+            .robots
+            .lock()
+            .unwrap()
+            .get(&origin)
+            .filter(|(_, fetched)| fetched.elapsed() < ROBOTS_TTL)
+            .map(|(robots, _)| robots.clone());
+        let robots = match cached {
+            Some(robots) => robots,
+            None => {
+                self.wait_turn().await;
+                match self.attempt(&format!("{}/robots.txt", origin)).await {
+                    Ok(text) => Some(Robots::parse(&text, &self.config.user_agent)),
+                    // no robots.txt means no restrictions
+                    Err(FetchError::Status(status)) if status.is_client_error() => {
+                        Some(Robots::default())
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to fetch robots.txt for {}: {}", origin, e);
+                        None
+                    }
+                }
+                .inspect(|robots| {
+                    self.robots
+                        .lock()
+                        .unwrap()
+                        .insert(origin, (robots.clone(), Instant::now()));
+                })
+                // not cached, so it's tried again on the next fetch
+                .unwrap_or_default()
+            }
+        };
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        robots.allows(&path)
+    }
+}
+
+/// Metrics are only logged if they can't be stored, since the fetch itself went ahead.
+fn record(store: &db::Store, host: &str, attempts: usize, outcome: &HttpOutcome) {
+    if let Err(e) = store.record_http_fetch(host, attempts, outcome) {
+        log::error!("Failed to record HTTP metrics: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_lets_a_burst_through_then_spaces_requests() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2.0, 2.0, start);
+        assert_eq!(bucket.take(start), Duration::ZERO);
+        assert_eq!(bucket.take(start), Duration::ZERO);
+        assert_eq!(bucket.take(start), Duration::from_millis(500));
+        // the caller behind that one waits its turn too
+        assert_eq!(bucket.take(start), Duration::from_secs(1));
+        // refills after a quiet spell, but never past the burst
+        let later = start + Duration::from_secs(60);
+        assert_eq!(bucket.take(later), Duration::ZERO);
+        assert_eq!(bucket.take(later), Duration::ZERO);
+        assert!(bucket.take(later) > Duration::ZERO);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_a_cap() {
+        let config = FetchConfig::default();
+        assert_eq!(config.backoff_for(0), Duration::from_secs(1));
+        assert_eq!(config.backoff_for(3), Duration::from_secs(8));
+        assert_eq!(config.backoff_for(30), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn follows_the_most_specific_robots_rules() {
+        let text = "User-agent: *\n\
+            Disallow: /private\n\
+            Allow: /private/open$\n\
+            \n\
+            User-agent: otherbot\n\
+            User-agent: ufc_prediction_tracker # us\n\
+            Disallow: /statistics/*?page=all\n\
+            Disallow:\n";
+        let ours = Robots::parse(text, &FetchConfig::default().user_agent);
+        assert!(!ours.allows("/statistics/events/completed?page=all"));
+        assert!(ours.allows("/statistics/events/completed"));
+        assert!(ours.allows("/private"));
+
+        let others = Robots::parse(text, "somebot/1.0");
+        assert!(!others.allows("/private/closed"));
+        assert!(others.allows("/private/open"));
+        assert!(!others.allows("/private/open/more"));
+        assert!(others.allows("/statistics/events/completed?page=all"));
+        assert!(Robots::default().allows("/anything"));
+    }
+}
@@ -0,0 +1,274 @@
+//! An iCalendar (RFC 5545) feed of upcoming events, for subscribing from a calendar app.
+//!
+//! Events with both start times get one calendar entry for the prelims and one for the main card;
+//! events with one get a single entry, and events without any an all-day entry. The entry starting
+//! first carries an alarm ahead of picks locking.
+
+use crate::db::{dates, Event};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use dioxus::prelude::server_fn::codec::{Encoding, FromRes, IntoRes};
+use dioxus::prelude::server_fn::response::{ClientRes, Res};
+use dioxus::prelude::ServerFnError;
+use http::Method;
+
+/// How long a main card is assumed to run, since nothing says when it ends.
+pub const MAIN_CARD_HOURS: i64 = 3;
+/// How long before picks lock the calendar alarm goes off.
+pub const LOCK_ALARM_MINUTES: i64 = 60;
+/// The name the feed is downloaded as.
+pub const FILE_NAME: &str = "ufc-events.ics";
+
+const UID_DOMAIN: &str = "ufc-prediction-tracker";
+// RFC 5545 folds lines longer than this many octets
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Serves a server function's output as an iCalendar file.
+pub struct Ics;
+
+impl Encoding for Ics {
+    const CONTENT_TYPE: &'static str = "text/calendar; charset=utf-8";
+    const METHOD: Method = Method::GET;
+}
+
+/// A whole calendar, as produced by [`calendar`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcsFile(pub String);
+
+impl<CustErr, Response> IntoRes<Ics, Response, CustErr> for IcsFile
+where
+    Response: Res<CustErr>,
+{
+    async fn into_res(self) -> Result<Response, ServerFnError<CustErr>> {
+        Response::try_from_string(Ics::CONTENT_TYPE, self.0)
+    }
+}
+
+impl<CustErr, Response> FromRes<Ics, Response, CustErr> for IcsFile
+where
+    Response: ClientRes<CustErr> + Send,
+{
+    async fn from_res(res: Response) -> Result<Self, ServerFnError<CustErr>> {
+        Ok(IcsFile(res.try_into_string().await?))
+    }
+}
+
+/// When an entry starts, and for timed entries, when it ends.
+enum Span {
+    Day(NaiveDate),
+    Timed(NaiveDateTime, NaiveDateTime),
+}
+
+/// The calendar for `events`, stamped with `now` (UTC).
+pub fn calendar(events: &[Event], now: NaiveDateTime) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:-//{}//Upcoming events//EN", UID_DOMAIN),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:UFC events".to_string(),
+    ];
+    for event in events {
+        lines.extend(event_entries(event, now));
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines
+        .iter()
+        .map(|line| fold(line))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        + "\r\n"
+}
+
+fn event_entries(event: &Event, now: NaiveDateTime) -> Vec<String> {
+    let Ok(date) = dates::parse_event_date(&event.date) else {
+        return Vec::new();
+    };
+    let time = |raw: &Option<String>| {
+        raw.as_deref()
+            .and_then(|raw| dates::parse_start_time(raw).ok())
+            .map(|time: NaiveTime| date.and_time(time))
+    };
+    let main_card = Duration::hours(MAIN_CARD_HOURS);
+    let entries = match (time(&event.prelims_time), time(&event.start_time)) {
+        (Some(prelims), Some(main)) => vec![
+            ("prelims", "Prelims", Span::Timed(prelims, main)),
+            ("main", "Main Card", Span::Timed(main, main + main_card)),
+        ],
+        (Some(start), None) | (None, Some(start)) => {
+            vec![("card", "", Span::Timed(start, start + main_card))]
+        }
+        (None, None) => vec![("card", "", Span::Day(date))],
+    };
+
+    let mut lines = Vec::new();
+    for (i, (part, title, span)) in entries.into_iter().enumerate() {
+        let summary = if title.is_empty() {
+            event.name.clone()
+        } else {
+            format!("{}: {}", event.name, title)
+        };
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:event-{}-{}@{}", event.id, part, UID_DOMAIN));
+        lines.push(format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")));
+        lines.extend(span_lines(&span, event.timezone.as_deref()));
+        lines.push(format!("SUMMARY:{}", escape(&summary)));
+        if let Some(place) = event.place() {
+            lines.push(format!("LOCATION:{}", escape(&place)));
+        }
+        // card file events may not have a page
+        if event.link.starts_with("http") {
+            lines.push(format!("URL:{}", event.link));
+        }
+        lines.push(format!(
+            "DESCRIPTION:{}",
+            escape("Picks lock when the first bout starts.")
+        ));
+        // picks lock as the first entry starts
+        if i == 0 {
+            lines.extend([
+                "BEGIN:VALARM".to_string(),
+                "ACTION:DISPLAY".to_string(),
+                format!("TRIGGER:-PT{}M", LOCK_ALARM_MINUTES),
+                format!(
+                    "DESCRIPTION:{}",
+                    escape(&format!("Picks lock for {}", event.name))
+                ),
+                "END:VALARM".to_string(),
+            ]);
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines
+}
+
+/// Times are converted to UTC from the event's timezone where it's known, so no VTIMEZONE is
+/// needed, and are floating otherwise.
+fn span_lines(span: &Span, timezone: Option<&str>) -> Vec<String> {
+    match span {
+        Span::Day(date) => vec![
+            format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+            format!(
+                "DTEND;VALUE=DATE:{}",
+                (*date + Duration::days(1)).format("%Y%m%d")
+            ),
+        ],
+        Span::Timed(start, end) => {
+            let tz = timezone.and_then(|tz| tz.parse::<chrono_tz::Tz>().ok());
+            vec![
+                format!("DTSTART:{}", date_time(*start, tz)),
+                format!("DTEND:{}", date_time(*end, tz)),
+            ]
+        }
+    }
+}
+
+/// A wall clock time in `tz` as a UTC date-time, or as a floating one without a timezone.
+fn date_time(time: NaiveDateTime, tz: Option<chrono_tz::Tz>) -> String {
+    // the earlier reading when clocks go back; a time skipped when they go forward stays floating
+    match tz.and_then(|tz| tz.from_local_datetime(&time).earliest()) {
+        Some(time) => time
+            .with_timezone(&Utc)
+            .format("%Y%m%dT%H%M%SZ")
+            .to_string(),
+        None => time.format("%Y%m%dT%H%M%S").to_string(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Splits a long content line into continuation lines, never inside a character.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // the leading space counts towards the continuation line
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event() -> Event {
+        Event {
+            id: 7,
+            name: "UFC 323".to_string(),
+            date: "2025-12-06".to_string(),
+            link: "http://ufcstats.com/event-details/abc".to_string(),
+            location: Some("Las Vegas, Nevada, USA".to_string()),
+            venue: Some("T-Mobile Arena".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn now() -> NaiveDateTime {
+        NaiveDateTime::parse_from_str("2025-12-01 12:00", "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn splits_prelims_and_main_card_with_an_alarm_before_lock() {
+        let event = Event {
+            prelims_time: Some("16:00".to_string()),
+            start_time: Some("19:00".to_string()),
+            timezone: Some("America/Los_Angeles".to_string()),
+            ..event()
+        };
+        let ics = calendar(&[event], now());
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("SUMMARY:UFC 323: Prelims\r\n"));
+        assert!(ics.contains("DTSTART:20251207T000000Z\r\n"));
+        assert!(ics.contains("DTEND:20251207T030000Z\r\n"));
+        assert!(ics.contains("SUMMARY:UFC 323: Main Card\r\n"));
+        assert!(ics.contains("DTEND:20251207T060000Z\r\n"));
+        assert!(!ics.contains("TZID"));
+        assert!(ics.contains("LOCATION:T-Mobile Arena\\, Las Vegas\\, Nevada\\, USA\r\n"));
+        assert!(ics.contains("DTSTAMP:20251201T120000Z\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert_eq!(ics.matches("BEGIN:VALARM").count(), 1);
+        assert!(ics.contains("TRIGGER:-PT60M\r\n"));
+    }
+
+    #[test]
+    fn times_without_a_timezone_are_floating() {
+        let event = Event {
+            start_time: Some("19:00".to_string()),
+            ..event()
+        };
+        let ics = calendar(&[event], now());
+        assert!(ics.contains("DTSTART:20251206T190000\r\n"));
+        assert!(ics.contains("DTEND:20251206T220000\r\n"));
+    }
+
+    #[test]
+    fn events_without_times_are_all_day() {
+        let ics = calendar(&[event()], now());
+        assert!(ics.contains("SUMMARY:UFC 323\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20251206\r\n"));
+        assert!(ics.contains("DTEND;VALUE=DATE:20251207\r\n"));
+        assert!(ics.contains("UID:event-7-card@ufc-prediction-tracker\r\n"));
+    }
+
+    #[test]
+    fn folds_long_lines_on_character_boundaries() {
+        let line = format!("SUMMARY:{}", "é".repeat(60));
+        let folded = fold(&line);
+        let parts = folded.split("\r\n").collect::<Vec<_>>();
+        assert_eq!(parts.len(), 2);
+        assert!(parts.iter().all(|part| part.len() <= MAX_LINE_OCTETS));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -1,2 +1,11 @@
 mod api;
 pub use api::*;
+mod auth;
+mod backfill;
+mod contests;
+mod fetch;
+pub mod ical;
+mod pick_cards;
+pub mod rest;
+mod scrape;
+mod source;
@@ -0,0 +1,349 @@
+//! Shareable images of a user's picks for an event: a pick sheet before it, and a results card
+//! marking each pick right or wrong once results are in. Cards are drawn as SVG and rasterised to
+//! PNG on the server, so no browser is involved.
+
+use crate::db::{self, Bout, Event};
+use dioxus::prelude::server_fn::codec::{Encoding, FromRes, IntoRes};
+use dioxus::prelude::server_fn::response::{ClientRes, Res};
+use dioxus::prelude::ServerFnError;
+use http::Method;
+use resvg::{tiny_skia, usvg};
+use std::sync::{Arc, OnceLock};
+
+const WIDTH: usize = 640;
+const HEADER_HEIGHT: usize = 96;
+const ROW_HEIGHT: usize = 44;
+const FOOTER_HEIGHT: usize = 48;
+const PADDING: usize = 24;
+const MARK_GAP: usize = 24;
+
+// the rasteriser maps plain sans-serif to Arial only, so common fonts are named too
+const FONT_FAMILY: &str = "Helvetica, Arial, DejaVu Sans, Liberation Sans, sans-serif";
+
+const BACKGROUND: &str = "#111827";
+const TEXT: &str = "#f9fafb";
+const MUTED: &str = "#9ca3af";
+const PICKED: &str = "#3b82f6";
+const CORRECT: &str = "#22c55e";
+const INCORRECT: &str = "#ef4444";
+
+/// Serves a server function's output as an SVG image.
+pub struct Svg;
+
+impl Encoding for Svg {
+    const CONTENT_TYPE: &'static str = "image/svg+xml";
+    const METHOD: Method = Method::GET;
+}
+
+/// Serves a server function's output as a PNG image.
+pub struct Png;
+
+impl Encoding for Png {
+    const CONTENT_TYPE: &'static str = "image/png";
+    const METHOD: Method = Method::GET;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvgImage(pub String);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PngImage(pub Vec<u8>);
+
+impl<CustErr, Response> IntoRes<Svg, Response, CustErr> for SvgImage
+where
+    Response: Res<CustErr>,
+{
+    async fn into_res(self) -> Result<Response, ServerFnError<CustErr>> {
+        Response::try_from_string(Svg::CONTENT_TYPE, self.0)
+    }
+}
+
+impl<CustErr, Response> FromRes<Svg, Response, CustErr> for SvgImage
+where
+    Response: ClientRes<CustErr> + Send,
+{
+    async fn from_res(res: Response) -> Result<Self, ServerFnError<CustErr>> {
+        Ok(SvgImage(res.try_into_string().await?))
+    }
+}
+
+impl<CustErr, Response> IntoRes<Png, Response, CustErr> for PngImage
+where
+    Response: Res<CustErr>,
+{
+    async fn into_res(self) -> Result<Response, ServerFnError<CustErr>> {
+        Response::try_from_bytes(Png::CONTENT_TYPE, self.0.into())
+    }
+}
+
+impl<CustErr, Response> FromRes<Png, Response, CustErr> for PngImage
+where
+    Response: ClientRes<CustErr> + Send,
+{
+    async fn from_res(res: Response) -> Result<Self, ServerFnError<CustErr>> {
+        Ok(PngImage(res.try_into_bytes().await?.to_vec()))
+    }
+}
+
+/// One bout on a card.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CardRow {
+    pub fighter1: String,
+    pub fighter2: String,
+    /// Who the user picked, if they picked the bout.
+    pub pick: Option<String>,
+    /// Who won, once the result is in.
+    pub winner: Option<String>,
+}
+
+impl CardRow {
+    /// Whether the pick was right, once both the pick and the result are in.
+    pub fn correct(&self) -> Option<bool> {
+        Some(self.pick.as_ref()? == self.winner.as_ref()?)
+    }
+}
+
+/// The rows for a card: the event's bouts in running order with the user's `picks` and the
+/// `results` (both as (winner, loser)) matched up. Events recorded before cards were stored only
+/// have their picks to go on.
+pub fn card_rows(
+    bouts: &[Bout],
+    picks: &[(String, String)],
+    results: &[(String, String)],
+) -> Vec<CardRow> {
+    let matching = |pairs: &[(String, String)], fighter1: &str, fighter2: &str| {
+        pairs
+            .iter()
+            .find(|(winner, loser)| {
+                (winner == fighter1 && loser == fighter2)
+                    || (winner == fighter2 && loser == fighter1)
+            })
+            .map(|(winner, _)| winner.clone())
+    };
+    let fights = if bouts.is_empty() {
+        picks.to_vec()
+    } else {
+        bouts
+            .iter()
+            .map(|bout| (bout.fighter1.clone(), bout.fighter2.clone()))
+            .collect()
+    };
+    fights
+        .into_iter()
+        .map(|(fighter1, fighter2)| CardRow {
+            pick: matching(picks, &fighter1, &fighter2),
+            winner: matching(results, &fighter1, &fighter2),
+            fighter1,
+            fighter2,
+        })
+        .collect()
+}
+
+/// `user`'s card for the event as SVG, from their picks and the results recorded so far.
+pub fn event_card(store: &db::Store, event_id: usize, user: &str) -> Result<String, ServerFnError> {
+    let event = store.get_event(event_id)?;
+    let rows = card_rows(
+        &store.get_bouts(event_id)?,
+        &store.get_predictions_for_user(event_id, user)?,
+        &store.get_results(event_id)?,
+    );
+    if rows.iter().all(|row| row.pick.is_none()) {
+        return Err(ServerFnError::new("Make some picks for this event first"));
+    }
+    Ok(card_svg(&event, user, &rows))
+}
+
+/// The card as SVG. It's a results card as soon as any result is in, and a pick sheet before.
+pub fn card_svg(event: &Event, user: &str, rows: &[CardRow]) -> String {
+    let height = HEADER_HEIGHT + rows.len() * ROW_HEIGHT + FOOTER_HEIGHT;
+    let results_in = rows.iter().any(|row| row.winner.is_some());
+    let mut subtitle = event.display_date();
+    if let Some(place) = event.place() {
+        subtitle.push_str(" · ");
+        subtitle.push_str(&place);
+    }
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" viewBox="0 0 {WIDTH} {height}" font-family="{FONT_FAMILY}">"#
+    );
+    svg += &format!(r#"<rect width="100%" height="100%" fill="{BACKGROUND}"/>"#);
+    svg += &text(PADDING, 44, 26, TEXT, "bold", "start", &event.name);
+    svg += &text(PADDING, 72, 15, MUTED, "normal", "start", &subtitle);
+
+    let half = (WIDTH - 2 * PADDING) / 2;
+    for (i, row) in rows.iter().enumerate() {
+        let top = HEADER_HEIGHT + i * ROW_HEIGHT;
+        let baseline = top + ROW_HEIGHT / 2 + 6;
+        let correct = row.correct();
+        for (fighter, x, anchor) in [
+            (&row.fighter1, PADDING, "start"),
+            (&row.fighter2, WIDTH - PADDING, "end"),
+        ] {
+            let picked = row.pick.as_ref() == Some(fighter);
+            if picked {
+                let fill = match correct {
+                    Some(true) => CORRECT,
+                    Some(false) => INCORRECT,
+                    None => PICKED,
+                };
+                // leaves room for the mark between the fighters
+                let left = if anchor == "start" {
+                    PADDING
+                } else {
+                    PADDING + half + MARK_GAP
+                };
+                svg += &format!(
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" rx="6" fill="{}"/>"#,
+                    left,
+                    top + 4,
+                    half - MARK_GAP,
+                    ROW_HEIGHT - 8,
+                    fill
+                );
+            }
+            let x = if anchor == "start" { x + 10 } else { x - 10 };
+            let weight = if picked { "bold" } else { "normal" };
+            let colour = if picked || row.pick.is_none() {
+                TEXT
+            } else {
+                MUTED
+            };
+            svg += &text(x, baseline, 17, colour, weight, anchor, fighter);
+        }
+        let mark = match (results_in, correct) {
+            (true, Some(true)) => "✓",
+            (true, Some(false)) => "✗",
+            _ => "vs",
+        };
+        svg += &text(WIDTH / 2, baseline, 15, MUTED, "normal", "middle", mark);
+    }
+
+    let footer = if results_in {
+        let decided = rows.iter().filter_map(CardRow::correct).collect::<Vec<_>>();
+        let correct = decided.iter().filter(|correct| **correct).count();
+        format!("{}: {}/{} correct", user, correct, decided.len())
+    } else {
+        let picked = rows.iter().filter(|row| row.pick.is_some()).count();
+        format!("{}'s picks: {} of {} bouts", user, picked, rows.len())
+    };
+    svg += &text(
+        PADDING,
+        height - FOOTER_HEIGHT / 2 + 6,
+        15,
+        MUTED,
+        "normal",
+        "start",
+        &footer,
+    );
+    svg += "</svg>";
+    svg
+}
+
+fn text(
+    x: usize,
+    y: usize,
+    size: usize,
+    fill: &str,
+    weight: &str,
+    anchor: &str,
+    content: &str,
+) -> String {
+    format!(
+        r#"<text x="{x}" y="{y}" font-size="{size}" font-weight="{weight}" text-anchor="{anchor}" fill="{fill}">{}</text>"#,
+        escape(content)
+    )
+}
+
+fn escape(content: &str) -> String {
+    content
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// loading the system fonts is slow, so it's done once
+static FONTS: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+
+/// Rasterises an SVG card to PNG, with text in the server's system fonts.
+pub fn render_png(svg: &str) -> Result<Vec<u8>, ServerFnError> {
+    let fonts = FONTS.get_or_init(|| {
+        let mut fonts = usvg::fontdb::Database::new();
+        fonts.load_system_fonts();
+        Arc::new(fonts)
+    });
+    let options = usvg::Options {
+        fontdb: fonts.clone(),
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &options)
+        .map_err(|e| ServerFnError::new(format!("Failed to read the card: {}", e)))?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| ServerFnError::new("The card has no size"))?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    pixmap
+        .encode_png()
+        .map_err(|e| ServerFnError::new(format!("Failed to encode the card: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(winner: &str, loser: &str) -> (String, String) {
+        (winner.to_string(), loser.to_string())
+    }
+
+    fn bout(fighter1: &str, fighter2: &str) -> Bout {
+        Bout {
+            fighter1: fighter1.to_string(),
+            fighter2: fighter2.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rows_follow_the_card_with_picks_and_results() {
+        let bouts = [bout("A", "B"), bout("C", "D"), bout("E", "F")];
+        let rows = card_rows(
+            &bouts,
+            &[pair("B", "A"), pair("C", "D")],
+            &[pair("B", "A"), pair("D", "C")],
+        );
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].correct(), Some(true));
+        assert_eq!(rows[1].correct(), Some(false));
+        assert_eq!(rows[2].pick, None);
+        assert_eq!(rows[2].correct(), None);
+
+        let without_card = card_rows(&[], &[pair("B", "A")], &[]);
+        assert_eq!(without_card[0].fighter1, "B");
+        assert_eq!(without_card[0].pick.as_deref(), Some("B"));
+    }
+
+    #[test]
+    fn draws_a_pick_sheet_then_a_results_card() {
+        let event = Event {
+            name: "UFC <1>".to_string(),
+            date: "2025-01-02".to_string(),
+            ..Default::default()
+        };
+        let bouts = [bout("A", "B"), bout("C", "D")];
+        let sheet = card_svg(&event, "me", &card_rows(&bouts, &[pair("A", "B")], &[]));
+        assert!(sheet.contains("UFC &lt;1&gt;"));
+        assert!(sheet.contains("me's picks: 1 of 2 bouts"));
+        assert!(sheet.contains(PICKED));
+
+        let results = card_svg(
+            &event,
+            "me",
+            &card_rows(&bouts, &[pair("A", "B")], &[pair("B", "A")]),
+        );
+        assert!(results.contains("me: 0/1 correct"));
+        assert!(results.contains(INCORRECT));
+
+        let png = render_png(&results).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
@@ -0,0 +1,249 @@
+//! Versioned JSON endpoints for clients other than the app itself, served under `/api/v1` by the
+//! fullstack server. Reads are `GET` with query parameters, writes are `POST` with a JSON body.
+//!
+//! The response types below are the public schema. They only ever gain optional fields; anything
+//! else goes into a new version. `API.md` documents every endpoint.
+//!
+//! Every endpoint needs the caller's API token as `Authorization: Bearer <token>` once accounts exist.
+//! The calendar feed also takes it as a `token` query parameter, since calendar apps only take a URL.
+
+use super::ical::{Ics, IcsFile};
+use crate::db;
+use dioxus::prelude::server_fn::codec::{GetUrl, Json};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventV1 {
+    pub id: usize,
+    pub name: String,
+    /// YYYY-MM-DD
+    pub date: String,
+    pub location: Option<String>,
+    pub venue: Option<String>,
+    pub country: Option<String>,
+    /// When the main card starts, HH:MM local to `timezone`
+    pub start_time: Option<String>,
+    pub timezone: Option<String>,
+    /// The event's page, on ufcstats or the promotion's site; none for events without one
+    pub url: Option<String>,
+    /// When the prelims start, HH:MM local to `timezone`
+    pub prelims_time: Option<String>,
+    /// Which promotion runs the event, e.g. "UFC" or "PFL"
+    pub promotion: String,
+}
+
+impl From<db::Event> for EventV1 {
+    fn from(event: db::Event) -> Self {
+        EventV1 {
+            id: event.id,
+            name: event.name,
+            date: event.date,
+            location: event.location,
+            venue: event.venue,
+            country: event.country,
+            start_time: event.start_time,
+            timezone: event.timezone,
+            // card file and hand-entered events may not have a page
+            url: event.link.starts_with("http").then_some(event.link),
+            prelims_time: event.prelims_time,
+            promotion: event.promotion,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoutV1 {
+    pub event_id: usize,
+    /// Order on the card, 0 being the main event.
+    pub position: usize,
+    pub fighter1: String,
+    pub fighter2: String,
+    pub weight_class: Option<String>,
+    /// "win", "draw" or "nc"; missing until the bout has happened.
+    pub outcome: Option<String>,
+    pub winner: Option<String>,
+    pub method: Option<String>,
+    pub round: Option<u32>,
+    pub time: Option<String>,
+}
+
+impl From<db::Bout> for BoutV1 {
+    fn from(bout: db::Bout) -> Self {
+        BoutV1 {
+            event_id: bout.event_id,
+            position: bout.position,
+            fighter1: bout.fighter1,
+            fighter2: bout.fighter2,
+            weight_class: bout.weight_class,
+            outcome: bout.outcome.map(|o| o.as_str().to_string()),
+            winner: bout.winner,
+            method: bout.method,
+            round: bout.round,
+            time: bout.time,
+        }
+    }
+}
+
+/// A pick, or a result when `user` is absent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PickV1 {
+    pub event_id: usize,
+    pub user: Option<String>,
+    pub winner: String,
+    pub loser: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatsV1 {
+    pub user: String,
+    pub correct: i64,
+    pub incorrect: i64,
+    pub pending: i64,
+    /// Fraction of decided picks that were right, missing until one is decided.
+    pub accuracy: Option<f64>,
+}
+
+impl From<(String, db::Score)> for StatsV1 {
+    fn from((user, score): (String, db::Score)) -> Self {
+        StatsV1 {
+            user,
+            correct: score.correct,
+            incorrect: score.incorrect,
+            pending: score.pending,
+            accuracy: score.accuracy(),
+        }
+    }
+}
+
+/// Events dated between `from` and `to` (YYYY-MM-DD, inclusive, both optional), oldest first,
+/// from one promotion when `promotion` is given.
+#[server(prefix = "/api/v1", endpoint = "events", input = GetUrl)]
+pub async fn list_events(
+    from: Option<String>,
+    to: Option<String>,
+    promotion: Option<String>,
+) -> Result<Vec<EventV1>, ServerFnError> {
+    let store = super::api::get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let events = store.get_events(from.as_deref(), to.as_deref())?;
+    Ok(events
+        .into_iter()
+        .filter(|event| promotion.is_none() || promotion.as_ref() == Some(&event.promotion))
+        .map(EventV1::from)
+        .collect())
+}
+
+#[server(prefix = "/api/v1", endpoint = "event", input = GetUrl)]
+pub async fn event_by_id(id: usize) -> Result<EventV1, ServerFnError> {
+    let store = super::api::get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    Ok(store.get_event(id)?.into())
+}
+
+/// The event's card in running order, with results where they're known.
+#[server(prefix = "/api/v1", endpoint = "card", input = GetUrl)]
+pub async fn event_card(event_id: usize) -> Result<Vec<BoutV1>, ServerFnError> {
+    let store = super::api::get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let bouts = store.get_bouts(event_id)?;
+    Ok(bouts.into_iter().map(BoutV1::from).collect())
+}
+
+/// A user's picks for the event, the caller's by default.
+#[server(prefix = "/api/v1", endpoint = "predictions", input = GetUrl)]
+pub async fn event_predictions(
+    event_id: usize,
+    user: Option<String>,
+) -> Result<Vec<PickV1>, ServerFnError> {
+    let store = super::api::get_store();
+    let caller = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let user = user.unwrap_or(caller.name);
+    let picks = store.get_predictions_for_user(event_id, &user)?;
+    Ok(picks
+        .into_iter()
+        .map(|(winner, loser)| PickV1 {
+            event_id,
+            user: Some(user.clone()),
+            winner,
+            loser,
+        })
+        .collect())
+}
+
+/// Records a pick for the caller, replacing their earlier pick on the same bout. The pick has to be
+/// a bout on the event's card.
+#[server(prefix = "/api/v1", endpoint = "predict", input = Json)]
+pub async fn submit_prediction(pick: PickV1) -> Result<PickV1, ServerFnError> {
+    let store = super::api::get_store();
+    let user = super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    if pick.user.as_ref().is_some_and(|name| *name != user.name) {
+        return Err(ServerFnError::new(
+            "Picks can only be submitted for your own account",
+        ));
+    }
+    super::api::check_unlocked(store, pick.event_id)?;
+    super::api::check_pick(store, pick.event_id, &pick.winner, &pick.loser)?;
+    store.add_or_update_prediction_for_user(
+        pick.event_id,
+        &pick.winner,
+        &pick.loser,
+        &user.name,
+    )?;
+    Ok(PickV1 {
+        user: Some(user.name),
+        ..pick
+    })
+}
+
+#[server(prefix = "/api/v1", endpoint = "results", input = GetUrl)]
+pub async fn event_results(event_id: usize) -> Result<Vec<PickV1>, ServerFnError> {
+    let store = super::api::get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let results = store.get_results(event_id)?;
+    Ok(results
+        .into_iter()
+        .map(|(winner, loser)| PickV1 {
+            event_id,
+            user: None,
+            winner,
+            loser,
+        })
+        .collect())
+}
+
+/// Scores per user over events dated between `from` and `to`, or only `user`'s when given, and
+/// only over one promotion's events when `promotion` is.
+#[server(prefix = "/api/v1", endpoint = "stats", input = GetUrl)]
+pub async fn user_stats(
+    user: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    promotion: Option<String>,
+) -> Result<Vec<StatsV1>, ServerFnError> {
+    let store = super::api::get_store();
+    super::auth::authenticate(store, &server_context().request_parts().headers)?;
+    let filter = db::ScoreFilter {
+        user,
+        from,
+        to,
+        promotion,
+        ..Default::default()
+    };
+    let scores = store.scores_by_user(&filter)?;
+    Ok(scores.into_iter().map(StatsV1::from).collect())
+}
+
+/// Upcoming events from every promotion as an iCalendar feed, refreshed from ufcstats when it can
+/// be reached.
+#[server(prefix = "/api/v1", endpoint = "calendar.ics", input = GetUrl, output = Ics)]
+pub async fn upcoming_calendar(token: Option<String>) -> Result<IcsFile, ServerFnError> {
+    let store = super::api::get_store();
+    let headers = &server_context().request_parts().headers;
+    super::auth::authenticate_token(store, token.or_else(|| super::auth::request_token(headers)))?;
+    let events = super::source::upcoming_events(store).await?;
+    Ok(IcsFile(super::ical::calendar(
+        &events,
+        chrono::Utc::now().naive_utc(),
+    )))
+}
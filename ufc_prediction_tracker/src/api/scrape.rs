@@ -0,0 +1,184 @@
+use super::fetch::{fetcher, FetchError};
+use crate::db;
+use crate::parser::{self, Diagnostic, ListedEvent, PageKind, Parsed};
+use dioxus::prelude::ServerFnError;
+
+pub const UPCOMING_EVENTS_URL: &str = "http://ufcstats.com/statistics/events/upcoming?page=all";
+pub const COMPLETED_EVENTS_URL: &str = "http://ufcstats.com/statistics/events/completed?page=all";
+
+/// Records how parsing the page went, logging when it stops parsing cleanly. Recording failures
+/// are only logged, since the parse itself still counts.
+fn record_health<T>(
+    store: &db::Store,
+    page: PageKind,
+    url: &str,
+    parsed: &Parsed<T>,
+    items: usize,
+) {
+    let diagnostics = parsed
+        .diagnostics
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    match store.record_parser_health(page.as_str(), url, items, &diagnostics) {
+        Ok(true) => log::error!(
+            "{} at {} no longer parses cleanly: {}",
+            page.label(),
+            url,
+            diagnostics.join("; ")
+        ),
+        Ok(false) => {}
+        Err(e) => log::error!("Failed to record parser health: {}", e),
+    }
+}
+
+pub async fn fetch_event_listing(
+    store: &db::Store,
+    url: &str,
+) -> Result<Vec<ListedEvent>, FetchError> {
+    let response = fetcher().get_text(store, url).await?;
+    let parsed = parser::parse_event_listing(&response);
+    record_health(
+        store,
+        PageKind::EventListing,
+        url,
+        &parsed,
+        parsed.value.len(),
+    );
+    Ok(parsed.value)
+}
+
+/// Adds listed events to the store (or finds them if already known) and records their locations.
+/// Events whose date can't be read are left out and reported, rather than failing the listing.
+pub fn record_listed_events(
+    store: &db::Store,
+    listed: &[ListedEvent],
+) -> rusqlite::Result<Parsed<Vec<db::Event>>> {
+    let mut recorded = Parsed {
+        value: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+    for (i, event) in listed.iter().enumerate() {
+        if let Err(e) = db::dates::parse_event_date(&event.date) {
+            recorded.diagnostics.push(Diagnostic::row(
+                i,
+                format!("Unreadable date {:?} for {}: {}", event.date, event.name, e),
+            ));
+            continue;
+        }
+        let id = store.add_event(&event.name, &event.date, &event.link)?;
+        if !event.location.is_empty() {
+            store.set_event_location(id, &event.location)?;
+        }
+        recorded.value.push(store.get_event(id)?);
+    }
+    Ok(recorded)
+}
+
+/// Fills in locations for known events from a listing, without adding the events that aren't known yet.
+pub fn update_known_event_locations(
+    store: &db::Store,
+    listed: &[ListedEvent],
+) -> rusqlite::Result<usize> {
+    let mut updated = 0;
+    for event in listed.iter().filter(|e| !e.location.is_empty()) {
+        if let Some(id) = store.find_event(&event.name, &event.date)? {
+            updated += store.set_event_location(id, &event.location)?;
+        }
+    }
+    Ok(updated)
+}
+
+/// The event's card, with what the parser made of the page. Callers acting on bouts that went
+/// missing should check the parse was healthy first.
+pub async fn fetch_event_bouts(
+    store: &db::Store,
+    link: &str,
+    event_id: usize,
+) -> Result<Parsed<Vec<db::Bout>>, FetchError> {
+    let response = fetcher().get_text(store, link).await?;
+    let parsed = parser::parse_event_card(&response, event_id);
+    record_health(
+        store,
+        PageKind::EventCard,
+        link,
+        &parsed,
+        parsed.value.len(),
+    );
+    Ok(parsed)
+}
+
+pub fn fighter_url(id: &str) -> String {
+    format!("http://ufcstats.com/fighter-details/{id}")
+}
+
+/// The fighter from the store if the cached copy is recent enough, otherwise scraped and cached.
+pub async fn fetch_fighter(store: &db::Store, id: &str) -> Result<db::Fighter, ServerFnError> {
+    if let Some(fighter) = store.get_fresh_fighter(id)? {
+        return Ok(fighter);
+    }
+    let url = fighter_url(id);
+    let response = fetcher().get_text(store, &url).await;
+    let fighter = response.ok().and_then(|html| {
+        let parsed = parser::parse_fighter_page(&html, id);
+        record_health(
+            store,
+            PageKind::Fighter,
+            &url,
+            &parsed,
+            parsed.value.iter().count(),
+        );
+        parsed.value
+    });
+    match fighter {
+        Some(fighter) => {
+            store.upsert_fighter(&fighter)?;
+            Ok(fighter)
+        }
+        // a stale copy beats nothing when ufcstats is unreachable
+        None => store
+            .get_fighter(id)?
+            .ok_or_else(|| ServerFnError::new(format!("Failed to fetch fighter {}", id))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_location_and_country() {
+        let store = db::Store::new(":memory:");
+        store.create_tables().unwrap();
+        let listed = [ListedEvent {
+            name: "UFC 322: Della Maddalena vs. Makhachev".to_string(),
+            date: "November 15, 2025".to_string(),
+            link: "http://ufcstats.com/event-details/abc".to_string(),
+            location: "New York City, New York, USA".to_string(),
+        }];
+        let events = record_listed_events(&store, &listed).unwrap().value;
+        assert_eq!(events[0].date, "2025-11-15");
+        assert_eq!(events[0].country.as_deref(), Some("USA"));
+        assert_eq!(store.get_event_countries().unwrap(), vec!["USA"]);
+    }
+
+    #[test]
+    fn skips_listed_events_with_unreadable_dates() {
+        let store = db::Store::new(":memory:");
+        store.create_tables().unwrap();
+        let event = |name: &str, date: &str| ListedEvent {
+            name: name.to_string(),
+            date: date.to_string(),
+            link: format!("http://ufcstats.com/event-details/{name}"),
+            location: String::new(),
+        };
+        let recorded = record_listed_events(
+            &store,
+            &[event("a", "TBD"), event("b", "December 06, 2025")],
+        )
+        .unwrap();
+        assert_eq!(recorded.value.len(), 1);
+        assert_eq!(recorded.value[0].name, "b");
+        assert_eq!(recorded.diagnostics[0].row, Some(0));
+    }
+}
@@ -0,0 +1,271 @@
+//! Where events and their cards come from.
+//!
+//! UFC events are scraped from ufcstats, which only covers the UFC. Events from other promotions
+//! are loaded from card files (see [`CardFile`](crate::parser::CardFile)), imported by an admin or
+//! dropped in `UFC_CARD_DIR`, and kept in the store. Cards that aren't anywhere yet can be entered
+//! by hand, and folded into the scraped event once it appears. Either way each event is tagged
+//! with its promotion.
+
+use super::scrape;
+use crate::db;
+use crate::parser::{self, Parsed};
+use dioxus::prelude::ServerFnError;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// The link recorded for card file events that don't give one, since every event needs a link to
+/// be routed to.
+pub const CARD_FILE_LINK: &str = "card-file";
+
+/// Where an event's card comes from, told apart by the event's link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventSource {
+    /// Scraped from the event's ufcstats page.
+    Ufcstats,
+    /// Loaded from a card file and kept in the store.
+    CardFile,
+    /// Entered by hand and kept in the store.
+    Manual,
+}
+
+impl EventSource {
+    pub fn of_link(link: &str) -> Self {
+        if link == db::MANUAL_LINK {
+            return EventSource::Manual;
+        }
+        let host = reqwest::Url::parse(link)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string));
+        match host.as_deref() {
+            Some("ufcstats.com" | "www.ufcstats.com") => EventSource::Ufcstats,
+            _ => EventSource::CardFile,
+        }
+    }
+
+    /// The event's card in running order. A scraped card's fighters, running order and weight
+    /// classes are stored as they're read, and users with picks on bouts that dropped off it are
+    /// notified. Results are left out: they're only stored by [`sync_event`] and the backfill, or
+    /// by an admin.
+    ///
+    /// [`sync_event`]: super::backfill::sync_event
+    pub async fn card(
+        &self,
+        store: &db::Store,
+        event_id: usize,
+        link: &str,
+    ) -> Result<Vec<db::Bout>, ServerFnError> {
+        match self {
+            EventSource::Ufcstats => {
+                let parsed = scrape::fetch_event_bouts(store, link, event_id)
+                    .await
+                    .map_err(|e| {
+                        ServerFnError::new(format!("Failed to fetch event page: {}", e))
+                    })?;
+                for bout in &parsed.value {
+                    store.upsert_bout(&db::Bout {
+                        outcome: None,
+                        winner: None,
+                        method: None,
+                        round: None,
+                        time: None,
+                        ..bout.clone()
+                    })?;
+                }
+                // bouts the parser couldn't read aren't off the card
+                if parsed.is_healthy() {
+                    crate::notify::queue_card_changes(store, event_id, &parsed.value)?;
+                }
+                Ok(parsed.value)
+            }
+            EventSource::CardFile | EventSource::Manual => Ok(store.get_bouts(event_id)?),
+        }
+    }
+}
+
+/// Upcoming events from every source, soonest first: ufcstats' listing along with whatever card
+/// files and admins have added. When ufcstats can't be reached, the upcoming events already stored
+/// are served instead.
+pub async fn upcoming_events(store: &db::Store) -> Result<Vec<db::Event>, ServerFnError> {
+    load_card_dir(store);
+    let today = db::dates::today().format(db::dates::ISO_DATE).to_string();
+    let listed = match scrape::fetch_event_listing(store, scrape::UPCOMING_EVENTS_URL).await {
+        Ok(listed) => listed,
+        Err(e) => {
+            log::warn!("Serving stored upcoming events: {}", e);
+            return Ok(store.get_events(Some(&today), None)?);
+        }
+    };
+    let recorded = scrape::record_listed_events(store, &listed)?;
+    for diagnostic in &recorded.diagnostics {
+        log::warn!("{}: {}", scrape::UPCOMING_EVENTS_URL, diagnostic);
+    }
+    let mut events = recorded.value;
+    events.extend(
+        store
+            .get_events(Some(&today), None)?
+            .into_iter()
+            .filter(|event| EventSource::of_link(&event.link) != EventSource::Ufcstats),
+    );
+    // stable, so ufcstats' order holds within a day
+    events.sort_by(|a, b| a.date.cmp(&b.date));
+    Ok(events)
+}
+
+/// Stores every event in a card file with its promotion, details and bouts, recording results for
+/// bouts that have them. Returns the events stored, and what was left out of the file.
+pub fn import_card_file(
+    store: &db::Store,
+    text: &str,
+) -> Result<Parsed<Vec<db::Event>>, ServerFnError> {
+    let parsed = parser::parse_card_file(text)
+        .map_err(|e| ServerFnError::new(format!("Invalid card file: {}", e)))?;
+    let mut events = Vec::new();
+    for loaded in &parsed.value {
+        let event = &loaded.event;
+        let link = event
+            .link
+            .as_deref()
+            .map(str::trim)
+            .filter(|link| !link.is_empty())
+            .unwrap_or(CARD_FILE_LINK);
+        let id = store.add_event(&event.name, &event.date, link)?;
+        store.set_event_promotion(id, &loaded.promotion)?;
+        if let Some(location) = &event.location {
+            store.set_event_location(id, location)?;
+        }
+        if event.venue.is_some() {
+            store.set_event_venue(id, event.venue.as_deref())?;
+        }
+        let time = |raw: &Option<String>| {
+            raw.as_deref()
+                .and_then(|raw| db::dates::parse_start_time(raw).ok())
+        };
+        store.set_event_start(id, time(&event.start_time), event.timezone.as_deref())?;
+        store.set_prelims_start(id, time(&event.prelims_time))?;
+        let bouts = loaded
+            .bouts
+            .iter()
+            .map(|bout| db::Bout {
+                event_id: id,
+                ..bout.clone()
+            })
+            .collect::<Vec<_>>();
+        super::backfill::record_event(store, id, &bouts)?;
+        events.push(store.get_event(id)?);
+    }
+    Ok(Parsed {
+        value: events,
+        diagnostics: parsed.diagnostics,
+    })
+}
+
+// when each card file was last imported, so unchanged files aren't imported again
+static LOADED: Mutex<Option<HashMap<PathBuf, SystemTime>>> = Mutex::new(None);
+
+/// Imports the `.json` card files in `UFC_CARD_DIR` that are new or changed since they were last
+/// imported. Problems are logged rather than returned, so a bad file doesn't keep events from
+/// loading.
+pub fn load_card_dir(store: &db::Store) {
+    let Some(dir) = std::env::var("UFC_CARD_DIR").ok().filter(|d| !d.is_empty()) else {
+        return;
+    };
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::error!("Failed to read card directory {}: {}", dir, e);
+            return;
+        }
+    };
+    for path in entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+    {
+        let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        if modified.is_some()
+            && LOADED
+                .lock()
+                .unwrap()
+                .as_ref()
+                .and_then(|loaded| loaded.get(&path))
+                == modified.as_ref()
+        {
+            continue;
+        }
+        let imported = std::fs::read_to_string(&path)
+            .map_err(|e| ServerFnError::new(e.to_string()))
+            .and_then(|text| import_card_file(store, &text));
+        match imported {
+            Ok(parsed) => {
+                for diagnostic in &parsed.diagnostics {
+                    log::warn!("{}: {}", path.display(), diagnostic);
+                }
+                if let Some(modified) = modified {
+                    LOADED
+                        .lock()
+                        .unwrap()
+                        .get_or_insert_with(HashMap::new)
+                        .insert(path, modified);
+                }
+            }
+            Err(e) => log::error!("Failed to import {}: {}", path.display(), e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stores_card_file_events_under_their_promotion() {
+        let store = db::Store::new(":memory:");
+        store.create_tables().unwrap();
+        let text = r#"{
+            "promotion": "PFL",
+            "events": [{
+                "name": "PFL 1",
+                "date": "2025-04-03",
+                "location": "Orlando, Florida, USA",
+                "start_time": "20:00",
+                "timezone": "America/New_York",
+                "bouts": [
+                    { "fighter1": "A", "fighter2": "B", "winner": "B", "method": "Decision" },
+                    { "fighter1": "C", "fighter2": "D" }
+                ]
+            }]
+        }"#;
+        let imported = import_card_file(&store, text).unwrap();
+        assert!(imported.is_healthy());
+        let event = &imported.value[0];
+        assert_eq!(event.promotion, "PFL");
+        assert_eq!(event.link, CARD_FILE_LINK);
+        assert_eq!(event.country.as_deref(), Some("USA"));
+        assert_eq!(event.start_time.as_deref(), Some("20:00"));
+        assert_eq!(EventSource::of_link(&event.link), EventSource::CardFile);
+        assert_eq!(store.get_bouts(event.id).unwrap().len(), 2);
+        assert_eq!(
+            store.get_results(event.id).unwrap(),
+            vec![("B".to_string(), "A".to_string())]
+        );
+
+        // importing again updates rather than duplicates
+        import_card_file(&store, text).unwrap();
+        assert_eq!(store.get_events(None, None).unwrap().len(), 1);
+        assert_eq!(store.get_bouts(event.id).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn ufcstats_events_are_scraped() {
+        assert_eq!(
+            EventSource::of_link("http://ufcstats.com/event-details/abc"),
+            EventSource::Ufcstats
+        );
+        assert_eq!(
+            EventSource::of_link("https://pflmma.com/event/1"),
+            EventSource::CardFile
+        );
+        assert_eq!(EventSource::of_link(db::MANUAL_LINK), EventSource::Manual);
+    }
+}
@@ -0,0 +1,239 @@
+use super::{Store, DEFAULT_USER};
+use rusqlite::{OptionalExtension, Result, Row};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct User {
+    pub id: usize,
+    /// Also the `user` the account's picks are recorded under.
+    pub name: String,
+    /// Admins can write results and run imports.
+    pub is_admin: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TokenKind {
+    /// Set as a cookie when signing in to the web UI, and expires.
+    #[default]
+    Session,
+    /// Sent as a bearer token by scripts, and lasts until it's revoked.
+    Api,
+}
+
+impl TokenKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenKind::Session => "session",
+            TokenKind::Api => "api",
+        }
+    }
+}
+
+/// An API token as listed to its owner. The token itself is only shown once, when it's created.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: usize,
+    pub label: String,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+}
+
+fn user_from_row(row: &Row<'_>) -> Result<User> {
+    Ok(User {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        is_admin: row.get(2)?,
+        created_at: row.get(3)?,
+    })
+}
+
+impl Store {
+    pub fn count_users(&self) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row("SELECT count(*) FROM users", (), |row| row.get(0))
+    }
+
+    /// Creates an account. The first account becomes the admin and takes over everything recorded
+    /// under the default user before accounts existed.
+    pub fn create_user(&self, name: &str, password_hash: &str) -> Result<User> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let first = tx.query_row("SELECT count(*) FROM users", (), |row| {
+            row.get::<_, usize>(0)
+        })? == 0;
+        tx.execute(
+            "INSERT INTO users (name, password_hash, is_admin) VALUES (?1, ?2, ?3)",
+            (name, password_hash, first),
+        )?;
+        let id = tx.last_insert_rowid();
+        if first {
+            for table in [
+                "predictions",
+                "tickets",
+                "contest_picks",
+                "notification_prefs",
+            ] {
+                tx.execute(
+                    &format!("UPDATE OR IGNORE {table} SET user=?1 WHERE user=?2"),
+                    (name, DEFAULT_USER),
+                )?;
+            }
+        }
+        let user = tx.query_row(
+            "SELECT id, name, is_admin, created_at FROM users WHERE id=?1",
+            (id,),
+            user_from_row,
+        )?;
+        tx.commit()?;
+        Ok(user)
+    }
+
+    /// The account with its password hash, for checking a sign-in.
+    pub fn get_user_credentials(&self, name: &str) -> Result<Option<(User, String)>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, name, is_admin, created_at, password_hash FROM users WHERE name=?1",
+            (name,),
+            |row| Ok((user_from_row(row)?, row.get(4)?)),
+        )
+        .optional()
+    }
+
+    /// Stores the hash of a new token. Session tokens expire after `expires_in_days`.
+    pub fn create_token(
+        &self,
+        user_id: usize,
+        kind: TokenKind,
+        token_hash: &str,
+        label: &str,
+        expires_in_days: Option<i64>,
+    ) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO auth_tokens (token_hash, user_id, kind, label, expires_at)
+            VALUES (?1, ?2, ?3, ?4, CASE WHEN ?5 IS NULL THEN NULL ELSE datetime('now', ?5 || ' days') END)",
+            (token_hash, user_id, kind.as_str(), label, expires_in_days),
+        )?;
+        Ok(conn.last_insert_rowid() as usize)
+    }
+
+    /// The owner of an unexpired token, noting that the token was used.
+    pub fn get_user_by_token(&self, token_hash: &str) -> Result<Option<User>> {
+        let conn = self.conn.lock().unwrap();
+        let user = conn
+            .query_row(
+                "SELECT u.id, u.name, u.is_admin, u.created_at FROM auth_tokens AS t
+                JOIN users AS u ON u.id = t.user_id
+                WHERE t.token_hash=?1 AND (t.expires_at IS NULL OR t.expires_at > datetime('now'))",
+                (token_hash,),
+                user_from_row,
+            )
+            .optional()?;
+        if user.is_some() {
+            conn.execute(
+                "UPDATE auth_tokens SET last_used_at=datetime('now') WHERE token_hash=?1",
+                (token_hash,),
+            )?;
+        }
+        Ok(user)
+    }
+
+    /// Ends a session. API tokens are left alone, even when one is presented the way a session is.
+    pub fn delete_session(&self, token_hash: &str) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM auth_tokens WHERE token_hash=?1 AND kind=?2",
+            (token_hash, TokenKind::Session.as_str()),
+        )
+    }
+
+    /// The user's API tokens, newest first.
+    pub fn get_api_tokens(&self, user_id: usize) -> Result<Vec<ApiToken>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, label, created_at, last_used_at FROM auth_tokens
+            WHERE user_id=?1 AND kind=?2 ORDER BY id DESC",
+        )?;
+        let tokens = stmt.query_map((user_id, TokenKind::Api.as_str()), |row| {
+            Ok(ApiToken {
+                id: row.get(0)?,
+                label: row.get(1)?,
+                created_at: row.get(2)?,
+                last_used_at: row.get(3)?,
+            })
+        })?;
+        tokens.collect()
+    }
+
+    /// Revokes one of the user's API tokens. Returns 0 when the user has no such token.
+    pub fn delete_api_token(&self, user_id: usize, id: usize) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM auth_tokens WHERE id=?1 AND user_id=?2 AND kind=?3",
+            (id, user_id, TokenKind::Api.as_str()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_account_is_admin_and_claims_default_picks() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let event = store.add_event("UFC 1", "2025-01-01", "").unwrap();
+        store.add_or_update_prediction(event, "A", "B").unwrap();
+
+        let admin = store.create_user("alice", "hash").unwrap();
+        let other = store.create_user("bob", "hash").unwrap();
+        assert!(admin.is_admin);
+        assert!(!other.is_admin);
+        assert_eq!(
+            store.get_predictions_for_user(event, "alice").unwrap(),
+            vec![("A".to_string(), "B".to_string())]
+        );
+        assert!(store.get_predictions(event).unwrap().is_empty());
+        assert!(store.create_user("bob", "hash").is_err());
+        assert_eq!(
+            store.get_user_credentials("bob").unwrap().unwrap(),
+            (other, "hash".to_string())
+        );
+    }
+
+    #[test]
+    fn tokens_expire_and_can_be_revoked() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let user = store.create_user("alice", "hash").unwrap();
+        store
+            .create_token(user.id, TokenKind::Session, "live", "", Some(30))
+            .unwrap();
+        store
+            .create_token(user.id, TokenKind::Session, "stale", "", Some(-1))
+            .unwrap();
+        let api = store
+            .create_token(user.id, TokenKind::Api, "script", "cron", None)
+            .unwrap();
+
+        assert_eq!(store.get_user_by_token("live").unwrap(), Some(user.clone()));
+        assert_eq!(store.get_user_by_token("stale").unwrap(), None);
+        assert_eq!(
+            store.get_user_by_token("script").unwrap(),
+            Some(user.clone())
+        );
+        let tokens = store.get_api_tokens(user.id).unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert!(tokens[0].last_used_at.is_some());
+
+        // signing out with an API token doesn't revoke it
+        assert_eq!(store.delete_session("script").unwrap(), 0);
+        assert_eq!(store.delete_api_token(user.id + 1, api).unwrap(), 0);
+        assert_eq!(store.delete_api_token(user.id, api).unwrap(), 1);
+        assert_eq!(store.get_user_by_token("script").unwrap(), None);
+        store.delete_session("live").unwrap();
+        assert_eq!(store.get_user_by_token("live").unwrap(), None);
+    }
+}
@@ -0,0 +1,302 @@
+use super::{names, Bout, Store};
+use rusqlite::{OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Another spelling of a fighter's name, recorded when an admin merges it into `name`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FighterAlias {
+    pub alias: String,
+    pub name: String,
+    pub created_at: String,
+}
+
+// every column holding a fighter's name, rewritten when two spellings are merged
+const NAME_COLUMNS: [(&str, &str); 14] = [
+    ("bouts", "fighter1"),
+    ("bouts", "fighter2"),
+    ("bouts", "winner"),
+    ("results", "winner"),
+    ("results", "loser"),
+    ("predictions", "winner"),
+    ("predictions", "loser"),
+    ("ticket_legs", "winner"),
+    ("ticket_legs", "loser"),
+    ("contest_picks", "winner"),
+    ("contest_picks", "loser"),
+    ("result_overrides", "winner"),
+    ("result_overrides", "loser"),
+    ("result_overrides", "previous_winner"),
+];
+
+impl Store {
+    /// The spelling `name` is stored under: the name it's an alias of, or on the event's card, the
+    /// spelling of the same fighter already there. Otherwise `name` as it is.
+    pub fn resolve_fighter_name(&self, event_id: Option<usize>, name: &str) -> Result<String> {
+        let name = name.trim();
+        {
+            let conn = self.conn.lock().unwrap();
+            let aliased: Option<String> = conn
+                .query_row(
+                    "SELECT name FROM fighter_aliases WHERE key=?1",
+                    (names::normalize(name),),
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if let Some(aliased) = aliased {
+                return Ok(aliased);
+            }
+        }
+        let Some(event_id) = event_id else {
+            return Ok(name.to_string());
+        };
+        let on_card = self
+            .get_bouts(event_id)?
+            .into_iter()
+            .flat_map(|bout| [bout.fighter1, bout.fighter2])
+            .collect::<Vec<_>>();
+        if on_card.iter().any(|fighter| fighter == name) {
+            return Ok(name.to_string());
+        }
+        Ok(on_card
+            .into_iter()
+            .find(|fighter| names::same_fighter(fighter, name))
+            .unwrap_or_else(|| name.to_string()))
+    }
+
+    /// The bout with its fighters and winner under the spellings they're stored as.
+    pub(super) fn resolve_bout_names(&self, bout: &Bout) -> Result<Bout> {
+        let event_id = Some(bout.event_id);
+        let fighter1 = self.resolve_fighter_name(event_id, &bout.fighter1)?;
+        let mut fighter2 = self.resolve_fighter_name(event_id, &bout.fighter2)?;
+        // two names that look alike on one bout are two fighters
+        if fighter2 == fighter1 {
+            fighter2 = bout.fighter2.trim().to_string();
+        }
+        let winner = match bout.winner.as_deref() {
+            Some(winner) if winner == bout.fighter1 => Some(fighter1.clone()),
+            Some(winner) if winner == bout.fighter2 => Some(fighter2.clone()),
+            Some(winner) => Some(self.resolve_fighter_name(event_id, winner)?),
+            None => None,
+        };
+        Ok(Bout {
+            fighter1,
+            fighter2,
+            winner,
+            ..bout.clone()
+        })
+    }
+
+    /// Records `from` as another spelling of `to`, and rewrites every bout, result, pick, ticket
+    /// leg and correction that uses it. Where both spellings were stored for the same thing, the
+    /// one under `to` is kept. Returns how many rows were rewritten.
+    pub fn merge_fighter_names(&self, from: &str, to: &str) -> Result<usize> {
+        let (from, to) = (from.trim(), to.trim());
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO fighter_aliases (key, alias, name) VALUES (?1, ?2, ?3)",
+            (names::normalize(from), from, to),
+        )?;
+        // spellings already merged into `from` follow it
+        tx.execute(
+            "UPDATE fighter_aliases SET name=?2 WHERE name=?1",
+            (from, to),
+        )?;
+        let mut rewritten = 0;
+        for (table, column) in NAME_COLUMNS {
+            rewritten += tx.execute(
+                &format!("UPDATE OR IGNORE {table} SET {column}=?2 WHERE {column}=?1"),
+                (from, to),
+            )?;
+            tx.execute(&format!("DELETE FROM {table} WHERE {column}=?1"), (from,))?;
+        }
+        tx.commit()?;
+        Ok(rewritten)
+    }
+
+    pub fn get_fighter_aliases(&self) -> Result<Vec<FighterAlias>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT alias, name, created_at FROM fighter_aliases ORDER BY name, alias")?;
+        let aliases = stmt.query_map([], |row| {
+            Ok(FighterAlias {
+                alias: row.get(0)?,
+                name: row.get(1)?,
+                created_at: row.get(2)?,
+            })
+        })?;
+        aliases.collect()
+    }
+
+    /// Pairs of stored names that look like the same fighter, for an admin to merge.
+    pub fn suggest_fighter_merges(&self) -> Result<Vec<(String, String)>> {
+        let stored = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT fighter1 FROM bouts UNION SELECT fighter2 FROM bouts
+                UNION SELECT winner FROM results UNION SELECT loser FROM results
+                UNION SELECT winner FROM predictions UNION SELECT loser FROM predictions",
+            )?;
+            let stored = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            stored.collect::<Result<Vec<_>>>()?
+        };
+        // only names with the same surname can match, so only those are compared
+        let mut by_surname = HashMap::<String, Vec<String>>::new();
+        for name in stored {
+            let normalized = names::normalize(&name);
+            let surname = normalized
+                .split_once(' ')
+                .map_or(normalized.as_str(), |(_, rest)| rest)
+                .to_string();
+            by_surname.entry(surname).or_default().push(name);
+        }
+        let mut suggestions = Vec::new();
+        for group in by_surname.values() {
+            for (i, a) in group.iter().enumerate() {
+                for b in &group[i + 1..] {
+                    if names::same_fighter(a, b) {
+                        let (a, b) = if a < b { (a, b) } else { (b, a) };
+                        suggestions.push((a.clone(), b.clone()));
+                    }
+                }
+            }
+        }
+        suggestions.sort();
+        Ok(suggestions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{BoutOutcome, DEFAULT_USER};
+
+    #[test]
+    fn stores_other_spellings_under_the_cards_name() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let id = store.add_event("UFC 300", "2024-04-13", "manual").unwrap();
+        store
+            .add_bout(id, "Alexander Volkanovski", "Ilia Topuria", None)
+            .unwrap();
+        store
+            .add_or_update_prediction(id, "Alex Volkanovski", "Ilia Topuria")
+            .unwrap();
+        store
+            .record_bout(&Bout {
+                event_id: id,
+                fighter1: "alex volkanovski".to_string(),
+                fighter2: "Ilia Topuria".to_string(),
+                outcome: Some(BoutOutcome::Win),
+                winner: Some("Ilia Topuria".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(store.get_bouts(id).unwrap().len(), 1);
+        assert_eq!(
+            store.get_predictions_for_user(id, DEFAULT_USER).unwrap(),
+            vec![(
+                "Alexander Volkanovski".to_string(),
+                "Ilia Topuria".to_string()
+            )]
+        );
+        assert_eq!(
+            store.get_results(id).unwrap(),
+            vec![(
+                "Ilia Topuria".to_string(),
+                "Alexander Volkanovski".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn stores_ticket_legs_and_contest_picks_under_the_cards_name() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let id = store.add_event("UFC 300", "2024-04-13", "manual").unwrap();
+        store
+            .add_bout(id, "José Aldo", "Jonathan Martinez", None)
+            .unwrap();
+        let (winner, loser) = ("Jose Aldo".to_string(), "jonathan martinez".to_string());
+
+        store
+            .create_ticket(
+                DEFAULT_USER,
+                10.0,
+                &[crate::db::TicketLeg {
+                    event_id: id,
+                    winner: winner.clone(),
+                    loser: loser.clone(),
+                    odds: 2.0,
+                    ..Default::default()
+                }],
+            )
+            .unwrap();
+        let leg = &store.get_tickets(DEFAULT_USER).unwrap()[0].legs[0];
+        assert_eq!(
+            (leg.winner.as_str(), leg.loser.as_str()),
+            ("José Aldo", "Jonathan Martinez")
+        );
+
+        let contest = store
+            .create_contest("Survivor", crate::db::ContestKind::Survivor)
+            .unwrap();
+        store
+            .set_contest_picks(
+                contest,
+                "friend",
+                id,
+                &[crate::db::ContestPick {
+                    event_id: id,
+                    user: "friend".to_string(),
+                    winner,
+                    loser,
+                    confidence: None,
+                }],
+            )
+            .unwrap();
+        let (pick, _) = &store.get_contest_picks(contest, Some("friend")).unwrap()[0];
+        assert_eq!(
+            (pick.winner.as_str(), pick.loser.as_str()),
+            ("José Aldo", "Jonathan Martinez")
+        );
+        assert_eq!(
+            store.get_predictions_for_user(id, "friend").unwrap(),
+            vec![("José Aldo".to_string(), "Jonathan Martinez".to_string())]
+        );
+    }
+
+    #[test]
+    fn merges_spellings_everywhere() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let first = store.add_event("UFC 1", "2024-01-01", "manual").unwrap();
+        let second = store.add_event("UFC 2", "2024-02-01", "manual").unwrap();
+        store.add_bout(first, "Jose Aldo", "A", None).unwrap();
+        store.add_bout(second, "José Aldo", "B", None).unwrap();
+        store
+            .add_or_update_prediction(first, "Jose Aldo", "A")
+            .unwrap();
+        assert_eq!(
+            store.suggest_fighter_merges().unwrap(),
+            vec![("Jose Aldo".to_string(), "José Aldo".to_string())]
+        );
+
+        assert_eq!(
+            store.merge_fighter_names("Jose Aldo", "José Aldo").unwrap(),
+            2
+        );
+        assert!(store.suggest_fighter_merges().unwrap().is_empty());
+        assert_eq!(store.get_bouts(first).unwrap()[0].fighter1, "José Aldo");
+        assert_eq!(
+            store.get_predictions_for_user(first, DEFAULT_USER).unwrap(),
+            vec![("José Aldo".to_string(), "A".to_string())]
+        );
+        // later cards use the merged spelling too
+        let third = store.add_event("UFC 3", "2024-03-01", "manual").unwrap();
+        store.add_bout(third, "jose aldo", "C", None).unwrap();
+        assert_eq!(store.get_bouts(third).unwrap()[0].fighter1, "José Aldo");
+        assert_eq!(store.get_fighter_aliases().unwrap()[0].alias, "Jose Aldo");
+    }
+}
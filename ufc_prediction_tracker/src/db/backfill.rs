@@ -0,0 +1,159 @@
+use super::{dates, Store};
+use chrono::NaiveDate;
+use rusqlite::Result;
+use serde::{Deserialize, Serialize};
+
+/// Where a backfill over a date range stands. Events are queued once and stay queued across runs,
+/// so an interrupted backfill picks up with whatever is still pending or failed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportProgress {
+    pub total: usize,
+    pub done: usize,
+    pub failed: usize,
+    pub pending: usize,
+}
+
+impl ImportProgress {
+    pub fn is_complete(&self) -> bool {
+        self.pending == 0 && self.failed == 0
+    }
+}
+
+impl Store {
+    /// Queues an event for import. Events already queued keep their status.
+    pub fn queue_import(&self, event_link: &str, event_id: usize) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR IGNORE INTO import_queue (event_link, event_id) VALUES (?1, ?2)",
+            (event_link, event_id),
+        )
+    }
+
+    /// Queued events in the range that haven't been imported yet, oldest first, as (event_id, link).
+    pub fn get_unfinished_imports(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(usize, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT q.event_id, q.event_link FROM import_queue AS q
+            JOIN events AS e ON e.id = q.event_id
+            WHERE q.status != 'done' AND e.date BETWEEN ?1 AND ?2
+            ORDER BY e.date, e.id",
+        )?;
+        let rows = stmt.query_map(
+            (
+                from.format(dates::ISO_DATE).to_string(),
+                to.format(dates::ISO_DATE).to_string(),
+            ),
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        rows.collect()
+    }
+
+    pub fn mark_import_done(&self, event_link: &str) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE import_queue SET status='done', error=NULL, updated_at=datetime('now') WHERE event_link=?1",
+            (event_link,),
+        )
+    }
+
+    pub fn mark_import_failed(&self, event_link: &str, error: &str) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE import_queue SET status='failed', error=?2, updated_at=datetime('now') WHERE event_link=?1",
+            (event_link, error),
+        )
+    }
+
+    pub fn get_import_progress(&self, from: NaiveDate, to: NaiveDate) -> Result<ImportProgress> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT count(*),
+                COALESCE(SUM(q.status = 'done'), 0),
+                COALESCE(SUM(q.status = 'failed'), 0),
+                COALESCE(SUM(q.status = 'pending'), 0)
+            FROM import_queue AS q
+            JOIN events AS e ON e.id = q.event_id
+            WHERE e.date BETWEEN ?1 AND ?2",
+            (
+                from.format(dates::ISO_DATE).to_string(),
+                to.format(dates::ISO_DATE).to_string(),
+            ),
+            |row| {
+                Ok(ImportProgress {
+                    total: row.get(0)?,
+                    done: row.get(1)?,
+                    failed: row.get(2)?,
+                    pending: row.get(3)?,
+                })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Bout, BoutOutcome};
+
+    fn date(raw: &str) -> NaiveDate {
+        dates::parse_event_date(raw).unwrap()
+    }
+
+    #[test]
+    fn progress_survives_reruns() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let first = store.add_event("UFC 1", "2024-01-13", "").unwrap();
+        let second = store.add_event("UFC 2", "2024-02-10", "").unwrap();
+        store.queue_import("link1", first).unwrap();
+        store.queue_import("link2", second).unwrap();
+        let (from, to) = (date("2024-01-01"), date("2024-12-31"));
+
+        store
+            .record_bout(&Bout {
+                event_id: first,
+                fighter1: "A".to_string(),
+                fighter2: "B".to_string(),
+                outcome: Some(BoutOutcome::Win),
+                winner: Some("A".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        store.mark_import_done("link1").unwrap();
+        store.mark_import_failed("link2", "timed out").unwrap();
+        // queueing again on a later run keeps the recorded status
+        store.queue_import("link1", first).unwrap();
+
+        assert_eq!(
+            store.get_import_progress(from, to).unwrap(),
+            ImportProgress {
+                total: 2,
+                done: 1,
+                failed: 1,
+                pending: 0
+            }
+        );
+        assert_eq!(
+            store.get_unfinished_imports(from, to).unwrap(),
+            vec![(second, "link2".to_string())]
+        );
+        assert_eq!(
+            store
+                .get_import_progress(date("2024-02-01"), to)
+                .unwrap()
+                .total,
+            1
+        );
+        store.add_or_update_prediction(first, "A", "B").unwrap();
+        assert_eq!(
+            store
+                .get_my_predictions_correctness_for_event(first)
+                .unwrap(),
+            (1, 0)
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use super::Store;
+use rusqlite::{Result, Row};
+use serde::{Deserialize, Serialize};
+
+/// One band of a model's calibration: of the scored bouts where the model gave its favourite a win
+/// probability in `[lower, upper)`, how often the favourite actually won.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationBucket {
+    pub lower: f64,
+    pub upper: f64,
+    pub bouts: usize,
+    pub mean_predicted: f64,
+    pub observed: f64,
+}
+
+/// How a prediction model did when replayed over every recorded result.
+///
+/// `model` is the pseudo-user the model's picks are recorded under. Only bouts that ended in a win
+/// are scored; a coin-flip pick counts as half right.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub model: String,
+    pub bouts: usize,
+    pub accuracy: f64,
+    pub log_loss: f64,
+    pub calibration: Vec<CalibrationBucket>,
+    /// When the report was stored, `None` until then.
+    pub run_at: Option<String>,
+}
+
+fn report_from_row(row: &Row<'_>) -> Result<BacktestReport> {
+    let calibration: String = row.get(4)?;
+    Ok(BacktestReport {
+        model: row.get(0)?,
+        bouts: row.get(1)?,
+        accuracy: row.get(2)?,
+        log_loss: row.get(3)?,
+        calibration: serde_json::from_str(&calibration).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        run_at: row.get(5)?,
+    })
+}
+
+impl Store {
+    /// Stores the report, replacing the model's previous one.
+    pub fn save_backtest(&self, report: &BacktestReport) -> Result<usize> {
+        let calibration = serde_json::to_string(&report.calibration)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO backtests (model, bouts, accuracy, log_loss, calibration, run_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'))",
+            (
+                &report.model,
+                report.bouts,
+                report.accuracy,
+                report.log_loss,
+                calibration,
+            ),
+        )
+    }
+
+    /// The latest report for every model that has been backtested, by model name.
+    pub fn get_backtests(&self) -> Result<Vec<BacktestReport>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT model, bouts, accuracy, log_loss, calibration, run_at FROM backtests ORDER BY model",
+        )?;
+        let reports = stmt.query_map((), report_from_row)?;
+        reports.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_replace_earlier_runs() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let mut report = BacktestReport {
+            model: "model:test".to_string(),
+            bouts: 10,
+            accuracy: 0.6,
+            log_loss: 0.65,
+            calibration: vec![CalibrationBucket {
+                lower: 0.5,
+                upper: 0.6,
+                bouts: 10,
+                mean_predicted: 0.55,
+                observed: 0.6,
+            }],
+            run_at: None,
+        };
+        store.save_backtest(&report).unwrap();
+        report.bouts = 12;
+        store.save_backtest(&report).unwrap();
+
+        let stored = store.get_backtests().unwrap();
+        assert_eq!(stored.len(), 1);
+        assert!(stored[0].run_at.is_some());
+        assert_eq!(
+            BacktestReport {
+                run_at: None,
+                ..stored[0].clone()
+            },
+            report
+        );
+    }
+}
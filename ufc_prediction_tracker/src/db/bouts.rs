@@ -0,0 +1,263 @@
+use super::Store;
+use rusqlite::{OptionalExtension, Result, Row};
+use serde::{Deserialize, Serialize};
+
+/// A fight on an event's card. `position` is the order on the card, 0 being the main event.
+///
+/// `outcome` is `None` until the bout has happened; `winner` is only set when the outcome is a win.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bout {
+    pub event_id: usize,
+    pub position: usize,
+    pub fighter1: String,
+    pub fighter2: String,
+    pub fighter1_link: Option<String>,
+    pub fighter2_link: Option<String>,
+    pub weight_class: Option<String>,
+    pub outcome: Option<BoutOutcome>,
+    pub winner: Option<String>,
+    pub method: Option<String>,
+    pub round: Option<u32>,
+    pub time: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BoutOutcome {
+    Win,
+    Draw,
+    NoContest,
+}
+
+impl BoutOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BoutOutcome::Win => "win",
+            BoutOutcome::Draw => "draw",
+            BoutOutcome::NoContest => "nc",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "win" => Some(BoutOutcome::Win),
+            "draw" => Some(BoutOutcome::Draw),
+            "nc" => Some(BoutOutcome::NoContest),
+            _ => None,
+        }
+    }
+}
+
+impl Bout {
+    pub fn loser(&self) -> Option<&str> {
+        let winner = self.winner.as_deref()?;
+        if winner == self.fighter1 {
+            Some(&self.fighter2)
+        } else {
+            Some(&self.fighter1)
+        }
+    }
+
+    pub fn involves(&self, fighter: &str) -> bool {
+        self.fighter1 == fighter || self.fighter2 == fighter
+    }
+}
+
+const BOUT_COLUMNS: &str = "event_id, position, fighter1, fighter2, fighter1_link, fighter2_link, weight_class, outcome, winner, method, round, time";
+
+/// [`BOUT_COLUMNS`] qualified for queries that join `bouts AS b` with other tables.
+pub(super) const BOUT_COLUMNS_B: &str = "b.event_id, b.position, b.fighter1, b.fighter2, b.fighter1_link, b.fighter2_link, b.weight_class, b.outcome, b.winner, b.method, b.round, b.time";
+
+/// Reads a bout selected with [`BOUT_COLUMNS`] starting at column `offset`.
+pub(super) fn bout_from_row_at(row: &Row<'_>, offset: usize) -> Result<Bout> {
+    Ok(Bout {
+        event_id: row.get(offset)?,
+        position: row.get(offset + 1)?,
+        fighter1: row.get(offset + 2)?,
+        fighter2: row.get(offset + 3)?,
+        fighter1_link: row.get(offset + 4)?,
+        fighter2_link: row.get(offset + 5)?,
+        weight_class: row.get(offset + 6)?,
+        outcome: row
+            .get::<_, Option<String>>(offset + 7)?
+            .as_deref()
+            .and_then(BoutOutcome::parse),
+        winner: row.get(offset + 8)?,
+        method: row.get(offset + 9)?,
+        round: row.get(offset + 10)?,
+        time: row.get(offset + 11)?,
+    })
+}
+
+impl Store {
+    /// Inserts the bout, or updates it if the same pairing (in either order) is already on the event.
+    ///
+    /// Fields the new bout leaves empty keep their stored values, so re-reading a card before the
+    /// fights doesn't erase a result or fighter link recorded earlier. Fighters are stored under the
+    /// spelling [`Store::resolve_fighter_name`] gives.
+    pub fn upsert_bout(&self, bout: &Bout) -> Result<usize> {
+        let bout = &self.resolve_bout_names(bout)?;
+        let conn = self.conn.lock().unwrap();
+        let existing: Option<usize> = conn
+            .query_row(
+                "SELECT id FROM bouts WHERE event_id=?1
+                AND ((fighter1=?2 AND fighter2=?3) OR (fighter1=?3 AND fighter2=?2))",
+                (bout.event_id, &bout.fighter1, &bout.fighter2),
+                |row| row.get(0),
+            )
+            .optional()?;
+        let params = (
+            bout.event_id,
+            bout.position,
+            &bout.fighter1,
+            &bout.fighter2,
+            &bout.fighter1_link,
+            &bout.fighter2_link,
+            &bout.weight_class,
+            bout.outcome.map(|o| o.as_str()),
+            &bout.winner,
+            &bout.method,
+            bout.round,
+            &bout.time,
+        );
+        match existing {
+            Some(id) => {
+                conn.execute(
+                    &format!(
+                        "UPDATE bouts SET event_id=?1, position=?2, fighter1=?3, fighter2=?4,
+                        fighter1_link=COALESCE(?5, fighter1_link), fighter2_link=COALESCE(?6, fighter2_link),
+                        weight_class=COALESCE(?7, weight_class), outcome=COALESCE(?8, outcome),
+                        winner=COALESCE(?9, winner), method=COALESCE(?10, method),
+                        round=COALESCE(?11, round), time=COALESCE(?12, time)
+                        WHERE id={id}"
+                    ),
+                    params,
+                )?;
+                Ok(id)
+            }
+            None => {
+                conn.execute(
+                    &format!("INSERT INTO bouts ({BOUT_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)"),
+                    params,
+                )?;
+                Ok(conn.last_insert_rowid() as usize)
+            }
+        }
+    }
+
+    /// The event's bouts in card order.
+    pub fn get_bouts(&self, event_id: usize) -> Result<Vec<Bout>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {BOUT_COLUMNS} FROM bouts WHERE event_id=?1 ORDER BY position"
+        ))?;
+        let bouts = stmt.query_map((event_id,), |row| bout_from_row_at(row, 0))?;
+        bouts.collect()
+    }
+
+    /// Bouts that ended in a win or draw, oldest event first, with their event dates. Only events strictly
+    /// before `before` are included when it's given.
+    pub fn get_decided_bouts(&self, before: Option<&str>) -> Result<Vec<(String, Bout)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT e.date, {BOUT_COLUMNS_B} FROM bouts AS b JOIN events AS e ON e.id = b.event_id
+            WHERE b.outcome IN ('win', 'draw') AND (?1 IS NULL OR e.date < ?1)
+            ORDER BY e.date, e.id, b.position DESC"
+        ))?;
+        let bouts = stmt.query_map((before,), |row| {
+            Ok((row.get(0)?, bout_from_row_at(row, 1)?))
+        })?;
+        bouts.collect()
+    }
+
+    /// Stores a bout along with its result, so decided bouts are also scored against predictions. A
+    /// result an admin has corrected is kept over the scraped one.
+    ///
+    /// Unlike [`Store::upsert_bout`], the bout is taken as the source's last word on the result: a
+    /// bout reported undecided loses any result stored for it, and a draw or no contest loses its
+    /// winner.
+    pub fn record_bout(&self, bout: &Bout) -> Result<usize> {
+        let bout = &self.resolve_bout_names(bout)?;
+        if self.is_result_overridden(bout.event_id, &bout.fighter1, &bout.fighter2)? {
+            return self.upsert_bout(&Bout {
+                outcome: None,
+                winner: None,
+                ..bout.clone()
+            });
+        }
+        let id = self.upsert_bout(bout)?;
+        match (bout.winner.as_deref(), bout.loser()) {
+            (Some(winner), Some(loser)) => {
+                self.add_or_update_result(bout.event_id, winner, loser)?;
+            }
+            _ if bout.outcome.is_none() => {
+                self.clear_bout_result(bout.event_id, &bout.fighter1, &bout.fighter2)?
+            }
+            _ => self.clear_bout_winner(bout.event_id, &bout.fighter1, &bout.fighter2)?,
+        }
+        Ok(id)
+    }
+
+    // a draw or no contest has no winner, and no row in results
+    fn clear_bout_winner(&self, event_id: usize, fighter1: &str, fighter2: &str) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE bouts SET winner=NULL
+            WHERE event_id=?1 AND ((fighter1=?2 AND fighter2=?3) OR (fighter1=?3 AND fighter2=?2))",
+            (event_id, fighter1, fighter2),
+        )?;
+        tx.execute(
+            "DELETE FROM results WHERE event_id=?1
+            AND ((winner=?2 AND loser=?3) OR (winner=?3 AND loser=?2))",
+            (event_id, fighter1, fighter2),
+        )?;
+        tx.commit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recording_a_bout_again_replaces_its_result() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let bout = Bout {
+            event_id: 1,
+            fighter1: "A".to_string(),
+            fighter2: "B".to_string(),
+            outcome: Some(BoutOutcome::Win),
+            winner: Some("A".to_string()),
+            method: Some("KO/TKO".to_string()),
+            ..Default::default()
+        };
+        store.record_bout(&bout).unwrap();
+        assert_eq!(store.get_results(1).unwrap().len(), 1);
+
+        store
+            .record_bout(&Bout {
+                outcome: Some(BoutOutcome::NoContest),
+                winner: None,
+                ..bout.clone()
+            })
+            .unwrap();
+        let stored = &store.get_bouts(1).unwrap()[0];
+        assert_eq!(
+            (stored.outcome, stored.winner.as_deref()),
+            (Some(BoutOutcome::NoContest), None)
+        );
+        assert!(store.get_results(1).unwrap().is_empty());
+
+        store
+            .record_bout(&Bout {
+                outcome: None,
+                winner: None,
+                method: None,
+                ..bout
+            })
+            .unwrap();
+        let stored = &store.get_bouts(1).unwrap()[0];
+        assert_eq!((stored.outcome, stored.method.as_deref()), (None, None));
+    }
+}
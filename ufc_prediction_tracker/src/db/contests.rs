@@ -0,0 +1,307 @@
+use super::{db::save_prediction, Store};
+use rusqlite::{OptionalExtension, Result, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContestKind {
+    /// One fighter per event, never the same fighter twice. A losing pick knocks the user out.
+    #[default]
+    Survivor,
+    /// Every pick on a card gets a distinct confidence from 1 to the number of bouts, and a correct
+    /// pick scores its confidence.
+    Confidence,
+}
+
+impl ContestKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContestKind::Survivor => "survivor",
+            ContestKind::Confidence => "confidence",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "survivor" => Some(ContestKind::Survivor),
+            "confidence" => Some(ContestKind::Confidence),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Contest {
+    pub id: usize,
+    pub name: String,
+    pub kind: ContestKind,
+    pub created_at: String,
+}
+
+/// A pick made for a contest. `confidence` is only set in confidence pools.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContestPick {
+    pub event_id: usize,
+    pub user: String,
+    pub winner: String,
+    pub loser: String,
+    pub confidence: Option<u32>,
+}
+
+/// A user's place in a contest.
+///
+/// In a survivor pool `points` is how many events the user survived and `eliminated_at` the event that
+/// knocked them out; in a confidence pool it's the summed confidence of their correct picks.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Standing {
+    pub user: String,
+    pub points: u32,
+    pub correct: u32,
+    pub incorrect: u32,
+    pub eliminated_at: Option<usize>,
+}
+
+fn contest_from_row(row: &Row<'_>) -> Result<Contest> {
+    Ok(Contest {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        kind: ContestKind::parse(&row.get::<_, String>(2)?).unwrap_or_default(),
+        created_at: row.get(3)?,
+    })
+}
+
+impl Store {
+    pub fn create_contest(&self, name: &str, kind: ContestKind) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO contests (name, kind) VALUES (?1, ?2)",
+            (name, kind.as_str()),
+        )?;
+        Ok(conn.last_insert_rowid() as usize)
+    }
+
+    /// Every contest, newest first.
+    pub fn get_contests(&self) -> Result<Vec<Contest>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt =
+            conn.prepare("SELECT id, name, kind, created_at FROM contests ORDER BY id DESC")?;
+        let contests = stmt.query_map((), contest_from_row)?;
+        contests.collect()
+    }
+
+    pub fn get_contest(&self, id: usize) -> Result<Option<Contest>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, name, kind, created_at FROM contests WHERE id=?1",
+            (id,),
+            contest_from_row,
+        )
+        .optional()
+    }
+
+    /// The contest's picks, oldest event first, with the winner of each picked bout once it's known.
+    /// Only `user`'s picks are returned when one is given.
+    pub fn get_contest_picks(
+        &self,
+        contest_id: usize,
+        user: Option<&str>,
+    ) -> Result<Vec<(ContestPick, Option<String>)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT c.event_id, c.user, c.winner, c.loser, c.confidence, r.winner
+            FROM contest_picks AS c
+            JOIN events AS e ON e.id = c.event_id
+            LEFT JOIN results AS r ON r.event_id = c.event_id
+                AND ((r.winner = c.winner AND r.loser = c.loser) OR (r.winner = c.loser AND r.loser = c.winner))
+            WHERE c.contest_id=?1 AND (?2 IS NULL OR c.user = ?2)
+            ORDER BY e.date, e.id, c.confidence DESC",
+        )?;
+        let picks = stmt.query_map((contest_id, user), |row| {
+            Ok((
+                ContestPick {
+                    event_id: row.get(0)?,
+                    user: row.get(1)?,
+                    winner: row.get(2)?,
+                    loser: row.get(3)?,
+                    confidence: row.get(4)?,
+                },
+                row.get(5)?,
+            ))
+        })?;
+        picks.collect()
+    }
+
+    /// Replaces the user's picks for one event of the contest, and records each as their prediction.
+    /// Either all of it is saved or none is.
+    pub fn set_contest_picks(
+        &self,
+        contest_id: usize,
+        user: &str,
+        event_id: usize,
+        picks: &[ContestPick],
+    ) -> Result<()> {
+        let picks = picks
+            .iter()
+            .map(|pick| {
+                Ok(ContestPick {
+                    winner: self.resolve_fighter_name(Some(event_id), &pick.winner)?,
+                    loser: self.resolve_fighter_name(Some(event_id), &pick.loser)?,
+                    ..pick.clone()
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for pick in &picks {
+            save_prediction(&tx, event_id, &pick.winner, &pick.loser, user)?;
+        }
+        tx.execute(
+            "DELETE FROM contest_picks WHERE contest_id=?1 AND user=?2 AND event_id=?3",
+            (contest_id, user, event_id),
+        )?;
+        for pick in &picks {
+            tx.execute(
+                "INSERT INTO contest_picks (contest_id, user, event_id, winner, loser, confidence)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                (
+                    contest_id,
+                    user,
+                    event_id,
+                    &pick.winner,
+                    &pick.loser,
+                    pick.confidence,
+                ),
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Standings for the contest, leaders first.
+    pub fn get_standings(&self, contest: &Contest) -> Result<Vec<Standing>> {
+        let picks = self.get_contest_picks(contest.id, None)?;
+        Ok(standings(contest.kind, &picks))
+    }
+
+    pub fn get_user_contest_picks(
+        &self,
+        contest_id: usize,
+        user: &str,
+    ) -> Result<Vec<ContestPick>> {
+        Ok(self
+            .get_contest_picks(contest_id, Some(user))?
+            .into_iter()
+            .map(|(pick, _)| pick)
+            .collect())
+    }
+}
+
+/// Works out standings from picks in event order, as returned by [`Store::get_contest_picks`].
+pub fn standings(kind: ContestKind, picks: &[(ContestPick, Option<String>)]) -> Vec<Standing> {
+    let mut by_user = BTreeMap::<&str, Standing>::new();
+    for (pick, result) in picks {
+        let standing = by_user
+            .entry(pick.user.as_str())
+            .or_insert_with(|| Standing {
+                user: pick.user.clone(),
+                ..Default::default()
+            });
+        if standing.eliminated_at.is_some() {
+            continue;
+        }
+        match result {
+            Some(winner) if *winner == pick.winner => {
+                standing.correct += 1;
+                standing.points += match kind {
+                    ContestKind::Survivor => 1,
+                    ContestKind::Confidence => pick.confidence.unwrap_or(0),
+                };
+            }
+            Some(_) => {
+                standing.incorrect += 1;
+                if kind == ContestKind::Survivor {
+                    standing.eliminated_at = Some(pick.event_id);
+                }
+            }
+            None => {}
+        }
+    }
+    let mut standings = by_user.into_values().collect::<Vec<_>>();
+    standings.sort_by_key(|s| (s.eliminated_at.is_some(), std::cmp::Reverse(s.points)));
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::DEFAULT_USER;
+
+    fn pick(user: &str, event_id: usize, winner: &str, confidence: Option<u32>) -> ContestPick {
+        ContestPick {
+            event_id,
+            user: user.to_string(),
+            winner: winner.to_string(),
+            loser: format!("not {winner}"),
+            confidence,
+        }
+    }
+
+    #[test]
+    fn survivor_losers_drop_below_everyone_alive() {
+        let picks = [
+            (pick("a", 1, "X", None), Some("X".to_string())),
+            (pick("b", 1, "Y", None), Some("X".to_string())),
+            (pick("a", 2, "Z", None), Some("Z".to_string())),
+            (pick("c", 2, "Z", None), Some("Z".to_string())),
+            (pick("b", 3, "W", None), Some("W".to_string())),
+        ];
+        let standings = standings(ContestKind::Survivor, &picks);
+        let order = standings
+            .iter()
+            .map(|s| (s.user.as_str(), s.points, s.eliminated_at))
+            .collect::<Vec<_>>();
+        // b's win after being knocked out doesn't count
+        assert_eq!(
+            order,
+            vec![("a", 2, None), ("c", 1, None), ("b", 0, Some(1))]
+        );
+    }
+
+    #[test]
+    fn confidence_scores_rank_of_correct_picks() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let contest = store
+            .create_contest("Card pool", ContestKind::Confidence)
+            .unwrap();
+        let event = store.add_event("UFC 1", "2025-01-01", "").unwrap();
+        let picks = [
+            pick(DEFAULT_USER, event, "A", Some(2)),
+            pick(DEFAULT_USER, event, "C", Some(1)),
+        ];
+        store
+            .set_contest_picks(contest, DEFAULT_USER, event, &picks)
+            .unwrap();
+        store.add_or_update_result(event, "A", "not A").unwrap();
+        store.add_or_update_result(event, "not C", "C").unwrap();
+
+        let contest = store.get_contest(contest).unwrap().unwrap();
+        assert_eq!(contest.kind, ContestKind::Confidence);
+        assert_eq!(
+            store
+                .get_user_contest_picks(contest.id, DEFAULT_USER)
+                .unwrap(),
+            picks
+        );
+        assert_eq!(store.get_predictions(event).unwrap().len(), 2);
+        assert_eq!(
+            store.get_standings(&contest).unwrap(),
+            vec![Standing {
+                user: DEFAULT_USER.to_string(),
+                points: 2,
+                correct: 1,
+                incorrect: 1,
+                eliminated_at: None,
+            }]
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use chrono::{Local, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+/// The format event dates are stored in.
+pub const ISO_DATE: &str = "%Y-%m-%d";
+
+// ufcstats writes "November 15, 2025"; older rows or hand-entered events may use the other forms
+const ACCEPTED_DATE_FORMATS: [&str; 5] =
+    [ISO_DATE, "%B %d, %Y", "%b %d, %Y", "%m/%d/%Y", "%b. %d, %Y"];
+
+/// Parses a scraped or hand-entered event date.
+pub fn parse_event_date(raw: &str) -> Result<NaiveDate, chrono::ParseError> {
+    let raw = raw.trim();
+    let mut last_err = None;
+    for format in ACCEPTED_DATE_FORMATS {
+        match NaiveDate::parse_from_str(raw, format) {
+            Ok(date) => return Ok(date),
+            Err(e) => last_err = Some(e),
+        }
+    }
+    Err(last_err.unwrap())
+}
+
+/// Parses a start time such as "18:00" or "6:00 PM".
+pub fn parse_start_time(raw: &str) -> Result<NaiveTime, chrono::ParseError> {
+    let raw = raw.trim();
+    NaiveTime::parse_from_str(raw, "%H:%M").or_else(|_| NaiveTime::parse_from_str(raw, "%I:%M %p"))
+}
+
+/// The date past/upcoming classification is measured against.
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventTiming {
+    Past,
+    /// Today or later. An event happening today still accepts picks until it starts.
+    Upcoming,
+}
+
+impl EventTiming {
+    pub fn of(date: NaiveDate, today: NaiveDate) -> Self {
+        if date < today {
+            EventTiming::Past
+        } else {
+            EventTiming::Upcoming
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(raw: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(raw, ISO_DATE).unwrap()
+    }
+
+    #[test]
+    fn parses_scraped_formats() {
+        assert_eq!(
+            parse_event_date("November 15, 2025").unwrap(),
+            date("2025-11-15")
+        );
+        assert_eq!(
+            parse_event_date(" March 09, 2024 ").unwrap(),
+            date("2024-03-09")
+        );
+        assert_eq!(parse_event_date("Mar 9, 2024").unwrap(), date("2024-03-09"));
+        assert_eq!(parse_event_date("2024-03-09").unwrap(), date("2024-03-09"));
+        assert!(parse_event_date("next saturday").is_err());
+    }
+
+    #[test]
+    fn parses_start_times() {
+        assert_eq!(
+            parse_start_time("18:00").unwrap(),
+            NaiveTime::from_hms_opt(18, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_start_time("6:30 PM").unwrap(),
+            NaiveTime::from_hms_opt(18, 30, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn timing_across_month_boundaries() {
+        let today = date("2025-12-01");
+        assert_eq!(
+            EventTiming::of(date("2025-11-30"), today),
+            EventTiming::Past
+        );
+        assert_eq!(
+            EventTiming::of(date("2025-12-01"), today),
+            EventTiming::Upcoming
+        );
+        assert_eq!(
+            EventTiming::of(date("2025-12-02"), today),
+            EventTiming::Upcoming
+        );
+        // "December" < "November" as text, but not as a date
+        let today = date("2025-11-20");
+        assert_eq!(
+            EventTiming::of(date("2025-12-06"), today),
+            EventTiming::Upcoming
+        );
+        assert_eq!(
+            EventTiming::of(date("2025-02-01"), today),
+            EventTiming::Past
+        );
+        let today = date("2026-01-01");
+        assert_eq!(
+            EventTiming::of(date("2025-12-31"), today),
+            EventTiming::Past
+        );
+    }
+}
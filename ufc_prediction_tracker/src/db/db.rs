@@ -1,16 +1,115 @@
-use rusqlite::{Connection, Map, Result};
+use super::{dates, ScoreFilter};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use rusqlite::{Connection, OptionalExtension, Result};
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
-use tracing::event;
 
 pub type SharedConnection = Arc<Mutex<Connection>>;
 
+/// The user predictions are recorded under when no other user is specified.
+pub const DEFAULT_USER: &str = "me";
+
 pub fn create_shared_connection(path: &str) -> SharedConnection {
     Arc::new(Mutex::new(
         Connection::open(path).expect("Failed to open DB"),
     ))
 }
 
+/// An event row. `date` is YYYY-MM-DD.
+///
+/// ufcstats only publishes a location ("Las Vegas, Nevada, USA"), so for scraped events `venue` is
+/// left for an admin to fill in.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub id: usize,
+    pub name: String,
+    pub date: String,
+    pub link: String,
+    pub location: Option<String>,
+    pub venue: Option<String>,
+    pub country: Option<String>,
+    /// When the main card starts, HH:MM local to `timezone`.
+    pub start_time: Option<String>,
+    pub timezone: Option<String>,
+    /// When the prelims start, HH:MM local to `timezone`.
+    pub prelims_time: Option<String>,
+    /// Which promotion runs the event, e.g. "UFC" or "PFL".
+    pub promotion: String,
+}
+
+impl Event {
+    /// The date as ufcstats shows it, e.g. "November 15, 2025".
+    pub fn display_date(&self) -> String {
+        NaiveDate::parse_from_str(&self.date, dates::ISO_DATE)
+            .map(|d| d.format("%B %d, %Y").to_string())
+            .unwrap_or_else(|_| self.date.clone())
+    }
+
+    /// When picks for the event close: as the first bout starts, which is the prelims where their
+    /// start is known and the main card otherwise. Without either, the start of the event's day.
+    /// Local to `timezone` where it's known.
+    pub fn lock_time(&self) -> Option<NaiveDateTime> {
+        let date = dates::parse_event_date(&self.date).ok()?;
+        let start = [&self.prelims_time, &self.start_time]
+            .into_iter()
+            .flatten()
+            .filter_map(|raw| dates::parse_start_time(raw).ok())
+            .next()
+            .unwrap_or(NaiveTime::MIN);
+        Some(date.and_time(start))
+    }
+
+    /// `now` as a wall clock at the event shows it, to compare with [`Event::lock_time`]. The
+    /// server's local time where the event's timezone isn't known.
+    pub fn local_time(&self, now: DateTime<Utc>) -> NaiveDateTime {
+        match self.timezone.as_deref().map(str::parse::<chrono_tz::Tz>) {
+            Some(Ok(tz)) => now.with_timezone(&tz).naive_local(),
+            _ => now.with_timezone(&chrono::Local).naive_local(),
+        }
+    }
+
+    /// Venue and location joined for display, skipping whichever is missing.
+    pub fn place(&self) -> Option<String> {
+        match (&self.venue, &self.location) {
+            (Some(venue), Some(location)) => Some(format!("{venue}, {location}")),
+            (Some(place), None) | (None, Some(place)) => Some(place.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// The promotion of events scraped from ufcstats.
+pub const DEFAULT_PROMOTION: &str = "UFC";
+
+pub(super) const EVENT_COLUMNS: &str =
+    "e.id, e.name, e.date, e.link, e.location, e.venue, e.country, e.start_time, e.timezone, e.prelims_time, e.promotion";
+
+pub(super) fn event_from_row(row: &rusqlite::Row<'_>) -> Result<Event> {
+    Ok(Event {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        date: row.get(2)?,
+        link: row.get(3)?,
+        location: row.get(4)?,
+        venue: row.get(5)?,
+        country: row.get(6)?,
+        start_time: row.get(7)?,
+        timezone: row.get(8)?,
+        prelims_time: row.get(9)?,
+        promotion: row.get(10)?,
+    })
+}
+
+/// The country part of a ufcstats location, which is always its last comma-separated component.
+pub fn country_of(location: &str) -> Option<String> {
+    location
+        .rsplit(',')
+        .next()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(str::to_string)
+}
+
 #[derive(Clone)]
 pub struct Store {
     pub conn: SharedConnection,
@@ -30,6 +129,11 @@ impl Store {
             name TEXT NOT NULL,
             date DATE NOT NULL,
             link TEXT NOT NULL,
+            start_time TEXT,
+            timezone TEXT,
+            location TEXT,
+            venue TEXT,
+            country TEXT,
             unique (name,date)
         )",
             (),
@@ -48,10 +152,218 @@ impl Store {
             event_id INTEGER NOT NULL,
             winner TEXT NOT NULL,
             loser TEXT NOT NULL,
-            unique (event_id, winner, loser)
+            user TEXT NOT NULL DEFAULT 'me',
+            unique (event_id, winner, loser, user)
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS bouts (
+            id INTEGER PRIMARY KEY,
+            event_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            fighter1 TEXT NOT NULL,
+            fighter2 TEXT NOT NULL,
+            fighter1_link TEXT,
+            fighter2_link TEXT,
+            weight_class TEXT,
+            outcome TEXT,
+            winner TEXT,
+            method TEXT,
+            round INTEGER,
+            time TEXT,
+            unique (event_id, fighter1, fighter2)
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS fighters (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            nickname TEXT,
+            record TEXT,
+            height TEXT,
+            weight TEXT,
+            reach_inches REAL,
+            stance TEXT,
+            dob TEXT,
+            slpm REAL,
+            str_acc REAL,
+            sapm REAL,
+            str_def REAL,
+            td_avg REAL,
+            td_acc REAL,
+            td_def REAL,
+            sub_avg REAL,
+            recent_form TEXT,
+            fetched_at TEXT NOT NULL
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS import_queue (
+            event_link TEXT PRIMARY KEY,
+            event_id INTEGER NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            error TEXT,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS backtests (
+            model TEXT PRIMARY KEY,
+            bouts INTEGER NOT NULL,
+            accuracy REAL NOT NULL,
+            log_loss REAL NOT NULL,
+            calibration TEXT NOT NULL,
+            run_at TEXT NOT NULL
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tickets (
+            id INTEGER PRIMARY KEY,
+            user TEXT NOT NULL DEFAULT 'me',
+            stake REAL NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            payout REAL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            settled_at TEXT
         )",
             (),
         )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS ticket_legs (
+            ticket_id INTEGER NOT NULL REFERENCES tickets(id),
+            event_id INTEGER NOT NULL,
+            winner TEXT NOT NULL,
+            loser TEXT NOT NULL,
+            odds REAL NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending'
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS contests (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS contest_picks (
+            contest_id INTEGER NOT NULL REFERENCES contests(id),
+            user TEXT NOT NULL,
+            event_id INTEGER NOT NULL,
+            winner TEXT NOT NULL,
+            loser TEXT NOT NULL,
+            confidence INTEGER,
+            UNIQUE(contest_id, user, event_id, winner, loser)
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS users (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            password_hash TEXT NOT NULL,
+            is_admin INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS auth_tokens (
+            id INTEGER PRIMARY KEY,
+            token_hash TEXT NOT NULL UNIQUE,
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            kind TEXT NOT NULL,
+            label TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            expires_at TEXT,
+            last_used_at TEXT
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS result_overrides (
+            id INTEGER PRIMARY KEY,
+            event_id INTEGER NOT NULL,
+            outcome TEXT NOT NULL DEFAULT 'win',
+            winner TEXT NOT NULL,
+            loser TEXT NOT NULL,
+            previous_winner TEXT,
+            admin TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS notification_prefs (
+            user TEXT PRIMARY KEY,
+            webhook_url TEXT,
+            email TEXT,
+            desktop INTEGER NOT NULL DEFAULT 0,
+            event_lock INTEGER NOT NULL DEFAULT 1,
+            card_changed INTEGER NOT NULL DEFAULT 1,
+            results_in INTEGER NOT NULL DEFAULT 1
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS notifications (
+            id INTEGER PRIMARY KEY,
+            user TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            event_id INTEGER NOT NULL,
+            key TEXT NOT NULL DEFAULT '',
+            title TEXT NOT NULL,
+            body TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            sent_at TEXT,
+            UNIQUE(user, kind, event_id, key)
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS parser_health (
+            page TEXT PRIMARY KEY,
+            url TEXT NOT NULL,
+            healthy INTEGER NOT NULL,
+            items INTEGER NOT NULL,
+            diagnostics TEXT NOT NULL DEFAULT '',
+            checked_at TEXT NOT NULL DEFAULT (datetime('now')),
+            last_healthy_at TEXT
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS http_metrics (
+            host TEXT PRIMARY KEY,
+            requests INTEGER NOT NULL DEFAULT 0,
+            retries INTEGER NOT NULL DEFAULT 0,
+            failures INTEGER NOT NULL DEFAULT 0,
+            disallowed INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            last_success_at TEXT,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS fighter_aliases (
+            key TEXT PRIMARY KEY,
+            alias TEXT NOT NULL,
+            name TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+            (),
+        )?;
+        migrate(&conn)?;
         Ok(())
     }
     pub fn add_or_update_result(
@@ -60,6 +372,8 @@ impl Store {
         winner: &str,
         loser: &str,
     ) -> Result<usize> {
+        let winner = &self.resolve_fighter_name(Some(event_id), winner)?;
+        let loser = &self.resolve_fighter_name(Some(event_id), loser)?;
         let conn = self.conn.lock().unwrap();
         match conn.query_row(
             "SELECT event_id FROM results WHERE event_id=?1 AND winner=?3 AND loser=?2",
@@ -67,17 +381,24 @@ impl Store {
             |row: &rusqlite::Row<'_>| row.get::<_, usize>(0),
         ) {
             Ok(_) => conn.execute(
-                "UPDATE results set (event_id, winner, loser) = (?1, ?2, ?3) where winner=?3 and loser=?2",
+                "UPDATE results set (winner, loser) = (?2, ?3) where event_id=?1 and winner=?3 and loser=?2",
                 (event_id, winner, loser),
             ),
+            // the same result recorded again is left as it is
             Err(_) => conn.execute(
-                "INSERT INTO results (event_id, winner, loser) VALUES (?1, ?2, ?3)",
+                "INSERT OR IGNORE INTO results (event_id, winner, loser) VALUES (?1, ?2, ?3)",
                 (event_id, winner, loser),
             ),
         }
     }
 
+    // date is normalized to YYYY-MM-DD, so the same event scraped in different formats maps to one row
     pub fn add_event(&self, name: &str, date: &str, link: &str) -> Result<usize> {
+        let date = dates::parse_event_date(date)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?
+            .format(dates::ISO_DATE)
+            .to_string();
+        let date = date.as_str();
         let conn = self.conn.lock().unwrap();
         match conn.query_row(
             "SELECT id FROM events WHERE name=?1 and date=?2",
@@ -100,92 +421,585 @@ impl Store {
         }
     }
 
+    /// Records when the event starts. `timezone` is the zone `start_time` is given in, e.g. "America/New_York".
+    pub fn set_event_start(
+        &self,
+        event_id: usize,
+        start_time: Option<NaiveTime>,
+        timezone: Option<&str>,
+    ) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE events SET start_time=?2, timezone=?3 WHERE id=?1",
+            (
+                event_id,
+                start_time.map(|t| t.format("%H:%M").to_string()),
+                timezone,
+            ),
+        )
+    }
+
+    /// Records when the prelims start, in the event's `timezone`.
+    pub fn set_prelims_start(
+        &self,
+        event_id: usize,
+        prelims_time: Option<NaiveTime>,
+    ) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE events SET prelims_time=?2 WHERE id=?1",
+            (
+                event_id,
+                prelims_time.map(|t| t.format("%H:%M").to_string()),
+            ),
+        )
+    }
+
+    pub fn find_event(&self, name: &str, date: &str) -> Result<Option<usize>> {
+        let Ok(date) = dates::parse_event_date(date) else {
+            return Ok(None);
+        };
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id FROM events WHERE name=?1 and date=?2",
+            (name, date.format(dates::ISO_DATE).to_string()),
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    /// Number of events before today that have no location recorded.
+    pub fn count_past_events_missing_location(&self) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT count(*) FROM events WHERE location IS NULL AND date < ?1",
+            (dates::today().format(dates::ISO_DATE).to_string(),),
+            |row| row.get(0),
+        )
+    }
+
+    pub fn set_event_location(&self, event_id: usize, location: &str) -> Result<usize> {
+        let location = location.trim();
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE events SET location=?2, country=?3 WHERE id=?1",
+            (event_id, location, country_of(location)),
+        )
+    }
+
+    pub fn set_event_promotion(&self, event_id: usize, promotion: &str) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE events SET promotion=?2 WHERE id=?1",
+            (event_id, promotion.trim()),
+        )
+    }
+
+    pub fn set_event_venue(&self, event_id: usize, venue: Option<&str>) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE events SET venue=?2 WHERE id=?1",
+            (event_id, venue.map(str::trim)),
+        )
+    }
+
+    pub fn get_event(&self, event_id: usize) -> Result<Event> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {EVENT_COLUMNS} FROM events AS e WHERE e.id=?1"),
+            (event_id,),
+            event_from_row,
+        )
+    }
+
+    /// Events between `from` and `to` (inclusive, either may be left open), oldest first.
+    pub fn get_events(&self, from: Option<&str>, to: Option<&str>) -> Result<Vec<Event>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {EVENT_COLUMNS} FROM events AS e
+            WHERE (?1 IS NULL OR e.date >= ?1) AND (?2 IS NULL OR e.date <= ?2)
+            ORDER BY e.date, e.id"
+        ))?;
+        let events = stmt.query_map((from, to), event_from_row)?;
+        events.collect()
+    }
+
+    /// Countries that have at least one event, alphabetically.
+    pub fn get_event_countries(&self) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT country FROM events WHERE country IS NOT NULL ORDER BY country",
+        )?;
+        let countries = stmt.query_map([], |row| row.get(0))?;
+        countries.collect()
+    }
+
+    /// Promotions that have at least one event, alphabetically.
+    pub fn get_promotions(&self) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT DISTINCT promotion FROM events ORDER BY promotion")?;
+        let promotions = stmt.query_map([], |row| row.get(0))?;
+        promotions.collect()
+    }
+
+    pub fn get_event_date(&self, event_id: usize) -> Result<NaiveDate> {
+        let conn = self.conn.lock().unwrap();
+        let date: String =
+            conn.query_row("SELECT date FROM events WHERE id=?1", (event_id,), |row| {
+                row.get(0)
+            })?;
+        NaiveDate::parse_from_str(&date, dates::ISO_DATE).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        })
+    }
+
     pub fn add_or_update_prediction(
         &self,
         event_id: usize,
         winner: &str,
         loser: &str,
     ) -> Result<usize> {
+        self.add_or_update_prediction_for_user(event_id, winner, loser, DEFAULT_USER)
+    }
+
+    // a flipped pick replaces the user's existing pick for the same bout
+    pub fn add_or_update_prediction_for_user(
+        &self,
+        event_id: usize,
+        winner: &str,
+        loser: &str,
+        user: &str,
+    ) -> Result<usize> {
+        let winner = &self.resolve_fighter_name(Some(event_id), winner)?;
+        let loser = &self.resolve_fighter_name(Some(event_id), loser)?;
         let conn = self.conn.lock().unwrap();
         match conn.query_row(
-            "SELECT event_id FROM predictions WHERE event_id=?1 AND winner=?3 AND loser=?2",
-            (event_id, winner, loser),
+            "SELECT event_id FROM predictions WHERE event_id=?1 AND winner=?3 AND loser=?2 AND user=?4",
+            (event_id, winner, loser, user),
             |row: &rusqlite::Row<'_>| row.get::<_, usize>(0),
         ) {
             Ok(_) => conn.execute(
-                "UPDATE predictions set (event_id, winner, loser) = (?1, ?2, ?3) where winner=?3 and loser=?2",
-                (event_id, winner, loser),
+                "UPDATE predictions set (winner, loser) = (?2, ?3) where event_id=?1 and winner=?3 and loser=?2 and user=?4",
+                (event_id, winner, loser, user),
             ),
             Err(_) => conn.execute(
-                "INSERT INTO predictions (event_id, winner, loser) VALUES (?1, ?2, ?3)",
-                (event_id, winner, loser),
+                "INSERT OR IGNORE INTO predictions (event_id, winner, loser, user) VALUES (?1, ?2, ?3, ?4)",
+                (event_id, winner, loser, user),
             ),
         }
     }
 
+    /// Records the user's (winner, loser) `picks` for the event, each replacing their earlier pick on
+    /// the same bout, and removes their picks on the `removed` bouts. Either all of it is saved or
+    /// none is.
+    pub fn save_predictions_for_user(
+        &self,
+        event_id: usize,
+        picks: &[(String, String)],
+        removed: &[(String, String)],
+        user: &str,
+    ) -> Result<usize> {
+        let picks = picks
+            .iter()
+            .map(|(winner, loser)| {
+                Ok((
+                    self.resolve_fighter_name(Some(event_id), winner)?,
+                    self.resolve_fighter_name(Some(event_id), loser)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for (winner, loser) in &picks {
+            save_prediction(&tx, event_id, winner, loser, user)?;
+        }
+        for (fighter1, fighter2) in removed {
+            tx.execute(
+                "DELETE FROM predictions WHERE event_id=?1 AND user=?4
+                AND ((winner=?2 AND loser=?3) OR (winner=?3 AND loser=?2))",
+                (event_id, fighter1, fighter2, user),
+            )?;
+        }
+        tx.commit()?;
+        Ok(picks.len())
+    }
+
+    /// Removes the user's pick on the bout between `fighter1` and `fighter2`, whichever way round
+    /// it was made. Returns how many picks were removed.
+    pub fn delete_prediction_for_user(
+        &self,
+        event_id: usize,
+        fighter1: &str,
+        fighter2: &str,
+        user: &str,
+    ) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM predictions WHERE event_id=?1 AND user=?4
+            AND ((winner=?2 AND loser=?3) OR (winner=?3 AND loser=?2))",
+            (event_id, fighter1, fighter2, user),
+        )
+    }
+
+    /// Removes every pick the user made on the event.
+    pub fn clear_predictions_for_user(&self, event_id: usize, user: &str) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM predictions WHERE event_id=?1 AND user=?2",
+            (event_id, user),
+        )
+    }
+
+    /// Whether picks on the event are part of users' records: it has locked by `now`, or has results
+    /// in. Changing picks then would rewrite how someone did.
+    pub fn are_predictions_locked(&self, event_id: usize, now: DateTime<Utc>) -> Result<bool> {
+        let event = self.get_event(event_id)?;
+        let locked = event
+            .lock_time()
+            .is_some_and(|lock| event.local_time(now) >= lock);
+        Ok(locked || !self.get_results(event_id)?.is_empty())
+    }
+
     pub fn get_predictions(&self, event_id: usize) -> Result<Vec<(String, String)>> {
+        self.get_predictions_for_user(event_id, DEFAULT_USER)
+    }
+
+    pub fn get_predictions_for_user(
+        &self,
+        event_id: usize,
+        user: &str,
+    ) -> Result<Vec<(String, String)>> {
         let conn = self.conn.lock().unwrap();
         let mut statement: rusqlite::Statement<'_> =
-            conn.prepare("SELECT winner,loser FROM predictions WHERE event_id=?1")?;
-        let mut rows: rusqlite::Rows<'_> = statement.query((event_id,))?;
+            conn.prepare("SELECT winner,loser FROM predictions WHERE event_id=?1 AND user=?2")?;
+        let mut rows: rusqlite::Rows<'_> = statement.query((event_id, user))?;
         let mut predictions: Vec<(String, String)> = vec![];
         while let Some(row) = rows.next()? {
             let winner: String = row.get(0)?;
             let loser: String = row.get(1)?;
-            predictions.push((winner.clone(), loser.clone()));
-            println!("{winner} {loser}");
+            predictions.push((winner, loser));
         }
         Ok(predictions)
     }
 
+    /// The event's recorded results as (winner, loser).
+    pub fn get_results(&self, event_id: usize) -> Result<Vec<(String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt =
+            conn.prepare("SELECT winner, loser FROM results WHERE event_id=?1 ORDER BY rowid")?;
+        let results = stmt.query_map((event_id,), |row| Ok((row.get(0)?, row.get(1)?)))?;
+        results.collect()
+    }
+
     //returns correct,incorrect number of guesses
     pub fn get_my_predictions_correctness(&self) -> Result<(i64, i64)> {
-        let conn = self.conn.lock().unwrap();
-        let correct: i64 = conn.query_row(
-            "SELECT count(*) FROM results as r JOIN predictions as p ON p.event_id=r.event_id and p.winner=r.winner and p.loser=r.loser",
+        let score = self.score(&ScoreFilter::for_user(DEFAULT_USER))?;
+        Ok((score.correct, score.incorrect))
+    }
+
+    pub fn get_my_predictions_correctness_for_event(&self, id: usize) -> Result<(i64, i64)> {
+        let score = self.score(&ScoreFilter::for_user(DEFAULT_USER).event(id))?;
+        Ok((score.correct, score.incorrect))
+    }
+}
+
+/// Saves the user's pick as part of a larger write, replacing their earlier pick on the same bout.
+/// Names should already be resolved.
+pub(super) fn save_prediction(
+    conn: &Connection,
+    event_id: usize,
+    winner: &str,
+    loser: &str,
+    user: &str,
+) -> Result<()> {
+    conn.execute(
+        "DELETE FROM predictions WHERE event_id=?1 AND user=?4
+        AND ((winner=?2 AND loser=?3) OR (winner=?3 AND loser=?2))",
+        (event_id, winner, loser, user),
+    )?;
+    conn.execute(
+        "INSERT INTO predictions (event_id, winner, loser, user) VALUES (?1, ?2, ?3, ?4)",
+        (event_id, winner, loser, user),
+    )?;
+    Ok(())
+}
+
+fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<String>>>()?;
+    Ok(names.iter().any(|name| name == column))
+}
+
+// brings databases created by older versions up to the current schema
+fn migrate(conn: &Connection) -> Result<()> {
+    if !has_column(conn, "predictions", "user")? {
+        // the unique constraint changes, so the table has to be rebuilt
+        conn.execute_batch(
+            "BEGIN;
+            CREATE TABLE predictions_new (
+                event_id INTEGER NOT NULL,
+                winner TEXT NOT NULL,
+                loser TEXT NOT NULL,
+                user TEXT NOT NULL DEFAULT 'me',
+                unique (event_id, winner, loser, user)
+            );
+            INSERT INTO predictions_new (event_id, winner, loser) SELECT event_id, winner, loser FROM predictions;
+            DROP TABLE predictions;
+            ALTER TABLE predictions_new RENAME TO predictions;
+            COMMIT;",
+        )?;
+    }
+    for column in [
+        "start_time",
+        "timezone",
+        "location",
+        "venue",
+        "country",
+        "prelims_time",
+    ] {
+        if !has_column(conn, "events", column)? {
+            conn.execute(&format!("ALTER TABLE events ADD COLUMN {column} TEXT"), ())?;
+        }
+    }
+    // everything before other promotions were supported came from ufcstats
+    if !has_column(conn, "events", "promotion")? {
+        conn.execute(
+            &format!("ALTER TABLE events ADD COLUMN promotion TEXT NOT NULL DEFAULT '{DEFAULT_PROMOTION}'"),
             (),
-            |row: &rusqlite::Row<'_>| row.get(0),
         )?;
-        let incorrect: i64 = conn.query_row(
-            "SELECT count(*) FROM results as r JOIN predictions as p ON p.event_id=r.event_id and p.loser=r.winner and p.winner=r.loser",
+    }
+    if !has_column(conn, "fighters", "recent_form")? {
+        conn.execute("ALTER TABLE fighters ADD COLUMN recent_form TEXT", ())?;
+    }
+    // corrections could only be wins before draws and no contests could be entered
+    if !has_column(conn, "result_overrides", "outcome")? {
+        conn.execute(
+            "ALTER TABLE result_overrides ADD COLUMN outcome TEXT NOT NULL DEFAULT 'win'",
             (),
-            |row: &rusqlite::Row<'_>| row.get(0),
         )?;
+    }
+    normalize_event_dates(conn)?;
+    Ok(())
+}
 
-        Ok((correct, incorrect))
+// rewrites dates stored as scraped ("November 15, 2025") to YYYY-MM-DD. An event stored under
+// both formats is merged into the one already under YYYY-MM-DD.
+fn normalize_event_dates(conn: &Connection) -> Result<()> {
+    let mut stmt = conn.prepare(
+        "SELECT id, date FROM events WHERE date NOT GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]'",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, usize>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    for (id, raw) in rows {
+        match dates::parse_event_date(&raw) {
+            Ok(date) => {
+                let date = date.format(dates::ISO_DATE).to_string();
+                let existing: Option<usize> = conn
+                    .query_row(
+                        "SELECT id FROM events WHERE date=?2 AND name=(SELECT name FROM events WHERE id=?1)",
+                        (id, &date),
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                match existing {
+                    Some(existing) => merge_events(conn, id, existing)?,
+                    None => {
+                        conn.execute("UPDATE events SET date=?2 WHERE id=?1", (id, &date))?;
+                    }
+                }
+            }
+            Err(e) => log::warn!("Leaving unparseable date {raw:?} on event {id}: {e}"),
+        }
     }
-    pub fn get_my_predictions_correctness_for_event(&self, id: usize) -> Result<(i64, i64)> {
-        let conn = self.conn.lock().unwrap();
-        let correct: i64 = conn.query_row(
-            "SELECT count(*) FROM results as r JOIN predictions as p ON p.event_id=r.event_id and p.winner=r.winner and p.loser=r.loser where p.event_id=?1",
-            (id,),
-            |row: &rusqlite::Row<'_>| row.get(0),
+    Ok(())
+}
+
+// moves everything stored against event `from` onto `into`, keeping what `into` already has, and
+// deletes `from`
+fn merge_events(conn: &Connection, from: usize, into: usize) -> Result<()> {
+    let tables = {
+        let mut stmt = conn.prepare(
+            "SELECT m.name FROM sqlite_master AS m, pragma_table_info(m.name) AS c
+            WHERE m.type='table' AND c.name='event_id'",
         )?;
-        let incorrect: i64 = conn.query_row(
-            "SELECT count(*) FROM results as r JOIN predictions as p ON p.event_id=r.event_id and p.loser=r.winner and p.winner=r.loser",
-            (),
-            |row: &rusqlite::Row<'_>| row.get(0),
+        let tables = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        tables.collect::<Result<Vec<_>>>()?
+    };
+    let tx = conn.unchecked_transaction()?;
+    // a pick on `into` stands over the same user's pick of the bout either way round on `from`
+    tx.execute(
+        "DELETE FROM predictions AS p WHERE p.event_id=?1 AND EXISTS (
+            SELECT 1 FROM predictions AS q WHERE q.event_id=?2 AND q.user=p.user
+            AND ((q.winner=p.winner AND q.loser=p.loser) OR (q.winner=p.loser AND q.loser=p.winner))
+        )",
+        (from, into),
+    )?;
+    for table in tables {
+        tx.execute(
+            &format!("UPDATE OR IGNORE {table} SET event_id=?2 WHERE event_id=?1"),
+            (from, into),
         )?;
+        tx.execute(&format!("DELETE FROM {table} WHERE event_id=?1"), (from,))?;
+    }
+    tx.execute("DELETE FROM events WHERE id=?1", (from,))?;
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Ok((correct, incorrect))
+    #[test]
+    fn saves_a_batch_of_picks_over_earlier_ones() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        store.add_or_update_prediction(1, "A", "B").unwrap();
+        store.add_or_update_prediction(1, "E", "F").unwrap();
+        let picks = [
+            ("B".to_string(), "A".to_string()),
+            ("C".to_string(), "D".to_string()),
+        ];
+        let removed = [("F".to_string(), "E".to_string())];
+        assert_eq!(
+            store
+                .save_predictions_for_user(1, &picks, &removed, DEFAULT_USER)
+                .unwrap(),
+            2
+        );
+        assert_eq!(store.get_predictions(1).unwrap(), picks.to_vec());
     }
 
-    pub fn get_past_events_with_predictions(&self) -> Result<Vec<(usize, String, String, String)>> {
-        let conn = self.conn.lock().unwrap();
-        let mut stmt: rusqlite::Statement<'_> = conn.prepare("SELECT DISTINCT events.id, events.name, events.date,link FROM events where events.date < date('now')").unwrap();
-        let rows = stmt
-            .query_map([], |row| {
-                let id: usize = row.get(0)?;
-                let name: String = row.get(1)?;
-                let date: String = row.get(2)?;
-                let link: String = row.get(3)?;
-                Ok((id, name, date, link))
-            })
+    #[test]
+    fn removes_picks_until_the_event_locks() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let event = store.add_event("UFC 1", "2025-01-02", "").unwrap();
+        store.add_or_update_prediction(event, "A", "B").unwrap();
+        store.add_or_update_prediction(event, "C", "D").unwrap();
+        store.add_or_update_prediction(event, "E", "F").unwrap();
+        assert_eq!(
+            store
+                .delete_prediction_for_user(event, "B", "A", DEFAULT_USER)
+                .unwrap(),
+            1
+        );
+        assert_eq!(store.get_predictions(event).unwrap().len(), 2);
+
+        // locks at midnight in New York, 05:00 UTC
+        store
+            .set_event_start(event, None, Some("America/New_York"))
+            .unwrap();
+        let at = |time: &str| {
+            DateTime::parse_from_rfc3339(time)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        assert!(!store
+            .are_predictions_locked(event, at("2025-01-02T04:00:00Z"))
+            .unwrap());
+        assert!(store
+            .are_predictions_locked(event, at("2025-01-02T05:00:00Z"))
+            .unwrap());
+        store.add_or_update_result(event, "C", "D").unwrap();
+        assert!(store
+            .are_predictions_locked(event, at("2025-01-01T00:00:00Z"))
+            .unwrap());
+
+        assert_eq!(
+            store
+                .clear_predictions_for_user(event, DEFAULT_USER)
+                .unwrap(),
+            2
+        );
+        assert!(store.get_predictions(event).unwrap().is_empty());
+        assert_eq!(store.score(&ScoreFilter::all()).unwrap().decided(), 0);
+    }
+
+    #[test]
+    fn migrates_scraped_dates_and_old_prediction_schema() {
+        let store = Store::new(":memory:");
+        {
+            let conn = store.conn.lock().unwrap();
+            conn.execute_batch(
+                "CREATE TABLE events (id INTEGER PRIMARY KEY, name TEXT NOT NULL, date DATE NOT NULL, link TEXT NOT NULL, unique (name,date));
+                CREATE TABLE predictions (event_id INTEGER NOT NULL, winner TEXT NOT NULL, loser TEXT NOT NULL, unique (event_id, winner, loser));
+                INSERT INTO events (id, name, date, link) VALUES (1, 'UFC 322', 'November 15, 2025', ''), (2, 'UFC 323', 'December 06, 2025', '');
+                INSERT INTO predictions VALUES (1, 'A', 'B');",
+            )
             .unwrap();
-        let mut result: Vec<(usize, String, String, String)> = Vec::new();
-        for row in rows {
-            result.push(row.unwrap());
         }
-        Ok(result)
+        store.create_tables().unwrap();
+
+        assert_eq!(
+            store.get_event_date(1).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 11, 15).unwrap()
+        );
+        assert_eq!(
+            store.get_event_date(2).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 12, 6).unwrap()
+        );
+        assert_eq!(
+            store.add_event("UFC 322", "November 15, 2025", "").unwrap(),
+            1
+        );
+        assert_eq!(
+            store.get_predictions(1).unwrap(),
+            vec![("A".to_string(), "B".to_string())]
+        );
+        store
+            .set_event_start(
+                2,
+                dates::parse_start_time("18:00").ok(),
+                Some("America/New_York"),
+            )
+            .unwrap();
+        store
+            .set_prelims_start(2, dates::parse_start_time("4:00 PM").ok())
+            .unwrap();
+        let event = store.get_event(2).unwrap();
+        assert_eq!(event.prelims_time.as_deref(), Some("16:00"));
+        assert_eq!(
+            event.lock_time().unwrap().to_string(),
+            "2025-12-06 16:00:00"
+        );
+    }
+
+    #[test]
+    fn merges_an_event_stored_under_both_date_formats() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        {
+            let conn = store.conn.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO events (id, name, date, link) VALUES (1, 'UFC 322', 'November 15, 2025', ''), (2, 'UFC 322', '2025-11-15', '');
+                INSERT INTO predictions (event_id, winner, loser) VALUES (1, 'A', 'B'), (1, 'C', 'D'), (2, 'C', 'D'), (1, 'E', 'F'), (2, 'F', 'E');
+                INSERT INTO results (event_id, winner, loser) VALUES (1, 'A', 'B');",
+            )
+            .unwrap();
+        }
+        store.create_tables().unwrap();
+
+        assert_eq!(store.get_events(None, None).unwrap().len(), 1);
+        assert!(store.get_event(1).is_err());
+        let mut picks = store.get_predictions(2).unwrap();
+        picks.sort();
+        assert_eq!(
+            picks,
+            vec![
+                ("A".to_string(), "B".to_string()),
+                ("C".to_string(), "D".to_string()),
+                // picked both ways round, and the ISO event's pick stands
+                ("F".to_string(), "E".to_string())
+            ]
+        );
+        assert_eq!(
+            store.get_results(2).unwrap(),
+            vec![("A".to_string(), "B".to_string())]
+        );
     }
 }
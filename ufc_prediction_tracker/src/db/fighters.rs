@@ -0,0 +1,284 @@
+use super::{
+    bouts::{bout_from_row_at, BOUT_COLUMNS_B},
+    dates, event_from_row, Bout, Event, Store, EVENT_COLUMNS,
+};
+use chrono::{Datelike, NaiveDate};
+use rusqlite::{OptionalExtension, Result, Row};
+use serde::{Deserialize, Serialize};
+
+/// How many of a fighter's latest results are kept as their recent form.
+pub const RECENT_FORM_LEN: usize = 5;
+
+/// How long a scraped fighter profile is trusted before it's fetched again.
+pub const FIGHTER_CACHE_DAYS: i64 = 7;
+
+/// A fighter as shown on their ufcstats fighter-details page. `id` is the last segment of that page's
+/// URL. Percentages are stored as written, e.g. 48.0 for "48%".
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Fighter {
+    pub id: String,
+    pub name: String,
+    pub nickname: Option<String>,
+    pub record: Option<String>,
+    pub height: Option<String>,
+    pub weight: Option<String>,
+    pub reach_inches: Option<f64>,
+    pub stance: Option<String>,
+    /// YYYY-MM-DD
+    pub dob: Option<String>,
+    pub slpm: Option<f64>,
+    pub str_acc: Option<f64>,
+    pub sapm: Option<f64>,
+    pub str_def: Option<f64>,
+    pub td_avg: Option<f64>,
+    pub td_acc: Option<f64>,
+    pub td_def: Option<f64>,
+    pub sub_avg: Option<f64>,
+    /// Latest results first, one letter each: W, L, D or N (no contest). At most [`RECENT_FORM_LEN`].
+    pub recent_form: Option<String>,
+}
+
+impl Fighter {
+    pub fn age_on(&self, date: NaiveDate) -> Option<u32> {
+        let dob = NaiveDate::parse_from_str(self.dob.as_deref()?, dates::ISO_DATE).ok()?;
+        let had_birthday = (date.month(), date.day()) >= (dob.month(), dob.day());
+        let years = date.year() - dob.year() - if had_birthday { 0 } else { 1 };
+        u32::try_from(years).ok()
+    }
+
+    pub fn age(&self) -> Option<u32> {
+        self.age_on(dates::today())
+    }
+}
+
+/// How a user's picks involving one fighter went.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FighterPickStats {
+    /// Bouts where the user picked this fighter to win.
+    pub picked_for: i64,
+    /// Bouts where the user picked this fighter's opponent.
+    pub picked_against: i64,
+    /// Decided picks involving this fighter that were right, whichever side was picked.
+    pub correct: i64,
+    pub incorrect: i64,
+}
+
+/// The fighter id at the end of a ufcstats fighter-details link.
+pub fn fighter_id_from_link(link: &str) -> Option<&str> {
+    link.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|id| !id.is_empty() && !id.contains(':'))
+}
+
+const FIGHTER_COLUMNS: &str = "id, name, nickname, record, height, weight, reach_inches, stance, dob, slpm, str_acc, sapm, str_def, td_avg, td_acc, td_def, sub_avg, recent_form";
+
+fn fighter_from_row(row: &Row<'_>) -> Result<Fighter> {
+    Ok(Fighter {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        nickname: row.get(2)?,
+        record: row.get(3)?,
+        height: row.get(4)?,
+        weight: row.get(5)?,
+        reach_inches: row.get(6)?,
+        stance: row.get(7)?,
+        dob: row.get(8)?,
+        slpm: row.get(9)?,
+        str_acc: row.get(10)?,
+        sapm: row.get(11)?,
+        str_def: row.get(12)?,
+        td_avg: row.get(13)?,
+        td_acc: row.get(14)?,
+        td_def: row.get(15)?,
+        sub_avg: row.get(16)?,
+        recent_form: row.get(17)?,
+    })
+}
+
+impl Store {
+    pub fn upsert_fighter(&self, fighter: &Fighter) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            &format!(
+                "INSERT OR REPLACE INTO fighters ({FIGHTER_COLUMNS}, fetched_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, datetime('now'))"
+            ),
+            rusqlite::params![
+                fighter.id,
+                fighter.name,
+                fighter.nickname,
+                fighter.record,
+                fighter.height,
+                fighter.weight,
+                fighter.reach_inches,
+                fighter.stance,
+                fighter.dob,
+                fighter.slpm,
+                fighter.str_acc,
+                fighter.sapm,
+                fighter.str_def,
+                fighter.td_avg,
+                fighter.td_acc,
+                fighter.td_def,
+                fighter.sub_avg,
+                fighter.recent_form,
+            ],
+        )
+    }
+
+    pub fn get_fighter(&self, id: &str) -> Result<Option<Fighter>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {FIGHTER_COLUMNS} FROM fighters WHERE id=?1"),
+            (id,),
+            fighter_from_row,
+        )
+        .optional()
+    }
+
+    /// The cached fighter, unless it's missing or older than [`FIGHTER_CACHE_DAYS`].
+    pub fn get_fresh_fighter(&self, id: &str) -> Result<Option<Fighter>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!(
+                "SELECT {FIGHTER_COLUMNS} FROM fighters
+                WHERE id=?1 AND fetched_at > datetime('now', ?2)"
+            ),
+            (id, format!("-{FIGHTER_CACHE_DAYS} days")),
+            fighter_from_row,
+        )
+        .optional()
+    }
+
+    /// Fighter name to fighter id for everyone on the event's recorded card.
+    pub fn get_event_fighter_ids(&self, event_id: usize) -> Result<Vec<(String, String)>> {
+        Ok(self
+            .get_bouts(event_id)?
+            .into_iter()
+            .flat_map(|bout| {
+                [
+                    (bout.fighter1, bout.fighter1_link),
+                    (bout.fighter2, bout.fighter2_link),
+                ]
+            })
+            .filter_map(|(name, link)| Some((name, fighter_id_from_link(&link?)?.to_string())))
+            .collect())
+    }
+
+    /// Bouts the fighter is recorded in, newest first, matched by fighter-details link or by name.
+    pub fn get_fighter_bouts(&self, fighter: &Fighter) -> Result<Vec<(Event, Bout)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {EVENT_COLUMNS}, {BOUT_COLUMNS_B} FROM events AS e JOIN bouts AS b ON b.event_id = e.id
+            WHERE b.fighter1 = ?1 OR b.fighter2 = ?1
+                OR b.fighter1_link LIKE '%/' || ?2 OR b.fighter2_link LIKE '%/' || ?2
+            ORDER BY e.date DESC, b.position"
+        ))?;
+        let bouts = stmt.query_map((&fighter.name, &fighter.id), |row| {
+            Ok((event_from_row(row)?, bout_from_row_at(row, 11)?))
+        })?;
+        bouts.collect()
+    }
+
+    pub fn get_fighter_pick_stats(&self, name: &str, user: &str) -> Result<FighterPickStats> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT
+                COALESCE(SUM(p.winner = ?1), 0),
+                COALESCE(SUM(p.loser = ?1), 0),
+                COALESCE(SUM(r.winner = p.winner), 0),
+                COALESCE(SUM(r.winner = p.loser), 0)
+            FROM predictions AS p
+            LEFT JOIN results AS r ON r.event_id = p.event_id
+                AND ((r.winner = p.winner AND r.loser = p.loser) OR (r.winner = p.loser AND r.loser = p.winner))
+            WHERE p.user = ?2 AND (p.winner = ?1 OR p.loser = ?1)",
+            (name, user),
+            |row| {
+                Ok(FighterPickStats {
+                    picked_for: row.get(0)?,
+                    picked_against: row.get(1)?,
+                    correct: row.get(2)?,
+                    incorrect: row.get(3)?,
+                })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{BoutOutcome, DEFAULT_USER};
+
+    #[test]
+    fn age_counts_birthdays() {
+        let fighter = Fighter {
+            dob: Some("1991-10-27".to_string()),
+            ..Default::default()
+        };
+        let on = |d: &str| fighter.age_on(dates::parse_event_date(d).unwrap());
+        assert_eq!(on("2025-10-26"), Some(33));
+        assert_eq!(on("2025-10-27"), Some(34));
+    }
+
+    #[test]
+    fn bouts_and_pick_stats_for_a_fighter() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let fighter = Fighter {
+            id: "abc123".to_string(),
+            name: "Islam Makhachev".to_string(),
+            ..Default::default()
+        };
+        store.upsert_fighter(&fighter).unwrap();
+        assert_eq!(store.get_fighter("abc123").unwrap(), Some(fighter.clone()));
+        assert!(store.get_fresh_fighter("abc123").unwrap().is_some());
+
+        let first = store.add_event("UFC 1", "2024-01-13", "").unwrap();
+        let second = store.add_event("UFC 2", "2024-06-01", "").unwrap();
+        for (event_id, opponent) in [(first, "A"), (second, "B")] {
+            store
+                .record_bout(&Bout {
+                    event_id,
+                    fighter1: "Islam Makhachev".to_string(),
+                    fighter2: opponent.to_string(),
+                    fighter1_link: Some("http://ufcstats.com/fighter-details/abc123".to_string()),
+                    outcome: Some(BoutOutcome::Win),
+                    winner: Some("Islam Makhachev".to_string()),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        store
+            .add_or_update_prediction(first, "Islam Makhachev", "A")
+            .unwrap();
+        store
+            .add_or_update_prediction(second, "B", "Islam Makhachev")
+            .unwrap();
+
+        let bouts = store.get_fighter_bouts(&fighter).unwrap();
+        assert_eq!(
+            bouts
+                .iter()
+                .map(|(e, _)| e.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["UFC 2", "UFC 1"]
+        );
+        assert_eq!(
+            store.get_event_fighter_ids(first).unwrap(),
+            vec![("Islam Makhachev".to_string(), "abc123".to_string())]
+        );
+        assert_eq!(
+            store
+                .get_fighter_pick_stats("Islam Makhachev", DEFAULT_USER)
+                .unwrap(),
+            FighterPickStats {
+                picked_for: 1,
+                picked_against: 1,
+                correct: 1,
+                incorrect: 1
+            }
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use super::{dates, Store};
+use chrono::NaiveDate;
+use rusqlite::Result;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+
+/// Which past events to list. Only events that already happened and have at least one pick are ever returned.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PastEventQuery {
+    /// Only events this user made picks on. `None` means picks by anyone.
+    pub user: Option<String>,
+    pub year: Option<i32>,
+    /// Case-insensitive substring of the event name.
+    pub search: Option<String>,
+    pub country: Option<String>,
+    pub promotion: Option<String>,
+    /// Zero-based page index.
+    pub page: usize,
+    pub per_page: usize,
+}
+
+impl Default for PastEventQuery {
+    fn default() -> Self {
+        PastEventQuery {
+            user: None,
+            year: None,
+            search: None,
+            country: None,
+            promotion: None,
+            page: 0,
+            per_page: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+/// One page of past events, newest first, along with the total number of matching events.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PastEventPage {
+    pub events: Vec<(usize, String, String, String)>,
+    pub total: usize,
+}
+
+impl PastEventPage {
+    pub fn page_count(&self, per_page: usize) -> usize {
+        self.total.div_ceil(per_page.max(1))
+    }
+}
+
+// event dates are stored as YYYY-MM-DD, so text comparison orders them as dates
+const PAST_EVENTS_WITH_PICKS: &str = "FROM events AS e
+    WHERE e.date < ?4
+        AND EXISTS (SELECT 1 FROM predictions AS p WHERE p.event_id = e.id AND (?1 IS NULL OR p.user = ?1))
+        AND (?2 IS NULL OR substr(e.date, 1, 4) = printf('%04d', ?2))
+        AND (?3 IS NULL OR e.name LIKE '%' || ?3 || '%')
+        AND (?5 IS NULL OR e.country = ?5)
+        AND (?6 IS NULL OR e.promotion = ?6)";
+
+impl Store {
+    pub fn get_past_events_page(&self, query: &PastEventQuery) -> Result<PastEventPage> {
+        self.get_past_events_page_as_of(query, dates::today())
+    }
+
+    /// Like [`Store::get_past_events_page`], treating `today` as the current date.
+    pub fn get_past_events_page_as_of(
+        &self,
+        query: &PastEventQuery,
+        today: NaiveDate,
+    ) -> Result<PastEventPage> {
+        let conn = self.conn.lock().unwrap();
+        let today = today.format(dates::ISO_DATE).to_string();
+        let params = (
+            query.user.as_deref(),
+            query.year,
+            query.search.as_deref(),
+            &today,
+            query.country.as_deref(),
+            query.promotion.as_deref(),
+        );
+        let total: usize = conn.query_row(
+            &format!("SELECT count(*) {PAST_EVENTS_WITH_PICKS}"),
+            params,
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT e.id, e.name, e.date, e.link {PAST_EVENTS_WITH_PICKS}
+            ORDER BY e.date DESC, e.id DESC
+            LIMIT ?7 OFFSET ?8"
+        ))?;
+        let per_page = query.per_page.max(1);
+        let events = stmt
+            .query_map(
+                (
+                    query.user.as_deref(),
+                    query.year,
+                    query.search.as_deref(),
+                    &today,
+                    query.country.as_deref(),
+                    query.promotion.as_deref(),
+                    per_page,
+                    query.page * per_page,
+                ),
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(PastEventPage { events, total })
+    }
+
+    /// Years that have at least one past event with picks, newest first.
+    pub fn get_past_event_years(&self, user: Option<&str>) -> Result<Vec<i32>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT CAST(substr(e.date, 1, 4) AS INTEGER) AS year
+            FROM events AS e
+            WHERE e.date < ?2
+                AND EXISTS (SELECT 1 FROM predictions AS p WHERE p.event_id = e.id AND (?1 IS NULL OR p.user = ?1))
+            ORDER BY year DESC",
+        )?;
+        let today = dates::today().format(dates::ISO_DATE).to_string();
+        let years = stmt.query_map((user, today), |row| row.get(0))?;
+        years.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> Store {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        store
+    }
+
+    fn names(page: &PastEventPage) -> Vec<&str> {
+        page.events.iter().map(|e| e.1.as_str()).collect()
+    }
+
+    #[test]
+    fn only_events_with_picks_are_listed() {
+        let store = store();
+        let picked = store.add_event("UFC 300", "April 13, 2024", "").unwrap();
+        store.add_event("UFC 301", "May 04, 2024", "").unwrap();
+        store.add_or_update_prediction(picked, "A", "B").unwrap();
+
+        let page = store
+            .get_past_events_page(&PastEventQuery::default())
+            .unwrap();
+        assert_eq!(names(&page), vec!["UFC 300"]);
+        assert_eq!(page.total, 1);
+    }
+
+    #[test]
+    fn sorted_by_date_not_by_text() {
+        let store = store();
+        for (name, date) in [
+            ("UFC 296", "December 16, 2023"),
+            ("UFC 297", "January 20, 2024"),
+            ("UFC 298", "February 17, 2024"),
+            ("UFC 299", "March 09, 2024"),
+        ] {
+            let id = store.add_event(name, date, "").unwrap();
+            store.add_or_update_prediction(id, "A", "B").unwrap();
+        }
+
+        let page = store
+            .get_past_events_page(&PastEventQuery::default())
+            .unwrap();
+        assert_eq!(
+            names(&page),
+            vec!["UFC 299", "UFC 298", "UFC 297", "UFC 296"]
+        );
+        assert_eq!(store.get_past_event_years(None).unwrap(), vec![2024, 2023]);
+    }
+
+    #[test]
+    fn filters_and_pages() {
+        let store = store();
+        for (name, date, user) in [
+            ("UFC 296", "December 16, 2023", "me"),
+            (
+                "UFC Fight Night: Allen vs. Curtis 2",
+                "April 06, 2024",
+                "me",
+            ),
+            ("UFC 300", "April 13, 2024", "friend"),
+            ("UFC 301", "May 04, 2024", "me"),
+        ] {
+            let id = store.add_event(name, date, "").unwrap();
+            store
+                .add_or_update_prediction_for_user(id, "A", "B", user)
+                .unwrap();
+        }
+
+        let by_user = PastEventQuery {
+            user: Some("friend".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&store.get_past_events_page(&by_user).unwrap()),
+            vec!["UFC 300"]
+        );
+
+        let by_year = PastEventQuery {
+            year: Some(2023),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&store.get_past_events_page(&by_year).unwrap()),
+            vec!["UFC 296"]
+        );
+
+        let by_name = PastEventQuery {
+            search: Some("fight night".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&store.get_past_events_page(&by_name).unwrap()),
+            vec!["UFC Fight Night: Allen vs. Curtis 2"]
+        );
+
+        let second_page = PastEventQuery {
+            page: 1,
+            per_page: 3,
+            ..Default::default()
+        };
+        let page = store.get_past_events_page(&second_page).unwrap();
+        assert_eq!(names(&page), vec!["UFC 296"]);
+        assert_eq!(page.total, 4);
+        assert_eq!(page.page_count(3), 2);
+    }
+
+    #[test]
+    fn past_classification_across_month_boundaries() {
+        let store = store();
+        for (name, date) in [
+            ("Last of November", "November 30, 2025"),
+            ("First of December", "December 01, 2025"),
+            ("Early December", "December 06, 2025"),
+            ("February", "February 01, 2025"),
+        ] {
+            let id = store.add_event(name, date, "").unwrap();
+            store.add_or_update_prediction(id, "A", "B").unwrap();
+        }
+        let as_of = |today: &str| {
+            let today = NaiveDate::parse_from_str(today, dates::ISO_DATE).unwrap();
+            let page = store
+                .get_past_events_page_as_of(&PastEventQuery::default(), today)
+                .unwrap();
+            page.events.into_iter().map(|e| e.1).collect::<Vec<_>>()
+        };
+
+        assert_eq!(as_of("2025-11-30"), vec!["February"]);
+        assert_eq!(as_of("2025-12-01"), vec!["Last of November", "February"]);
+        assert_eq!(
+            as_of("2026-01-01"),
+            vec![
+                "Early December",
+                "First of December",
+                "Last of November",
+                "February"
+            ]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use super::Store;
+use rusqlite::Result;
+use serde::{Deserialize, Serialize};
+
+/// How fetching from one host has gone, totalled since the store was created.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpMetrics {
+    pub host: String,
+    /// Every request made, retries included.
+    pub requests: usize,
+    pub retries: usize,
+    /// Fetches that still failed after their retries.
+    pub failures: usize,
+    /// Fetches skipped because robots.txt disallows the page.
+    pub disallowed: usize,
+    pub last_error: Option<String>,
+    pub last_success_at: Option<String>,
+    pub updated_at: String,
+}
+
+/// How one fetch ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HttpOutcome {
+    Fetched,
+    Failed(String),
+    Disallowed,
+}
+
+impl Store {
+    /// Counts a fetch from `host` that took `attempts` requests.
+    pub fn record_http_fetch(
+        &self,
+        host: &str,
+        attempts: usize,
+        outcome: &HttpOutcome,
+    ) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let error = match outcome {
+            HttpOutcome::Failed(e) => Some(e.as_str()),
+            _ => None,
+        };
+        conn.execute(
+            "INSERT INTO http_metrics (host, requests, retries, failures, disallowed, last_error, last_success_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, CASE WHEN ?7 THEN datetime('now') END)
+            ON CONFLICT(host) DO UPDATE SET
+                requests=requests + excluded.requests,
+                retries=retries + excluded.retries,
+                failures=failures + excluded.failures,
+                disallowed=disallowed + excluded.disallowed,
+                last_error=COALESCE(excluded.last_error, last_error),
+                last_success_at=COALESCE(excluded.last_success_at, last_success_at),
+                updated_at=datetime('now')",
+            (
+                host,
+                attempts,
+                attempts.saturating_sub(1),
+                error.is_some(),
+                *outcome == HttpOutcome::Disallowed,
+                error,
+                *outcome == HttpOutcome::Fetched,
+            ),
+        )
+    }
+
+    /// Totals for every host fetched from, busiest first.
+    pub fn get_http_metrics(&self) -> Result<Vec<HttpMetrics>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT host, requests, retries, failures, disallowed, last_error, last_success_at, updated_at
+            FROM http_metrics ORDER BY requests DESC, host",
+        )?;
+        let metrics = stmt.query_map((), |row| {
+            Ok(HttpMetrics {
+                host: row.get(0)?,
+                requests: row.get(1)?,
+                retries: row.get(2)?,
+                failures: row.get(3)?,
+                disallowed: row.get(4)?,
+                last_error: row.get(5)?,
+                last_success_at: row.get(6)?,
+                updated_at: row.get(7)?,
+            })
+        })?;
+        metrics.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn totals_fetches_per_host() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        store
+            .record_http_fetch("ufcstats.com", 3, &HttpOutcome::Fetched)
+            .unwrap();
+        store
+            .record_http_fetch(
+                "ufcstats.com",
+                4,
+                &HttpOutcome::Failed("The server responded 503".to_string()),
+            )
+            .unwrap();
+        store
+            .record_http_fetch("ufcstats.com", 0, &HttpOutcome::Disallowed)
+            .unwrap();
+
+        let metrics = store.get_http_metrics().unwrap();
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].requests, 7);
+        assert_eq!(metrics[0].retries, 5);
+        assert_eq!(metrics[0].failures, 1);
+        assert_eq!(metrics[0].disallowed, 1);
+        assert_eq!(
+            metrics[0].last_error.as_deref(),
+            Some("The server responded 503")
+        );
+        assert!(metrics[0].last_success_at.is_some());
+    }
+}
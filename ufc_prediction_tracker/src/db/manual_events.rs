@@ -0,0 +1,490 @@
+use super::{names, tickets::reopen_tickets, Bout, Store};
+use rusqlite::{OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The link recorded for events entered by hand, since every event needs a link to be routed to.
+pub const MANUAL_LINK: &str = "manual";
+
+/// How a hand-entered event's card lines up with the scraped card that replaces it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Reconciliation {
+    /// (hand-entered, scraped) for bouts on both cards.
+    pub matched: Vec<(Bout, Bout)>,
+    /// Hand-entered bouts that aren't on the scraped card. Picks on them are kept, like picks on
+    /// any bout that drops off a card.
+    pub unmatched: Vec<Bout>,
+}
+
+impl Reconciliation {
+    /// The scraped spelling of a fighter on a matched bout, or the name as it is otherwise.
+    fn scraped_name<'a>(&'a self, name: &'a str) -> &'a str {
+        for (manual, scraped) in &self.matched {
+            let flipped = !names::same_fighter(&manual.fighter1, &scraped.fighter1);
+            if name == manual.fighter1 {
+                return if flipped {
+                    &scraped.fighter2
+                } else {
+                    &scraped.fighter1
+                };
+            }
+            if name == manual.fighter2 {
+                return if flipped {
+                    &scraped.fighter1
+                } else {
+                    &scraped.fighter2
+                };
+            }
+        }
+        name
+    }
+}
+
+/// Lines hand-entered bouts up with scraped ones between the same two fighters, in either order,
+/// however each card spells their names.
+pub fn match_bouts(manual: &[Bout], scraped: &[Bout]) -> Reconciliation {
+    let same_bout = |a: &Bout, b: &Bout| {
+        (names::same_fighter(&a.fighter1, &b.fighter1)
+            && names::same_fighter(&a.fighter2, &b.fighter2))
+            || (names::same_fighter(&a.fighter1, &b.fighter2)
+                && names::same_fighter(&a.fighter2, &b.fighter1))
+    };
+    let mut reconciliation = Reconciliation::default();
+    for bout in manual {
+        match scraped.iter().find(|s| same_bout(bout, s)) {
+            Some(s) => reconciliation.matched.push((bout.clone(), s.clone())),
+            None => reconciliation.unmatched.push(bout.clone()),
+        }
+    }
+    reconciliation
+}
+
+impl Store {
+    pub fn add_manual_event(&self, name: &str, date: &str, promotion: &str) -> Result<usize> {
+        let id = self.add_event(name.trim(), date, MANUAL_LINK)?;
+        self.set_event_promotion(id, promotion)?;
+        Ok(id)
+    }
+
+    /// Adds a bout at the bottom of the card.
+    pub fn add_bout(
+        &self,
+        event_id: usize,
+        fighter1: &str,
+        fighter2: &str,
+        weight_class: Option<&str>,
+    ) -> Result<usize> {
+        let position = self.get_bouts(event_id)?.len();
+        self.upsert_bout(&Bout {
+            event_id,
+            position,
+            fighter1: fighter1.trim().to_string(),
+            fighter2: fighter2.trim().to_string(),
+            weight_class: weight_class.map(|w| w.trim().to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Takes a bout and its result off the card, closing up the running order. Picks on it are
+    /// kept, as they are when a bout drops off a scraped card.
+    pub fn remove_bout(&self, event_id: usize, fighter1: &str, fighter2: &str) -> Result<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let position: Option<usize> = tx
+            .query_row(
+                "SELECT position FROM bouts WHERE event_id=?1
+                AND ((fighter1=?2 AND fighter2=?3) OR (fighter1=?3 AND fighter2=?2))",
+                (event_id, fighter1, fighter2),
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(position) = position else {
+            return Ok(0);
+        };
+        tx.execute(
+            "DELETE FROM bouts WHERE event_id=?1
+            AND ((fighter1=?2 AND fighter2=?3) OR (fighter1=?3 AND fighter2=?2))",
+            (event_id, fighter1, fighter2),
+        )?;
+        tx.execute(
+            "DELETE FROM results WHERE event_id=?1
+            AND ((winner=?2 AND loser=?3) OR (winner=?3 AND loser=?2))",
+            (event_id, fighter1, fighter2),
+        )?;
+        tx.execute(
+            "UPDATE bouts SET position=position - 1 WHERE event_id=?1 AND position > ?2",
+            (event_id, position),
+        )?;
+        tx.commit()?;
+        Ok(1)
+    }
+
+    /// Moves the bout at position `from` to `to`, shifting the bouts in between.
+    pub fn move_bout(&self, event_id: usize, from: usize, to: usize) -> Result<()> {
+        let mut bouts = self.get_bouts(event_id)?;
+        if from >= bouts.len() || to >= bouts.len() {
+            return Ok(());
+        }
+        let bout = bouts.remove(from);
+        bouts.insert(to, bout);
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for (position, bout) in bouts.iter().enumerate() {
+            tx.execute(
+                "UPDATE bouts SET position=?2 WHERE event_id=?1 AND fighter1=?3 AND fighter2=?4",
+                (event_id, position, &bout.fighter1, &bout.fighter2),
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Takes a result entered by hand back off a bout, reopening tickets settled on it.
+    pub fn clear_bout_result(&self, event_id: usize, fighter1: &str, fighter2: &str) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE bouts SET outcome=NULL, winner=NULL, method=NULL, round=NULL, time=NULL
+            WHERE event_id=?1 AND ((fighter1=?2 AND fighter2=?3) OR (fighter1=?3 AND fighter2=?2))",
+            (event_id, fighter1, fighter2),
+        )?;
+        tx.execute(
+            "DELETE FROM results WHERE event_id=?1
+            AND ((winner=?2 AND loser=?3) OR (winner=?3 AND loser=?2))",
+            (event_id, fighter1, fighter2),
+        )?;
+        reopen_tickets(&tx, event_id, fighter1, fighter2)?;
+        tx.commit()
+    }
+
+    /// Folds a hand-entered event into the scraped event that replaced it, then deletes it.
+    ///
+    /// Picks, ticket legs, contest picks and corrections move to the scraped event, renamed to the
+    /// scraped spelling of each fighter. Where a user already picked the bout on the scraped event,
+    /// that pick stands. Results entered by hand carry over to bouts the scraped card has no result
+    /// for yet.
+    pub fn reconcile_event(&self, manual_id: usize, scraped_id: usize) -> Result<Reconciliation> {
+        let reconciliation = match_bouts(&self.get_bouts(manual_id)?, &self.get_bouts(scraped_id)?);
+        // the scraped event's spelling of every fighter picked, including on bouts it doesn't have
+        let picked = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT winner FROM predictions WHERE event_id=?1
+                UNION SELECT loser FROM predictions WHERE event_id=?1",
+            )?;
+            let names = stmt.query_map((manual_id,), |row| row.get::<_, String>(0))?;
+            names.collect::<Result<Vec<_>>>()?
+        };
+        let mut resolved = HashMap::new();
+        for name in picked {
+            let scraped =
+                self.resolve_fighter_name(Some(scraped_id), reconciliation.scraped_name(&name))?;
+            resolved.insert(name, scraped);
+        }
+        let scraped_name = |name: &str| {
+            resolved
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| reconciliation.scraped_name(name).to_string())
+        };
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let picks = {
+            let mut stmt =
+                tx.prepare("SELECT winner, loser, user FROM predictions WHERE event_id=?1")?;
+            let picks = stmt.query_map((manual_id,), |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?;
+            picks.collect::<Result<Vec<_>>>()?
+        };
+        for (winner, loser, user) in &picks {
+            let (winner, loser) = (scraped_name(winner), scraped_name(loser));
+            tx.execute(
+                "INSERT INTO predictions (event_id, winner, loser, user)
+                SELECT ?1, ?2, ?3, ?4 WHERE NOT EXISTS (
+                    SELECT 1 FROM predictions WHERE event_id=?1 AND user=?4
+                    AND ((winner=?2 AND loser=?3) OR (winner=?3 AND loser=?2))
+                )",
+                (scraped_id, winner, loser, user),
+            )?;
+        }
+
+        for (manual, scraped) in &reconciliation.matched {
+            for (from, to) in [
+                (
+                    &manual.fighter1,
+                    reconciliation.scraped_name(&manual.fighter1),
+                ),
+                (
+                    &manual.fighter2,
+                    reconciliation.scraped_name(&manual.fighter2),
+                ),
+            ] {
+                for (table, column) in [
+                    ("ticket_legs", "winner"),
+                    ("ticket_legs", "loser"),
+                    ("contest_picks", "winner"),
+                    ("contest_picks", "loser"),
+                    ("result_overrides", "winner"),
+                    ("result_overrides", "loser"),
+                    ("result_overrides", "previous_winner"),
+                ] {
+                    tx.execute(
+                        &format!(
+                            "UPDATE OR IGNORE {table} SET {column}=?3 WHERE event_id=?1 AND {column}=?2"
+                        ),
+                        (manual_id, from, to),
+                    )?;
+                }
+            }
+            if let (Some(winner), None) = (&manual.winner, &scraped.outcome) {
+                let winner = reconciliation.scraped_name(winner);
+                let loser = if winner == scraped.fighter1 {
+                    &scraped.fighter2
+                } else {
+                    &scraped.fighter1
+                };
+                tx.execute(
+                    "UPDATE bouts SET outcome=?4, winner=?2, method=?5, round=?6, time=?7
+                    WHERE event_id=?1 AND ((fighter1=?2 AND fighter2=?3) OR (fighter1=?3 AND fighter2=?2))",
+                    (
+                        scraped_id,
+                        winner,
+                        loser,
+                        manual.outcome.map(|o| o.as_str()),
+                        &manual.method,
+                        manual.round,
+                        &manual.time,
+                    ),
+                )?;
+                tx.execute(
+                    "INSERT OR IGNORE INTO results (event_id, winner, loser) VALUES (?1, ?2, ?3)",
+                    (scraped_id, winner, loser),
+                )?;
+            } else if let (Some(outcome), None) = (manual.outcome, &scraped.outcome) {
+                tx.execute(
+                    "UPDATE bouts SET outcome=?2 WHERE event_id=?1 AND fighter1=?3 AND fighter2=?4",
+                    (
+                        scraped_id,
+                        outcome.as_str(),
+                        &scraped.fighter1,
+                        &scraped.fighter2,
+                    ),
+                )?;
+            }
+        }
+
+        for table in [
+            "ticket_legs",
+            "contest_picks",
+            "result_overrides",
+            "notifications",
+        ] {
+            tx.execute(
+                &format!("UPDATE OR IGNORE {table} SET event_id=?2 WHERE event_id=?1"),
+                (manual_id, scraped_id),
+            )?;
+        }
+        for table in [
+            "predictions",
+            "results",
+            "bouts",
+            "contest_picks",
+            "notifications",
+        ] {
+            tx.execute(
+                &format!("DELETE FROM {table} WHERE event_id=?1"),
+                (manual_id,),
+            )?;
+        }
+        tx.execute("DELETE FROM events WHERE id=?1", (manual_id,))?;
+        tx.commit()?;
+        Ok(reconciliation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{BoutOutcome, Settlement, DEFAULT_USER};
+
+    fn bout(fighter1: &str, fighter2: &str) -> Bout {
+        Bout {
+            fighter1: fighter1.to_string(),
+            fighter2: fighter2.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn matches_bouts_by_fighter_names() {
+        let manual = [bout("jon jones", "Stipe  Miocic"), bout("A", "B")];
+        let scraped = [bout("Stipe Miocic", "Jon Jones"), bout("C", "D")];
+        let reconciliation = match_bouts(&manual, &scraped);
+        assert_eq!(reconciliation.matched.len(), 1);
+        assert_eq!(reconciliation.unmatched, vec![bout("A", "B")]);
+        assert_eq!(reconciliation.scraped_name("jon jones"), "Jon Jones");
+        assert_eq!(reconciliation.scraped_name("Stipe  Miocic"), "Stipe Miocic");
+        assert_eq!(reconciliation.scraped_name("A"), "A");
+    }
+
+    #[test]
+    fn edits_a_card_by_hand() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let id = store
+            .add_manual_event("UFC 330", "2026-03-07", "UFC")
+            .unwrap();
+        for (f1, f2) in [("A", "B"), ("C", "D"), ("E", "F")] {
+            store.add_bout(id, f1, f2, None).unwrap();
+        }
+        store.move_bout(id, 2, 0).unwrap();
+        store.remove_bout(id, "D", "C").unwrap();
+        let card = store.get_bouts(id).unwrap();
+        assert_eq!(
+            card.iter()
+                .map(|b| (b.position, b.fighter1.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(0, "E"), (1, "A")]
+        );
+
+        store
+            .record_bout(&Bout {
+                event_id: id,
+                outcome: Some(BoutOutcome::Win),
+                winner: Some("B".to_string()),
+                ..card[1].clone()
+            })
+            .unwrap();
+        assert_eq!(store.get_results(id).unwrap().len(), 1);
+        store.clear_bout_result(id, "A", "B").unwrap();
+        assert!(store.get_results(id).unwrap().is_empty());
+        assert_eq!(store.get_bouts(id).unwrap()[1].outcome, None);
+    }
+
+    #[test]
+    fn changing_a_result_resettles_tickets() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let id = store
+            .add_manual_event("UFC 330", "2026-03-07", "UFC")
+            .unwrap();
+        store.add_bout(id, "A", "B", None).unwrap();
+        let ticket = store
+            .create_ticket(
+                DEFAULT_USER,
+                10.0,
+                &[crate::db::TicketLeg {
+                    event_id: id,
+                    winner: "A".to_string(),
+                    loser: "B".to_string(),
+                    odds: 2.0,
+                    ..Default::default()
+                }],
+            )
+            .unwrap();
+        let result = |winner: &str| Bout {
+            event_id: id,
+            outcome: Some(BoutOutcome::Win),
+            winner: Some(winner.to_string()),
+            ..bout("A", "B")
+        };
+        let status = || {
+            let tickets = store.get_tickets(DEFAULT_USER).unwrap();
+            assert_eq!(tickets[0].id, ticket);
+            (tickets[0].status, tickets[0].legs[0].status)
+        };
+
+        store.record_bout(&result("A")).unwrap();
+        store.settle_tickets(id).unwrap();
+        assert_eq!(status(), (Settlement::Won, Settlement::Won));
+        // as set_manual_result changes it: off, then the new result on
+        store.clear_bout_result(id, "A", "B").unwrap();
+        assert_eq!(status(), (Settlement::Pending, Settlement::Pending));
+        store.record_bout(&result("B")).unwrap();
+        store.settle_tickets(id).unwrap();
+        assert_eq!(status(), (Settlement::Lost, Settlement::Lost));
+    }
+
+    #[test]
+    fn reconciles_picks_and_results_onto_the_scraped_event() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let manual = store
+            .add_manual_event("UFC 330", "2026-03-07", "UFC")
+            .unwrap();
+        store
+            .add_bout(manual, "jon jones", "Stipe Miocic", None)
+            .unwrap();
+        store.add_bout(manual, "A", "B", None).unwrap();
+        store.add_bout(manual, "C", "D", None).unwrap();
+        store
+            .record_bout(&Bout {
+                event_id: manual,
+                position: 2,
+                outcome: Some(BoutOutcome::Win),
+                winner: Some("D".to_string()),
+                ..bout("C", "D")
+            })
+            .unwrap();
+        store
+            .add_or_update_prediction(manual, "jon jones", "Stipe Miocic")
+            .unwrap();
+        store.add_or_update_prediction(manual, "A", "B").unwrap();
+        store
+            .add_or_update_prediction_for_user(manual, "Stipe Miocic", "jon jones", "friend")
+            .unwrap();
+
+        let scraped = store
+            .add_event(
+                "UFC 330: Jones vs. Miocic",
+                "2026-03-07",
+                "http://ufcstats.com/event-details/x",
+            )
+            .unwrap();
+        for (position, (f1, f2)) in [("Jon Jones", "Stipe Miocic"), ("C", "D")]
+            .into_iter()
+            .enumerate()
+        {
+            store
+                .upsert_bout(&Bout {
+                    event_id: scraped,
+                    position,
+                    ..bout(f1, f2)
+                })
+                .unwrap();
+        }
+        // picked on the scraped event too, which stands
+        store
+            .add_or_update_prediction_for_user(scraped, "Jon Jones", "Stipe Miocic", "friend")
+            .unwrap();
+
+        let reconciliation = store.reconcile_event(manual, scraped).unwrap();
+        assert_eq!(reconciliation.matched.len(), 2);
+        assert_eq!(reconciliation.unmatched.len(), 1);
+        assert!(store.get_event(manual).is_err());
+        let mut mine = store
+            .get_predictions_for_user(scraped, DEFAULT_USER)
+            .unwrap();
+        mine.sort();
+        assert_eq!(
+            mine,
+            vec![
+                ("A".to_string(), "B".to_string()),
+                ("Jon Jones".to_string(), "Stipe Miocic".to_string()),
+            ]
+        );
+        assert_eq!(
+            store.get_predictions_for_user(scraped, "friend").unwrap(),
+            vec![("Jon Jones".to_string(), "Stipe Miocic".to_string())]
+        );
+        assert_eq!(
+            store.get_results(scraped).unwrap(),
+            vec![("D".to_string(), "C".to_string())]
+        );
+    }
+}
@@ -1,2 +1,36 @@
 mod db;
 pub use db::*;
+mod accounts;
+pub use accounts::*;
+mod aliases;
+pub use aliases::*;
+mod backfill;
+pub use backfill::*;
+mod backtests;
+pub use backtests::*;
+mod bouts;
+pub use bouts::*;
+mod contests;
+pub use contests::*;
+pub mod dates;
+mod fighters;
+pub use fighters::*;
+mod history;
+pub use history::*;
+mod http_metrics;
+pub use http_metrics::*;
+mod manual_events;
+pub use manual_events::*;
+pub mod names;
+mod notifications;
+pub use notifications::*;
+mod overrides;
+pub use overrides::*;
+mod parser_health;
+pub use parser_health::*;
+mod scoring;
+pub use scoring::*;
+mod stats;
+pub use stats::*;
+mod tickets;
+pub use tickets::*;
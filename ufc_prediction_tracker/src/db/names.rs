@@ -0,0 +1,136 @@
+//! Telling whether two spellings of a fighter's name are the same fighter.
+//!
+//! ufcstats, card files and admins don't always agree on a name: "José Aldo" and "Jose Aldo",
+//! "Alex Volkanovski" and "Alexander Volkanovski", or a nickname in quotes. Names are compared in
+//! a normal form, and a first name matches its usual short forms when the rest of the name agrees.
+
+// short forms of first names that aren't just the start of the full name
+const NICKNAMES: [(&str, &str); 16] = [
+    ("bill", "william"),
+    ("billy", "william"),
+    ("bob", "robert"),
+    ("bobby", "robert"),
+    ("chuck", "charles"),
+    ("dick", "richard"),
+    ("jim", "james"),
+    ("jimmy", "james"),
+    ("joe", "joseph"),
+    ("joey", "joseph"),
+    ("johnny", "john"),
+    ("mike", "michael"),
+    ("rick", "richard"),
+    ("sasha", "alexander"),
+    ("tony", "anthony"),
+    ("zach", "zachary"),
+];
+
+// a prefix shorter than this isn't taken as a short form, so "Al" doesn't match "Alexander"
+const MIN_SHORT_FORM_LEN: usize = 3;
+
+/// A name as it's compared: nicknames in double quotes dropped, accents folded, lowercase, with
+/// apostrophes removed and other punctuation as single spaces.
+pub fn normalize(name: &str) -> String {
+    let mut folded = String::new();
+    let mut in_quotes = false;
+    for c in name.chars() {
+        if matches!(c, '"' | '“' | '”') {
+            in_quotes = !in_quotes;
+            continue;
+        }
+        if in_quotes || matches!(c, '\'' | '’' | '`') {
+            continue;
+        }
+        for c in c.to_lowercase() {
+            match fold(c) {
+                Some(ascii) => folded.push_str(ascii),
+                None if c.is_alphanumeric() => folded.push(c),
+                None => folded.push(' '),
+            }
+        }
+    }
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Whether two names are the same fighter: equal once normalized, or with the same surname and
+/// first names where one is a short form of the other.
+pub fn same_fighter(a: &str, b: &str) -> bool {
+    let (a, b) = (normalize(a), normalize(b));
+    if a == b {
+        return true;
+    }
+    let (Some((first_a, rest_a)), Some((first_b, rest_b))) = (a.split_once(' '), b.split_once(' '))
+    else {
+        return false;
+    };
+    if rest_a != rest_b {
+        return false;
+    }
+    let (first_a, first_b) = (full_first_name(first_a), full_first_name(first_b));
+    let (short, long) = if first_a.len() <= first_b.len() {
+        (first_a, first_b)
+    } else {
+        (first_b, first_a)
+    };
+    short == long || (short.len() >= MIN_SHORT_FORM_LEN && long.starts_with(short))
+}
+
+fn full_first_name(first: &str) -> &str {
+    NICKNAMES
+        .iter()
+        .find(|(nickname, _)| *nickname == first)
+        .map_or(first, |(_, full)| full)
+}
+
+// the plain letters for accented Latin letters, which is where fighters' names differ in practice
+fn fold(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'č' => "c",
+        'ď' | 'đ' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+        'ğ' => "g",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' | 'ı' => "i",
+        'ł' | 'ľ' => "l",
+        'ñ' | 'ń' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'œ' => "oe",
+        'ř' => "r",
+        'ś' | 'š' | 'ş' | 'ș' => "s",
+        'ß' => "ss",
+        'ť' | 'ţ' | 'ț' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => "u",
+        'ý' | 'ÿ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_spelling_differences() {
+        assert_eq!(normalize("José  Aldo"), "jose aldo");
+        assert_eq!(normalize("Jan Błachowicz"), "jan blachowicz");
+        assert_eq!(normalize("Sean O'Malley"), "sean omalley");
+        assert_eq!(normalize("Georges St-Pierre"), "georges st pierre");
+        assert_eq!(
+            normalize("Alexander \"The Great\" Volkanovski"),
+            "alexander volkanovski"
+        );
+    }
+
+    #[test]
+    fn matches_short_forms_of_first_names() {
+        assert!(same_fighter("Alex Volkanovski", "Alexander Volkanovski"));
+        assert!(same_fighter("Mike Perry", "Michael Perry"));
+        assert!(same_fighter("Zach Reese", "Zachary Reese"));
+        assert!(same_fighter("jose aldo", "José Aldo"));
+        assert!(!same_fighter("Al Iaquinta", "Alexander Iaquinta"));
+        assert!(!same_fighter("Alex Pereira", "Alexandre Pantoja"));
+        assert!(!same_fighter("Aldo", "Jose Aldo"));
+    }
+}
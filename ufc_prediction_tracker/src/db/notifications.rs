@@ -0,0 +1,253 @@
+use super::Store;
+use rusqlite::{OptionalExtension, Result, Row};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NotificationKind {
+    /// An event the user can still pick locks within a day.
+    #[default]
+    EventLock,
+    /// A bout the user picked is no longer on the card.
+    CardChanged,
+    /// An event the user picked has its results in.
+    ResultsIn,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::EventLock => "event_lock",
+            NotificationKind::CardChanged => "card_changed",
+            NotificationKind::ResultsIn => "results_in",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "event_lock" => Some(NotificationKind::EventLock),
+            "card_changed" => Some(NotificationKind::CardChanged),
+            "results_in" => Some(NotificationKind::ResultsIn),
+            _ => None,
+        }
+    }
+}
+
+/// Where a user wants to be notified, and about what. Users without saved preferences get nothing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationPrefs {
+    pub user: String,
+    /// Gets a JSON POST for every notification.
+    pub webhook_url: Option<String>,
+    pub email: Option<String>,
+    /// Shows notifications on the desktop the app runs on.
+    pub desktop: bool,
+    pub event_lock: bool,
+    pub card_changed: bool,
+    pub results_in: bool,
+}
+
+impl NotificationPrefs {
+    pub fn new(user: &str) -> Self {
+        NotificationPrefs {
+            user: user.to_string(),
+            webhook_url: None,
+            email: None,
+            desktop: false,
+            event_lock: true,
+            card_changed: true,
+            results_in: true,
+        }
+    }
+
+    pub fn wants(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::EventLock => self.event_lock,
+            NotificationKind::CardChanged => self.card_changed,
+            NotificationKind::ResultsIn => self.results_in,
+        }
+    }
+}
+
+/// A notification waiting to be sent, or already sent. `key` tells apart notifications of the same
+/// kind for the same event, so each is only queued once.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: usize,
+    pub user: String,
+    pub kind: NotificationKind,
+    pub event_id: usize,
+    pub key: String,
+    pub title: String,
+    pub body: String,
+    pub created_at: String,
+    pub sent_at: Option<String>,
+}
+
+/// What's wrong with the preferences, if anything.
+pub fn check_notification_prefs(prefs: &NotificationPrefs) -> Option<String> {
+    if let Some(url) = &prefs.webhook_url {
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Some("The webhook URL has to start with http:// or https://".to_string());
+        }
+    }
+    if let Some(email) = &prefs.email {
+        if !email.contains('@') {
+            return Some(format!("{} isn't an email address", email));
+        }
+    }
+    None
+}
+
+fn prefs_from_row(row: &Row<'_>) -> Result<NotificationPrefs> {
+    Ok(NotificationPrefs {
+        user: row.get(0)?,
+        webhook_url: row.get(1)?,
+        email: row.get(2)?,
+        desktop: row.get(3)?,
+        event_lock: row.get(4)?,
+        card_changed: row.get(5)?,
+        results_in: row.get(6)?,
+    })
+}
+
+const PREFS_COLUMNS: &str =
+    "user, webhook_url, email, desktop, event_lock, card_changed, results_in";
+
+impl Store {
+    /// The user's saved preferences, or the defaults when they haven't saved any.
+    pub fn get_notification_prefs(&self, user: &str) -> Result<NotificationPrefs> {
+        let conn = self.conn.lock().unwrap();
+        let prefs = conn
+            .query_row(
+                &format!("SELECT {PREFS_COLUMNS} FROM notification_prefs WHERE user=?1"),
+                (user,),
+                prefs_from_row,
+            )
+            .optional()?;
+        Ok(prefs.unwrap_or_else(|| NotificationPrefs::new(user)))
+    }
+
+    /// Every user's saved preferences wanting notifications of `kind`.
+    pub fn get_notification_prefs_for(
+        &self,
+        kind: NotificationKind,
+    ) -> Result<Vec<NotificationPrefs>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {PREFS_COLUMNS} FROM notification_prefs ORDER BY user"
+        ))?;
+        let prefs = stmt.query_map((), prefs_from_row)?;
+        Ok(prefs
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .filter(|prefs| prefs.wants(kind))
+            .collect())
+    }
+
+    pub fn set_notification_prefs(&self, prefs: &NotificationPrefs) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            &format!(
+                "INSERT OR REPLACE INTO notification_prefs ({PREFS_COLUMNS})
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+            ),
+            (
+                &prefs.user,
+                &prefs.webhook_url,
+                &prefs.email,
+                prefs.desktop,
+                prefs.event_lock,
+                prefs.card_changed,
+                prefs.results_in,
+            ),
+        )
+    }
+
+    /// Queues a notification unless the same one was queued before. Returns whether it was queued.
+    pub fn queue_notification(&self, notification: &Notification) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let queued = conn.execute(
+            "INSERT OR IGNORE INTO notifications (user, kind, event_id, key, title, body)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            (
+                &notification.user,
+                notification.kind.as_str(),
+                notification.event_id,
+                &notification.key,
+                &notification.title,
+                &notification.body,
+            ),
+        )?;
+        Ok(queued == 1)
+    }
+
+    /// Notifications still to be sent, oldest first.
+    pub fn get_unsent_notifications(&self) -> Result<Vec<Notification>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, user, kind, event_id, key, title, body, created_at, sent_at
+            FROM notifications WHERE sent_at IS NULL ORDER BY id",
+        )?;
+        let notifications = stmt.query_map((), |row| {
+            Ok(Notification {
+                id: row.get(0)?,
+                user: row.get(1)?,
+                kind: NotificationKind::parse(&row.get::<_, String>(2)?).unwrap_or_default(),
+                event_id: row.get(3)?,
+                key: row.get(4)?,
+                title: row.get(5)?,
+                body: row.get(6)?,
+                created_at: row.get(7)?,
+                sent_at: row.get(8)?,
+            })
+        })?;
+        notifications.collect()
+    }
+
+    pub fn mark_notification_sent(&self, id: usize) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE notifications SET sent_at=datetime('now') WHERE id=?1",
+            (id,),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notifications_are_queued_once() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        assert!(store.get_notification_prefs("me").unwrap().results_in);
+        store
+            .set_notification_prefs(&NotificationPrefs {
+                results_in: false,
+                ..NotificationPrefs::new("me")
+            })
+            .unwrap();
+        store
+            .set_notification_prefs(&NotificationPrefs::new("friend"))
+            .unwrap();
+        let wanting = store
+            .get_notification_prefs_for(NotificationKind::ResultsIn)
+            .unwrap();
+        assert_eq!(wanting, vec![NotificationPrefs::new("friend")]);
+
+        let notification = Notification {
+            user: "friend".to_string(),
+            kind: NotificationKind::ResultsIn,
+            event_id: 1,
+            title: "Results are in".to_string(),
+            ..Default::default()
+        };
+        assert!(store.queue_notification(&notification).unwrap());
+        assert!(!store.queue_notification(&notification).unwrap());
+        let unsent = store.get_unsent_notifications().unwrap();
+        assert_eq!(unsent.len(), 1);
+        store.mark_notification_sent(unsent[0].id).unwrap();
+        assert!(store.get_unsent_notifications().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+use super::{tickets::reopen_tickets, Bout, BoutOutcome, Store};
+use rusqlite::{OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+
+/// An admin's correction of a bout's result, kept as an audit trail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultOverride {
+    pub id: usize,
+    pub event_id: usize,
+    pub outcome: BoutOutcome,
+    /// For a win, who won; otherwise one of the two fighters.
+    pub winner: String,
+    pub loser: String,
+    /// The winner recorded before the correction, if there was one.
+    pub previous_winner: Option<String>,
+    pub admin: String,
+    pub reason: String,
+    pub created_at: String,
+}
+
+impl Store {
+    /// Records `result`'s outcome and winner for the bout between its two fighters, whatever was
+    /// recorded before, and notes who made the correction and why. Later syncs leave the bout's
+    /// result alone. Tickets with a leg on the bout are settled again.
+    pub fn override_result(&self, result: &Bout, admin: &str, reason: &str) -> Result<usize> {
+        let event_id = result.event_id;
+        let outcome = result.outcome.unwrap_or(BoutOutcome::Win);
+        let winner = result
+            .winner
+            .as_deref()
+            .filter(|_| outcome == BoutOutcome::Win);
+        // a win is stored winner first, as in results
+        let (fighter1, fighter2) = match (winner, result.loser()) {
+            (Some(winner), Some(loser)) => (winner, loser),
+            _ => (result.fighter1.as_str(), result.fighter2.as_str()),
+        };
+        let id = {
+            let mut conn = self.conn.lock().unwrap();
+            let tx = conn.transaction()?;
+            let previous_winner: Option<String> = tx
+                .query_row(
+                    "SELECT winner FROM results WHERE event_id=?1
+                    AND ((winner=?2 AND loser=?3) OR (winner=?3 AND loser=?2))",
+                    (event_id, fighter1, fighter2),
+                    |row| row.get(0),
+                )
+                .optional()?;
+            tx.execute(
+                "DELETE FROM results WHERE event_id=?1
+                AND ((winner=?2 AND loser=?3) OR (winner=?3 AND loser=?2))",
+                (event_id, fighter1, fighter2),
+            )?;
+            if winner.is_some() {
+                tx.execute(
+                    "INSERT INTO results (event_id, winner, loser) VALUES (?1, ?2, ?3)",
+                    (event_id, fighter1, fighter2),
+                )?;
+            }
+            tx.execute(
+                "UPDATE bouts SET outcome=?4, winner=?5 WHERE event_id=?1
+                AND ((fighter1=?2 AND fighter2=?3) OR (fighter1=?3 AND fighter2=?2))",
+                (event_id, fighter1, fighter2, outcome.as_str(), winner),
+            )?;
+            tx.execute(
+                "INSERT INTO result_overrides (event_id, outcome, winner, loser, previous_winner, admin, reason)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                (
+                    event_id,
+                    outcome.as_str(),
+                    fighter1,
+                    fighter2,
+                    &previous_winner,
+                    admin,
+                    reason,
+                ),
+            )?;
+            let id = tx.last_insert_rowid() as usize;
+            // reopen whatever was settled on the old result
+            reopen_tickets(&tx, event_id, fighter1, fighter2)?;
+            tx.commit()?;
+            id
+        };
+        self.settle_tickets(event_id)?;
+        Ok(id)
+    }
+
+    /// Whether an admin has corrected the bout between the two fighters.
+    pub fn is_result_overridden(
+        &self,
+        event_id: usize,
+        fighter1: &str,
+        fighter2: &str,
+    ) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM result_overrides WHERE event_id=?1
+            AND ((winner=?2 AND loser=?3) OR (winner=?3 AND loser=?2)))",
+            (event_id, fighter1, fighter2),
+            |row| row.get(0),
+        )
+    }
+
+    /// Corrections made to the event's results, newest first.
+    pub fn get_result_overrides(&self, event_id: usize) -> Result<Vec<ResultOverride>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, event_id, outcome, winner, loser, previous_winner, admin, reason, created_at
+            FROM result_overrides WHERE event_id=?1 ORDER BY id DESC",
+        )?;
+        let overrides = stmt.query_map((event_id,), |row| {
+            Ok(ResultOverride {
+                id: row.get(0)?,
+                event_id: row.get(1)?,
+                outcome: BoutOutcome::parse(&row.get::<_, String>(2)?).unwrap_or(BoutOutcome::Win),
+                winner: row.get(3)?,
+                loser: row.get(4)?,
+                previous_winner: row.get(5)?,
+                admin: row.get(6)?,
+                reason: row.get(7)?,
+                created_at: row.get(8)?,
+            })
+        })?;
+        overrides.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Settlement, TicketLeg, DEFAULT_USER};
+
+    #[test]
+    fn overrides_stick_through_later_syncs() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let event = store.add_event("UFC 1", "2025-01-01", "").unwrap();
+        let scraped = Bout {
+            event_id: event,
+            fighter1: "A".to_string(),
+            fighter2: "B".to_string(),
+            outcome: Some(BoutOutcome::Win),
+            winner: Some("A".to_string()),
+            ..Default::default()
+        };
+        store
+            .create_ticket(
+                DEFAULT_USER,
+                10.0,
+                &[TicketLeg {
+                    event_id: event,
+                    winner: "B".to_string(),
+                    loser: "A".to_string(),
+                    odds: 3.0,
+                    ..Default::default()
+                }],
+            )
+            .unwrap();
+        store.record_bout(&scraped).unwrap();
+        store.settle_tickets(event).unwrap();
+        assert_eq!(
+            store.get_tickets(DEFAULT_USER).unwrap()[0].status,
+            Settlement::Lost
+        );
+
+        store
+            .override_result(
+                &Bout {
+                    winner: Some("B".to_string()),
+                    ..scraped.clone()
+                },
+                "admin",
+                "scored the wrong way round",
+            )
+            .unwrap();
+        store.record_bout(&scraped).unwrap();
+
+        assert_eq!(
+            store.get_results(event).unwrap(),
+            vec![("B".to_string(), "A".to_string())]
+        );
+        assert_eq!(
+            store.get_bouts(event).unwrap()[0].winner.as_deref(),
+            Some("B")
+        );
+        let ticket = &store.get_tickets(DEFAULT_USER).unwrap()[0];
+        assert_eq!(ticket.status, Settlement::Won);
+        assert_eq!(ticket.payout, Some(30.0));
+        let audit = store.get_result_overrides(event).unwrap();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].previous_winner.as_deref(), Some("A"));
+        assert_eq!(audit[0].admin, "admin");
+    }
+
+    #[test]
+    fn overrides_record_draws_on_undecided_bouts() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let event = store.add_event("UFC 1", "2025-01-01", "").unwrap();
+        let undecided = Bout {
+            event_id: event,
+            fighter1: "A".to_string(),
+            fighter2: "B".to_string(),
+            ..Default::default()
+        };
+        store.upsert_bout(&undecided).unwrap();
+        store
+            .override_result(
+                &Bout {
+                    outcome: Some(BoutOutcome::Draw),
+                    ..undecided.clone()
+                },
+                "admin",
+                "scored a draw",
+            )
+            .unwrap();
+        store.record_bout(&undecided).unwrap();
+
+        let bout = &store.get_bouts(event).unwrap()[0];
+        assert_eq!(
+            (bout.outcome, bout.winner.as_deref()),
+            (Some(BoutOutcome::Draw), None)
+        );
+        assert!(store.get_results(event).unwrap().is_empty());
+        assert_eq!(
+            store.get_result_overrides(event).unwrap()[0].outcome,
+            BoutOutcome::Draw
+        );
+    }
+}
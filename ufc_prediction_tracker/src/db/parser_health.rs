@@ -0,0 +1,108 @@
+use super::Store;
+use rusqlite::{OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+
+/// How the last parse of a kind of ufcstats page went. `page` is a
+/// [`PageKind`](crate::parser::PageKind) as stored.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParserHealth {
+    pub page: String,
+    /// The page last parsed.
+    pub url: String,
+    pub healthy: bool,
+    /// How many events, bouts or fighters were read from it.
+    pub items: usize,
+    /// What didn't look as expected, one line each.
+    pub diagnostics: Vec<String>,
+    pub checked_at: String,
+    pub last_healthy_at: Option<String>,
+}
+
+impl Store {
+    /// Records how parsing a page went, replacing the last record for the same kind of page.
+    /// Returns whether the page just stopped parsing cleanly, so the change is only reported once.
+    pub fn record_parser_health(
+        &self,
+        page: &str,
+        url: &str,
+        items: usize,
+        diagnostics: &[String],
+    ) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let was_healthy = conn
+            .query_row(
+                "SELECT healthy FROM parser_health WHERE page=?1",
+                (page,),
+                |row| row.get::<_, bool>(0),
+            )
+            .optional()?
+            .unwrap_or(true);
+        let healthy = diagnostics.is_empty();
+        conn.execute(
+            "INSERT INTO parser_health (page, url, healthy, items, diagnostics, last_healthy_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, CASE WHEN ?3 THEN datetime('now') END)
+            ON CONFLICT(page) DO UPDATE SET
+                url=excluded.url,
+                healthy=excluded.healthy,
+                items=excluded.items,
+                diagnostics=excluded.diagnostics,
+                checked_at=datetime('now'),
+                last_healthy_at=COALESCE(excluded.last_healthy_at, last_healthy_at)",
+            (page, url, healthy, items, diagnostics.join("\n")),
+        )?;
+        Ok(was_healthy && !healthy)
+    }
+
+    /// The last parse of every kind of page checked so far.
+    pub fn get_parser_health(&self) -> Result<Vec<ParserHealth>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT page, url, healthy, items, diagnostics, checked_at, last_healthy_at
+            FROM parser_health ORDER BY page",
+        )?;
+        let health = stmt.query_map((), |row| {
+            Ok(ParserHealth {
+                page: row.get(0)?,
+                url: row.get(1)?,
+                healthy: row.get(2)?,
+                items: row.get(3)?,
+                diagnostics: row
+                    .get::<_, String>(4)?
+                    .lines()
+                    .map(str::to_string)
+                    .collect(),
+                checked_at: row.get(5)?,
+                last_healthy_at: row.get(6)?,
+            })
+        })?;
+        health.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_a_page_going_unhealthy_once() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        assert!(!store
+            .record_parser_health("event_card", "http://a", 12, &[])
+            .unwrap());
+        let drift = ["The table has no \"Method\" column".to_string()];
+        assert!(store
+            .record_parser_health("event_card", "http://b", 12, &drift)
+            .unwrap());
+        assert!(!store
+            .record_parser_health("event_card", "http://b", 12, &drift)
+            .unwrap());
+
+        let health = store.get_parser_health().unwrap();
+        assert_eq!(health.len(), 1);
+        assert!(!health[0].healthy);
+        assert_eq!(health[0].url, "http://b");
+        assert_eq!(health[0].diagnostics, drift.to_vec());
+        assert!(health[0].last_healthy_at.is_some());
+    }
+}
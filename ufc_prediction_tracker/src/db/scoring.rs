@@ -0,0 +1,329 @@
+use super::Store;
+use rusqlite::{Result, Row};
+use serde::{Deserialize, Serialize};
+
+/// How a set of predictions fared against the recorded results.
+///
+/// `pending` counts picks whose bout has no result yet (not scraped, cancelled, or still upcoming).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Score {
+    pub correct: i64,
+    pub incorrect: i64,
+    pub pending: i64,
+}
+
+impl Score {
+    pub fn decided(&self) -> i64 {
+        self.correct + self.incorrect
+    }
+
+    /// Fraction of decided picks that were correct, or `None` if nothing has been decided yet.
+    pub fn accuracy(&self) -> Option<f64> {
+        match self.decided() {
+            0 => None,
+            n => Some(self.correct as f64 / n as f64),
+        }
+    }
+}
+
+/// Narrows which predictions a score is computed over. Every field left as `None` matches everything.
+///
+/// `from` and `to` are inclusive and compared against `events.date`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoreFilter {
+    pub event_id: Option<usize>,
+    pub user: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// Only bouts this user also picked, for head-to-head comparisons.
+    pub shared_with: Option<String>,
+    pub promotion: Option<String>,
+}
+
+impl ScoreFilter {
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    pub fn for_user(user: &str) -> Self {
+        Self::default().user(user)
+    }
+
+    pub fn event(mut self, event_id: usize) -> Self {
+        self.event_id = Some(event_id);
+        self
+    }
+
+    pub fn user(mut self, user: &str) -> Self {
+        self.user = Some(user.to_string());
+        self
+    }
+
+    pub fn between(mut self, from: &str, to: &str) -> Self {
+        self.from = Some(from.to_string());
+        self.to = Some(to.to_string());
+        self
+    }
+
+    pub fn shared_with(mut self, user: &str) -> Self {
+        self.shared_with = Some(user.to_string());
+        self
+    }
+
+    pub fn promotion(mut self, promotion: &str) -> Self {
+        self.promotion = Some(promotion.to_string());
+        self
+    }
+
+    pub(super) fn params(&self) -> impl rusqlite::Params + '_ {
+        (
+            self.event_id,
+            self.user.as_deref(),
+            self.from.as_deref(),
+            self.to.as_deref(),
+            self.shared_with.as_deref(),
+            self.promotion.as_deref(),
+        )
+    }
+}
+
+// A prediction matches a result for the same bout in either orientation; which orientation decides the outcome.
+pub(super) const SCORE_FROM: &str = "
+    FROM predictions AS p
+    LEFT JOIN events AS e ON e.id = p.event_id
+    LEFT JOIN results AS r ON r.event_id = p.event_id
+        AND ((r.winner = p.winner AND r.loser = p.loser) OR (r.winner = p.loser AND r.loser = p.winner))
+    WHERE (?1 IS NULL OR p.event_id = ?1)
+        AND (?2 IS NULL OR p.user = ?2)
+        AND (?3 IS NULL OR e.date >= ?3)
+        AND (?4 IS NULL OR e.date <= ?4)
+        AND (?5 IS NULL OR EXISTS (
+            SELECT 1 FROM predictions AS s WHERE s.event_id = p.event_id AND s.user = ?5
+                AND ((s.winner = p.winner AND s.loser = p.loser) OR (s.winner = p.loser AND s.loser = p.winner))
+        ))
+        AND (?6 IS NULL OR e.promotion = ?6)";
+
+pub(super) const SCORE_COLUMNS: &str = "
+    COALESCE(SUM(CASE WHEN r.winner = p.winner THEN 1 ELSE 0 END), 0),
+    COALESCE(SUM(CASE WHEN r.winner = p.loser THEN 1 ELSE 0 END), 0),
+    COALESCE(SUM(CASE WHEN r.winner IS NULL THEN 1 ELSE 0 END), 0)";
+
+pub(super) fn score_from_row(row: &Row<'_>, offset: usize) -> Result<Score> {
+    Ok(Score {
+        correct: row.get(offset)?,
+        incorrect: row.get(offset + 1)?,
+        pending: row.get(offset + 2)?,
+    })
+}
+
+impl Store {
+    pub fn score(&self, filter: &ScoreFilter) -> Result<Score> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {SCORE_COLUMNS} {SCORE_FROM}"),
+            filter.params(),
+            |row| score_from_row(row, 0),
+        )
+    }
+
+    /// Scores grouped per event, for every event that has at least one matching prediction.
+    pub fn scores_by_event(&self, filter: &ScoreFilter) -> Result<Vec<(usize, Score)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT p.event_id, {SCORE_COLUMNS} {SCORE_FROM} GROUP BY p.event_id ORDER BY p.event_id"
+        ))?;
+        let rows = stmt.query_map(filter.params(), |row| {
+            Ok((row.get::<_, usize>(0)?, score_from_row(row, 1)?))
+        })?;
+        rows.collect()
+    }
+
+    /// Scores grouped per user, for every user with at least one matching prediction.
+    pub fn scores_by_user(&self, filter: &ScoreFilter) -> Result<Vec<(String, Score)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT p.user, {SCORE_COLUMNS} {SCORE_FROM} GROUP BY p.user ORDER BY p.user"
+        ))?;
+        let rows = stmt.query_map(filter.params(), |row| {
+            Ok((row.get::<_, String>(0)?, score_from_row(row, 1)?))
+        })?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::DEFAULT_USER;
+
+    fn store() -> Store {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        store
+    }
+
+    fn event(store: &Store, name: &str, date: &str) -> usize {
+        store.add_event(name, date, "http://example.com").unwrap()
+    }
+
+    #[test]
+    fn correct_and_incorrect_picks() {
+        let store = store();
+        let id = event(&store, "UFC 1", "2025-01-01");
+        store.add_or_update_prediction(id, "A", "B").unwrap();
+        store.add_or_update_prediction(id, "C", "D").unwrap();
+        store.add_or_update_result(id, "A", "B").unwrap();
+        store.add_or_update_result(id, "D", "C").unwrap();
+
+        let score = store.score(&ScoreFilter::all()).unwrap();
+        assert_eq!(
+            score,
+            Score {
+                correct: 1,
+                incorrect: 1,
+                pending: 0
+            }
+        );
+        assert_eq!(score.accuracy(), Some(0.5));
+    }
+
+    #[test]
+    fn missing_result_is_pending() {
+        let store = store();
+        let id = event(&store, "UFC 1", "2025-01-01");
+        store.add_or_update_prediction(id, "A", "B").unwrap();
+        store.add_or_update_prediction(id, "C", "D").unwrap();
+        store.add_or_update_result(id, "A", "B").unwrap();
+
+        let score = store.score(&ScoreFilter::all()).unwrap();
+        assert_eq!(score.correct, 1);
+        assert_eq!(score.incorrect, 0);
+        assert_eq!(score.pending, 1);
+        assert_eq!(Score::default().accuracy(), None);
+    }
+
+    #[test]
+    fn flipped_pick_replaces_original() {
+        let store = store();
+        let id = event(&store, "UFC 1", "2025-01-01");
+        store.add_or_update_prediction(id, "A", "B").unwrap();
+        store.add_or_update_prediction(id, "B", "A").unwrap();
+        store.add_or_update_result(id, "A", "B").unwrap();
+
+        assert_eq!(
+            store.get_predictions(id).unwrap(),
+            vec![("B".to_string(), "A".to_string())]
+        );
+        assert_eq!(store.get_my_predictions_correctness().unwrap(), (0, 1));
+    }
+
+    #[test]
+    fn per_event_counts_ignore_other_events() {
+        let store = store();
+        let first = event(&store, "UFC 1", "2025-01-01");
+        let second = event(&store, "UFC 2", "2025-02-01");
+        store.add_or_update_prediction(first, "A", "B").unwrap();
+        store.add_or_update_result(first, "A", "B").unwrap();
+        store.add_or_update_prediction(second, "C", "D").unwrap();
+        store.add_or_update_prediction(second, "E", "F").unwrap();
+        store.add_or_update_result(second, "D", "C").unwrap();
+        store.add_or_update_result(second, "F", "E").unwrap();
+
+        assert_eq!(
+            store
+                .get_my_predictions_correctness_for_event(first)
+                .unwrap(),
+            (1, 0)
+        );
+        assert_eq!(
+            store
+                .get_my_predictions_correctness_for_event(second)
+                .unwrap(),
+            (0, 2)
+        );
+        let by_event = store.scores_by_event(&ScoreFilter::all()).unwrap();
+        assert_eq!(by_event.len(), 2);
+        assert_eq!(by_event[0].0, first);
+        assert_eq!(by_event[1].1.incorrect, 2);
+    }
+
+    #[test]
+    fn scores_are_separated_by_user() {
+        let store = store();
+        let id = event(&store, "UFC 1", "2025-01-01");
+        store.add_or_update_prediction(id, "A", "B").unwrap();
+        store
+            .add_or_update_prediction_for_user(id, "B", "A", "friend")
+            .unwrap();
+        store.add_or_update_result(id, "A", "B").unwrap();
+
+        let mine = store.score(&ScoreFilter::for_user(DEFAULT_USER)).unwrap();
+        let theirs = store.score(&ScoreFilter::for_user("friend")).unwrap();
+        assert_eq!((mine.correct, mine.incorrect), (1, 0));
+        assert_eq!((theirs.correct, theirs.incorrect), (0, 1));
+        let by_user = store.scores_by_user(&ScoreFilter::all()).unwrap();
+        assert_eq!(
+            by_user.iter().map(|(u, _)| u.as_str()).collect::<Vec<_>>(),
+            vec!["friend", DEFAULT_USER]
+        );
+    }
+
+    #[test]
+    fn date_range_filters_events() {
+        let store = store();
+        let january = event(&store, "UFC 1", "2025-01-15");
+        let march = event(&store, "UFC 2", "2025-03-15");
+        store.add_or_update_prediction(january, "A", "B").unwrap();
+        store.add_or_update_result(january, "A", "B").unwrap();
+        store.add_or_update_prediction(march, "C", "D").unwrap();
+        store.add_or_update_result(march, "D", "C").unwrap();
+
+        let score = store
+            .score(&ScoreFilter::all().between("2025-01-01", "2025-01-31"))
+            .unwrap();
+        assert_eq!((score.correct, score.incorrect), (1, 0));
+        let score = store
+            .score(&ScoreFilter::all().between("2025-02-01", "2025-12-31"))
+            .unwrap();
+        assert_eq!((score.correct, score.incorrect), (0, 1));
+    }
+
+    #[test]
+    fn promotion_filters_events() {
+        let store = store();
+        let ufc = event(&store, "UFC 1", "2025-01-15");
+        let pfl = event(&store, "PFL 1", "2025-01-20");
+        store.set_event_promotion(pfl, "PFL").unwrap();
+        store.add_or_update_prediction(ufc, "A", "B").unwrap();
+        store.add_or_update_result(ufc, "A", "B").unwrap();
+        store.add_or_update_prediction(pfl, "C", "D").unwrap();
+        store.add_or_update_result(pfl, "D", "C").unwrap();
+
+        let score = store.score(&ScoreFilter::all().promotion("PFL")).unwrap();
+        assert_eq!((score.correct, score.incorrect), (0, 1));
+        let score = store.score(&ScoreFilter::all().promotion("UFC")).unwrap();
+        assert_eq!((score.correct, score.incorrect), (1, 0));
+        assert_eq!(store.get_promotions().unwrap(), vec!["PFL", "UFC"]);
+    }
+
+    #[test]
+    fn head_to_head_only_counts_shared_bouts() {
+        let store = store();
+        let id = event(&store, "UFC 1", "2025-01-01");
+        store.add_or_update_prediction(id, "A", "B").unwrap();
+        for (winner, loser) in [("B", "A"), ("C", "D")] {
+            store
+                .add_or_update_prediction_for_user(id, winner, loser, "model")
+                .unwrap();
+        }
+        store.add_or_update_result(id, "A", "B").unwrap();
+        store.add_or_update_result(id, "C", "D").unwrap();
+
+        let model = store.score(&ScoreFilter::for_user("model")).unwrap();
+        assert_eq!((model.correct, model.incorrect), (1, 1));
+        let model = store
+            .score(&ScoreFilter::for_user("model").shared_with(DEFAULT_USER))
+            .unwrap();
+        assert_eq!((model.correct, model.incorrect), (0, 1));
+    }
+}
@@ -0,0 +1,227 @@
+use super::scoring::{score_from_row, SCORE_COLUMNS, SCORE_FROM};
+use super::{Event, Score, ScoreFilter, Store};
+use rusqlite::Result;
+use serde::{Deserialize, Serialize};
+
+/// Everything the stats page shows for a set of picks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub overall: Score,
+    /// Events with at least one pick.
+    pub events: usize,
+    /// By month (YYYY-MM), oldest first.
+    pub by_month: Vec<(String, Score)>,
+    pub by_weight_class: Vec<(String, Score)>,
+    /// By how the bout ended: KO/TKO, Submission, Decision or Other. Only decided picks.
+    pub by_method: Vec<(String, Score)>,
+    /// Most picked to win first.
+    pub most_picked: Vec<(String, Score)>,
+    /// Best accuracy first.
+    pub best_events: Vec<(Event, Score)>,
+    /// Worst accuracy first.
+    pub worst_events: Vec<(Event, Score)>,
+}
+
+/// How many fighters and events the stats page lists.
+pub const STATS_LIST_LEN: usize = 5;
+
+// the bout a pick was made on, in either orientation
+fn bout_column(column: &str) -> String {
+    format!(
+        "(SELECT b.{column} FROM bouts AS b WHERE b.event_id = p.event_id
+            AND ((b.fighter1 = p.winner AND b.fighter2 = p.loser) OR (b.fighter1 = p.loser AND b.fighter2 = p.winner)))"
+    )
+}
+
+/// The kind of finish a method as written by ufcstats ("U-DEC", "SUB", "KO/TKO") or by hand
+/// ("Decision", "Submission") falls under.
+pub fn method_group(method: &str) -> &'static str {
+    let method = method.trim().to_uppercase();
+    if method.contains("DEC") {
+        "Decision"
+    } else if method.starts_with("SUB") {
+        "Submission"
+    } else if method.contains("KO") {
+        "KO/TKO"
+    } else {
+        "Other"
+    }
+}
+
+impl Store {
+    /// Scores grouped by `key`, a SQL expression over the pick; picks where it's NULL are left out.
+    fn scores_grouped_by(
+        &self,
+        filter: &ScoreFilter,
+        key: &str,
+        order: &str,
+    ) -> Result<Vec<(String, Score)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {key} AS k, {SCORE_COLUMNS} {SCORE_FROM} AND k IS NOT NULL GROUP BY k ORDER BY {order}"
+        ))?;
+        let rows = stmt.query_map(filter.params(), |row| {
+            Ok((row.get::<_, String>(0)?, score_from_row(row, 1)?))
+        })?;
+        rows.collect()
+    }
+
+    /// Scores grouped by the month of the event, oldest first.
+    pub fn scores_by_month(&self, filter: &ScoreFilter) -> Result<Vec<(String, Score)>> {
+        self.scores_grouped_by(filter, "substr(e.date, 1, 7)", "k")
+    }
+
+    /// Scores grouped by the bout's weight class, for bouts that have one.
+    pub fn scores_by_weight_class(&self, filter: &ScoreFilter) -> Result<Vec<(String, Score)>> {
+        self.scores_grouped_by(filter, &bout_column("weight_class"), "k")
+    }
+
+    /// Scores of decided picks grouped by [`method_group`], most picks first.
+    pub fn scores_by_method(&self, filter: &ScoreFilter) -> Result<Vec<(String, Score)>> {
+        let mut groups = Vec::<(String, Score)>::new();
+        for (method, score) in self.scores_grouped_by(filter, &bout_column("method"), "k")? {
+            let group = method_group(&method);
+            let i = match groups.iter().position(|(g, _)| g == group) {
+                Some(i) => i,
+                None => {
+                    groups.push((group.to_string(), Score::default()));
+                    groups.len() - 1
+                }
+            };
+            groups[i].1.correct += score.correct;
+            groups[i].1.incorrect += score.incorrect;
+        }
+        groups.retain(|(_, score)| score.decided() > 0);
+        groups.sort_by_key(|(_, score)| std::cmp::Reverse(score.decided()));
+        Ok(groups)
+    }
+
+    /// The fighters picked to win most often, with how those picks went.
+    pub fn most_picked_fighters(
+        &self,
+        filter: &ScoreFilter,
+        limit: usize,
+    ) -> Result<Vec<(String, Score)>> {
+        let mut picked = self.scores_grouped_by(filter, "p.winner", "COUNT(*) DESC, k")?;
+        picked.truncate(limit);
+        Ok(picked)
+    }
+
+    /// Stats for the picks `filter` matches.
+    pub fn stats(&self, filter: &ScoreFilter) -> Result<Stats> {
+        let mut events = self
+            .scores_by_event(filter)?
+            .into_iter()
+            .map(|(id, score)| Ok((self.get_event(id)?, score)))
+            .collect::<Result<Vec<_>>>()?;
+        let events_picked = events.len();
+        events.retain(|(_, score)| score.decided() > 0);
+        // more decided picks breaks a tie in accuracy
+        events.sort_by(|(_, a), (_, b)| {
+            b.accuracy()
+                .partial_cmp(&a.accuracy())
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.decided().cmp(&a.decided()))
+        });
+        let best_events = events.iter().take(STATS_LIST_LEN).cloned().collect();
+        // from the bottom of the list: past twice the list length the two can't overlap, and below
+        // it an event can be both, rather than leaving the worst list short or empty
+        let worst_events = events.iter().rev().take(STATS_LIST_LEN).cloned().collect();
+        Ok(Stats {
+            overall: self.score(filter)?,
+            events: events_picked,
+            by_month: self.scores_by_month(filter)?,
+            by_weight_class: self.scores_by_weight_class(filter)?,
+            by_method: self.scores_by_method(filter)?,
+            most_picked: self.most_picked_fighters(filter, STATS_LIST_LEN)?,
+            best_events,
+            worst_events,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Bout, BoutOutcome, DEFAULT_USER};
+
+    #[test]
+    fn groups_scores_for_the_stats_page() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let january = store.add_event("UFC 1", "2025-01-15", "manual").unwrap();
+        let march = store.add_event("UFC 2", "2025-03-15", "manual").unwrap();
+        for (event_id, f1, f2, weight_class, winner, method) in [
+            (january, "A", "B", "Lightweight", "A", "KO/TKO"),
+            (january, "C", "D", "Lightweight", "D", "U-DEC"),
+            (march, "A", "E", "Welterweight", "A", "SUB"),
+            (march, "F", "G", "Lightweight", "F", "S-DEC"),
+        ] {
+            store
+                .record_bout(&Bout {
+                    event_id,
+                    fighter1: f1.to_string(),
+                    fighter2: f2.to_string(),
+                    weight_class: Some(weight_class.to_string()),
+                    outcome: Some(BoutOutcome::Win),
+                    winner: Some(winner.to_string()),
+                    method: Some(method.to_string()),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        store.add_bout(march, "H", "I", None).unwrap();
+        for (event_id, winner, loser) in [
+            (january, "A", "B"),
+            (january, "C", "D"),
+            (march, "A", "E"),
+            (march, "F", "G"),
+            (march, "H", "I"),
+        ] {
+            store
+                .add_or_update_prediction(event_id, winner, loser)
+                .unwrap();
+        }
+
+        let stats = store.stats(&ScoreFilter::for_user(DEFAULT_USER)).unwrap();
+        assert_eq!(stats.events, 2);
+        assert_eq!((stats.overall.correct, stats.overall.incorrect), (3, 1));
+        assert_eq!(
+            stats
+                .by_month
+                .iter()
+                .map(|(month, score)| (month.as_str(), score.correct))
+                .collect::<Vec<_>>(),
+            vec![("2025-01", 1), ("2025-03", 2)]
+        );
+        assert_eq!(stats.by_weight_class[0].0, "Lightweight");
+        assert_eq!(stats.by_weight_class[0].1.decided(), 3);
+        assert_eq!(stats.by_method[0].0, "Decision");
+        assert_eq!(
+            (stats.by_method[0].1.correct, stats.by_method[0].1.incorrect),
+            (1, 1)
+        );
+        assert_eq!(stats.most_picked[0].0, "A");
+        assert_eq!(stats.most_picked[0].1.correct, 2);
+        assert_eq!(stats.best_events[0].0.id, march);
+        assert_eq!(stats.best_events[1].0.id, january);
+        // with fewer events than both lists hold, every event is on each
+        assert_eq!(
+            stats
+                .worst_events
+                .iter()
+                .map(|(event, _)| event.id)
+                .collect::<Vec<_>>(),
+            vec![january, march]
+        );
+    }
+
+    #[test]
+    fn groups_methods_by_finish() {
+        assert_eq!(method_group("U-DEC"), "Decision");
+        assert_eq!(method_group("Decision - Split"), "Decision");
+        assert_eq!(method_group("SUB"), "Submission");
+        assert_eq!(method_group("KO/TKO"), "KO/TKO");
+        assert_eq!(method_group("DQ"), "Other");
+    }
+}
@@ -0,0 +1,425 @@
+use super::{db::save_prediction, BoutOutcome, Store};
+use rusqlite::{OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+
+/// Where a ticket or one of its legs stands. A ticket is `Pending` while it's still open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Settlement {
+    #[default]
+    Pending,
+    Won,
+    Lost,
+    /// The bout didn't produce a winner (draw, no contest or cancelled), so the leg drops out of the
+    /// ticket. A ticket whose every leg is void returns its stake.
+    Void,
+}
+
+impl Settlement {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Settlement::Pending => "pending",
+            Settlement::Won => "won",
+            Settlement::Lost => "lost",
+            Settlement::Void => "void",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "pending" => Some(Settlement::Pending),
+            "won" => Some(Settlement::Won),
+            "lost" => Some(Settlement::Lost),
+            "void" => Some(Settlement::Void),
+            _ => None,
+        }
+    }
+}
+
+/// One pick on a ticket, at decimal odds (2.0 pays back double the stake).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TicketLeg {
+    pub event_id: usize,
+    pub winner: String,
+    pub loser: String,
+    pub odds: f64,
+    pub status: Settlement,
+}
+
+/// Several picks staked together as a parlay: every leg has to win, and the odds multiply.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ticket {
+    pub id: usize,
+    pub user: String,
+    pub stake: f64,
+    pub status: Settlement,
+    /// What the ticket returned, stake included, once it's settled. Zero for a lost ticket.
+    pub payout: Option<f64>,
+    pub created_at: String,
+    pub legs: Vec<TicketLeg>,
+}
+
+/// Product of the odds of every leg that isn't void.
+pub fn combined_odds(legs: &[TicketLeg]) -> f64 {
+    legs.iter()
+        .filter(|leg| leg.status != Settlement::Void)
+        .map(|leg| leg.odds)
+        .product()
+}
+
+/// Why the legs can't be staked together, if they can't.
+pub fn check_ticket(stake: f64, legs: &[TicketLeg]) -> Option<String> {
+    if legs.is_empty() {
+        return Some("A ticket needs at least one pick".to_string());
+    }
+    if !stake.is_finite() || stake <= 0.0 {
+        return Some("Stake must be more than zero".to_string());
+    }
+    if let Some(leg) = legs
+        .iter()
+        .find(|leg| !leg.odds.is_finite() || leg.odds <= 1.0)
+    {
+        return Some(format!("Odds for {} must be above 1.0", leg.winner));
+    }
+    for (i, leg) in legs.iter().enumerate() {
+        if leg.winner == leg.loser {
+            return Some(format!("{} can't fight themselves", leg.winner));
+        }
+        let same_bout = legs[..i].iter().any(|other| {
+            other.event_id == leg.event_id
+                && [&other.winner, &other.loser].contains(&&leg.winner)
+                && [&other.winner, &other.loser].contains(&&leg.loser)
+        });
+        if same_bout {
+            return Some(format!(
+                "{} vs {} is on the ticket twice",
+                leg.winner, leg.loser
+            ));
+        }
+    }
+    None
+}
+
+impl Ticket {
+    pub fn combined_odds(&self) -> f64 {
+        combined_odds(&self.legs)
+    }
+
+    /// The ticket's status and payout given where its legs stand: lost as soon as any leg loses,
+    /// otherwise open until every leg is settled. Void legs are dropped from the odds.
+    pub fn settlement(&self) -> (Settlement, Option<f64>) {
+        let statuses = self.legs.iter().map(|leg| leg.status);
+        if statuses.clone().any(|s| s == Settlement::Lost) {
+            (Settlement::Lost, Some(0.0))
+        } else if statuses.clone().any(|s| s == Settlement::Pending) {
+            (Settlement::Pending, None)
+        } else if statuses.clone().all(|s| s == Settlement::Void) {
+            (Settlement::Void, Some(self.stake))
+        } else {
+            (Settlement::Won, Some(self.stake * self.combined_odds()))
+        }
+    }
+}
+
+impl Store {
+    /// Stores a ticket for the user, recording each leg as one of their predictions too. Either the
+    /// ticket and every prediction are saved or none are.
+    pub fn create_ticket(&self, user: &str, stake: f64, legs: &[TicketLeg]) -> Result<usize> {
+        let legs = legs
+            .iter()
+            .map(|leg| {
+                Ok(TicketLeg {
+                    winner: self.resolve_fighter_name(Some(leg.event_id), &leg.winner)?,
+                    loser: self.resolve_fighter_name(Some(leg.event_id), &leg.loser)?,
+                    ..leg.clone()
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for leg in &legs {
+            save_prediction(&tx, leg.event_id, &leg.winner, &leg.loser, user)?;
+        }
+        tx.execute(
+            "INSERT INTO tickets (user, stake) VALUES (?1, ?2)",
+            (user, stake),
+        )?;
+        let id = tx.last_insert_rowid() as usize;
+        for leg in &legs {
+            tx.execute(
+                "INSERT INTO ticket_legs (ticket_id, event_id, winner, loser, odds) VALUES (?1, ?2, ?3, ?4, ?5)",
+                (id, leg.event_id, &leg.winner, &leg.loser, leg.odds),
+            )?;
+        }
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// The user's tickets with their legs, newest first.
+    pub fn get_tickets(&self, user: &str) -> Result<Vec<Ticket>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, user, stake, status, payout, created_at FROM tickets
+            WHERE user=?1 ORDER BY created_at DESC, id DESC",
+        )?;
+        let mut tickets = stmt
+            .query_map((user,), |row| {
+                Ok(Ticket {
+                    id: row.get(0)?,
+                    user: row.get(1)?,
+                    stake: row.get(2)?,
+                    status: Settlement::parse(&row.get::<_, String>(3)?).unwrap_or_default(),
+                    payout: row.get(4)?,
+                    created_at: row.get(5)?,
+                    legs: Vec::new(),
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        let mut legs = conn.prepare(
+            "SELECT event_id, winner, loser, odds, status FROM ticket_legs WHERE ticket_id=?1 ORDER BY rowid",
+        )?;
+        for ticket in &mut tickets {
+            ticket.legs = legs
+                .query_map((ticket.id,), |row| {
+                    Ok(TicketLeg {
+                        event_id: row.get(0)?,
+                        winner: row.get(1)?,
+                        loser: row.get(2)?,
+                        odds: row.get(3)?,
+                        status: Settlement::parse(&row.get::<_, String>(4)?).unwrap_or_default(),
+                    })
+                })?
+                .collect::<Result<_>>()?;
+        }
+        Ok(tickets)
+    }
+
+    /// Settles the event's pending legs against its recorded bouts and results, then every open
+    /// ticket they're on. Should be called once a card's results are in, since a bout still without
+    /// an outcome by then is taken to have been cancelled. Returns how many tickets were settled.
+    pub fn settle_tickets(&self, event_id: usize) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let card_has_results: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM bouts WHERE event_id=?1 AND outcome IS NOT NULL)
+                OR EXISTS (SELECT 1 FROM results WHERE event_id=?1)",
+            (event_id,),
+            |row| row.get(0),
+        )?;
+        let mut pending = conn.prepare(
+            "SELECT rowid, winner, loser FROM ticket_legs WHERE event_id=?1 AND status='pending'",
+        )?;
+        let legs = pending
+            .query_map((event_id,), |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        for (rowid, winner, loser) in legs {
+            let bout: Option<(Option<String>, Option<String>)> = conn
+                .query_row(
+                    "SELECT outcome, winner FROM bouts WHERE event_id=?1
+                    AND ((fighter1=?2 AND fighter2=?3) OR (fighter1=?3 AND fighter2=?2))",
+                    (event_id, &winner, &loser),
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+            let result: Option<String> = conn
+                .query_row(
+                    "SELECT winner FROM results WHERE event_id=?1
+                    AND ((winner=?2 AND loser=?3) OR (winner=?3 AND loser=?2))",
+                    (event_id, &winner, &loser),
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let status = match result.or_else(|| bout.as_ref().and_then(|(_, w)| w.clone())) {
+                Some(result) if result == winner => Settlement::Won,
+                Some(_) => Settlement::Lost,
+                None => {
+                    match bout.map(|(outcome, _)| outcome.as_deref().and_then(BoutOutcome::parse)) {
+                        Some(Some(BoutOutcome::Draw | BoutOutcome::NoContest)) => Settlement::Void,
+                        Some(None) if card_has_results => Settlement::Void,
+                        _ => continue,
+                    }
+                }
+            };
+            conn.execute(
+                "UPDATE ticket_legs SET status=?1 WHERE rowid=?2",
+                (status.as_str(), rowid),
+            )?;
+        }
+        drop(pending);
+        drop(conn);
+
+        let mut settled = 0;
+        for ticket in self.get_open_tickets_for_event(event_id)? {
+            let (status, payout) = ticket.settlement();
+            if status == Settlement::Pending {
+                continue;
+            }
+            let conn = self.conn.lock().unwrap();
+            conn.execute(
+                "UPDATE tickets SET status=?1, payout=?2, settled_at=datetime('now') WHERE id=?3",
+                (status.as_str(), payout, ticket.id),
+            )?;
+            settled += 1;
+        }
+        Ok(settled)
+    }
+
+    fn get_open_tickets_for_event(&self, event_id: usize) -> Result<Vec<Ticket>> {
+        let users: Vec<String> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT t.user FROM tickets AS t JOIN ticket_legs AS l ON l.ticket_id = t.id
+                WHERE l.event_id=?1 AND t.status='pending'",
+            )?;
+            let users = stmt.query_map((event_id,), |row| row.get(0))?;
+            users.collect::<Result<_>>()?
+        };
+        let mut tickets = Vec::new();
+        for user in users {
+            tickets.extend(self.get_tickets(&user)?.into_iter().filter(|ticket| {
+                ticket.status == Settlement::Pending
+                    && ticket.legs.iter().any(|leg| leg.event_id == event_id)
+            }));
+        }
+        Ok(tickets)
+    }
+}
+
+/// Puts every ticket with a leg on the bout between the two fighters, and those legs, back to
+/// pending, for when the result they were settled on changes.
+pub(super) fn reopen_tickets(
+    conn: &rusqlite::Connection,
+    event_id: usize,
+    fighter1: &str,
+    fighter2: &str,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE tickets SET status='pending', payout=NULL, settled_at=NULL WHERE id IN (
+            SELECT ticket_id FROM ticket_legs WHERE event_id=?1
+            AND ((winner=?2 AND loser=?3) OR (winner=?3 AND loser=?2)))",
+        (event_id, fighter1, fighter2),
+    )?;
+    conn.execute(
+        "UPDATE ticket_legs SET status='pending' WHERE event_id=?1
+        AND ((winner=?2 AND loser=?3) OR (winner=?3 AND loser=?2))",
+        (event_id, fighter1, fighter2),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Bout, DEFAULT_USER};
+
+    fn leg(event_id: usize, winner: &str, loser: &str, odds: f64) -> TicketLeg {
+        TicketLeg {
+            event_id,
+            winner: winner.to_string(),
+            loser: loser.to_string(),
+            odds,
+            ..Default::default()
+        }
+    }
+
+    fn bout(event_id: usize, fighter1: &str, fighter2: &str, outcome: Option<BoutOutcome>) -> Bout {
+        Bout {
+            event_id,
+            fighter1: fighter1.to_string(),
+            fighter2: fighter2.to_string(),
+            winner: (outcome == Some(BoutOutcome::Win)).then(|| fighter1.to_string()),
+            outcome,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn checks_tickets() {
+        assert!(check_ticket(10.0, &[]).is_some());
+        assert!(check_ticket(0.0, &[leg(1, "A", "B", 2.0)]).is_some());
+        assert!(check_ticket(10.0, &[leg(1, "A", "B", 1.0)]).is_some());
+        assert!(check_ticket(10.0, &[leg(1, "A", "B", 2.0), leg(1, "B", "A", 2.0)]).is_some());
+        assert!(check_ticket(10.0, &[leg(1, "A", "A", 2.0)]).is_some());
+        assert!(check_ticket(f64::INFINITY, &[leg(1, "A", "B", 2.0)]).is_some());
+        assert!(check_ticket(10.0, &[leg(1, "A", "B", f64::INFINITY)]).is_some());
+        assert!(check_ticket(10.0, &[leg(1, "A", "B", 2.0), leg(2, "A", "B", 2.0)]).is_none());
+    }
+
+    #[test]
+    fn void_legs_reduce_the_parlay() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let event = store.add_event("UFC 1", "2025-01-01", "").unwrap();
+        let later = store.add_event("UFC 2", "2025-02-01", "").unwrap();
+        let id = store
+            .create_ticket(
+                DEFAULT_USER,
+                10.0,
+                &[
+                    leg(event, "A", "B", 2.0),
+                    leg(event, "C", "D", 1.5),
+                    leg(event, "E", "F", 3.0),
+                    leg(later, "G", "H", 2.0),
+                ],
+            )
+            .unwrap();
+        assert_eq!(store.get_predictions(event).unwrap().len(), 3);
+        store
+            .record_bout(&bout(event, "A", "B", Some(BoutOutcome::Win)))
+            .unwrap();
+        store
+            .record_bout(&bout(event, "C", "D", Some(BoutOutcome::Draw)))
+            .unwrap();
+        // E vs F was on the card but never happened
+        store.upsert_bout(&bout(event, "E", "F", None)).unwrap();
+
+        assert_eq!(store.settle_tickets(event).unwrap(), 0);
+        let ticket = &store.get_tickets(DEFAULT_USER).unwrap()[0];
+        assert_eq!(ticket.id, id);
+        assert_eq!(ticket.status, Settlement::Pending);
+        assert_eq!(
+            ticket.legs.iter().map(|l| l.status).collect::<Vec<_>>(),
+            vec![
+                Settlement::Won,
+                Settlement::Void,
+                Settlement::Void,
+                Settlement::Pending
+            ]
+        );
+        assert_eq!(ticket.combined_odds(), 4.0);
+
+        store
+            .record_bout(&bout(later, "G", "H", Some(BoutOutcome::Win)))
+            .unwrap();
+        assert_eq!(store.settle_tickets(later).unwrap(), 1);
+        let ticket = &store.get_tickets(DEFAULT_USER).unwrap()[0];
+        assert_eq!(ticket.status, Settlement::Won);
+        assert_eq!(ticket.payout, Some(40.0));
+    }
+
+    #[test]
+    fn one_losing_leg_loses_the_ticket() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let event = store.add_event("UFC 1", "2025-01-01", "").unwrap();
+        let other = store.add_event("UFC 2", "2025-02-01", "").unwrap();
+        store
+            .create_ticket(
+                DEFAULT_USER,
+                10.0,
+                &[leg(event, "B", "A", 2.0), leg(other, "C", "D", 2.0)],
+            )
+            .unwrap();
+        store.add_or_update_result(event, "A", "B").unwrap();
+
+        assert_eq!(store.settle_tickets(event).unwrap(), 1);
+        let ticket = &store.get_tickets(DEFAULT_USER).unwrap()[0];
+        assert_eq!(ticket.status, Settlement::Lost);
+        assert_eq!(ticket.payout, Some(0.0));
+    }
+}
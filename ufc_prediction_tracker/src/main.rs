@@ -2,9 +2,15 @@
 
 use dioxus::{logger::tracing::Level, prelude::*};
 
-use views::{Home, Navbar, PastEvent, PastPredictions, Predict};
+use views::{
+    Account, Backfill, ContestPage, Contests, FighterProfile, Home, Models, Navbar, PastEvent,
+    PastPredictions, Predict, Stats,
+};
 mod api;
 mod db;
+mod model;
+mod notify;
+mod parser;
 
 /// Define a views module that contains the UI for all Layouts and Routes for our app.
 mod views;
@@ -34,8 +40,29 @@ enum Route {
         #[route("/past-predictions")]
         PastPredictions {},
         
-        #[route("/past-event/:id/:link")]
-        PastEvent{id: usize, link: String}
+        #[route("/past-event/:id")]
+        PastEvent{id: usize},
+
+        #[route("/import")]
+        Backfill {},
+
+        #[route("/fighter/:id")]
+        FighterProfile { id: String },
+
+        #[route("/models")]
+        Models {},
+
+        #[route("/contests")]
+        Contests {},
+
+        #[route("/contest/:id")]
+        ContestPage { id: usize },
+
+        #[route("/stats")]
+        Stats {},
+
+        #[route("/account")]
+        Account {},
 }
 
 // We can import assets in dioxus with the `asset!` macro. This macro takes a path to an asset relative to the crate root.
@@ -54,6 +81,7 @@ fn main() {
 
     // Pass the store to the API module (assume you add a set_store function or similar)
     api::set_store(store.clone());
+    notify::spawn_watcher(store.clone());
 
     // The `launch` function is the main entry point for a dioxus app. It takes a component and renders it with the platform feature
     // you have enabled
@@ -0,0 +1,150 @@
+use super::{ModelPick, PredictionModel};
+use crate::db::{BacktestReport, Bout, BoutOutcome, CalibrationBucket};
+
+/// Number of equal-width calibration bands between 50% and 100%.
+pub const CALIBRATION_BUCKETS: usize = 5;
+
+/// Probabilities are clamped this far from 0 and 1, so one confident miss can't make the log loss infinite.
+const PROBABILITY_EPSILON: f64 = 1e-6;
+
+/// Replays `history` through `model`, which should not have seen any results yet.
+///
+/// `history` is decided bouts with their event dates, oldest event first, as returned by
+/// [`Store::get_decided_bouts`](crate::db::Store::get_decided_bouts). Every bout on an event is
+/// predicted before any of that event's results are observed, so each prediction only uses what was
+/// known before the event. Returns the report along with the model's picks on bouts that ended in a
+/// win, as (event_id, pick).
+pub fn backtest(
+    model: &mut dyn PredictionModel,
+    history: &[(String, Bout)],
+) -> (BacktestReport, Vec<(usize, ModelPick)>) {
+    let mut picks = Vec::new();
+    // (fighter1 win probability, whether fighter1 won)
+    let mut scored = Vec::new();
+    for event in history.chunk_by(|(_, a), (_, b)| a.event_id == b.event_id) {
+        for (_, bout) in event {
+            let (Some(BoutOutcome::Win), Some(winner)) = (bout.outcome, bout.winner.as_deref())
+            else {
+                continue;
+            };
+            let probability = model.predict(bout);
+            scored.push((probability, winner == bout.fighter1));
+            picks.push((
+                bout.event_id,
+                ModelPick {
+                    fighter1: bout.fighter1.clone(),
+                    fighter2: bout.fighter2.clone(),
+                    fighter1_win_probability: probability,
+                },
+            ));
+        }
+        for (_, bout) in event {
+            model.observe(bout);
+        }
+    }
+    (report(model.user(), &scored), picks)
+}
+
+fn report(model: &str, scored: &[(f64, bool)]) -> BacktestReport {
+    let n = scored.len().max(1) as f64;
+    let accuracy = scored
+        .iter()
+        .map(|&(p, won)| match p {
+            0.5 => 0.5,
+            p if (p > 0.5) == won => 1.0,
+            _ => 0.0,
+        })
+        .sum::<f64>()
+        / n;
+    let log_loss = -scored
+        .iter()
+        .map(|&(p, won)| {
+            let p = p.clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON);
+            if won {
+                p.ln()
+            } else {
+                (1.0 - p).ln()
+            }
+        })
+        .sum::<f64>()
+        / n;
+
+    let width = 0.5 / CALIBRATION_BUCKETS as f64;
+    let mut calibration = (0..CALIBRATION_BUCKETS)
+        .map(|i| CalibrationBucket {
+            lower: 0.5 + i as f64 * width,
+            upper: 0.5 + (i + 1) as f64 * width,
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    for &(p, won) in scored {
+        let (confidence, favourite_won) = if p >= 0.5 { (p, won) } else { (1.0 - p, !won) };
+        let bucket =
+            &mut calibration[(((confidence - 0.5) / width) as usize).min(CALIBRATION_BUCKETS - 1)];
+        bucket.bouts += 1;
+        bucket.mean_predicted += confidence;
+        bucket.observed += if favourite_won { 1.0 } else { 0.0 };
+    }
+    for bucket in calibration.iter_mut().filter(|b| b.bouts > 0) {
+        bucket.mean_predicted /= bucket.bouts as f64;
+        bucket.observed /= bucket.bouts as f64;
+    }
+
+    BacktestReport {
+        model: model.to_string(),
+        bouts: scored.len(),
+        accuracy,
+        log_loss,
+        calibration,
+        run_at: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{EloRatings, ELO_USER};
+
+    fn win(event_id: usize, winner: &str, loser: &str) -> (String, Bout) {
+        (
+            String::new(),
+            Bout {
+                event_id,
+                fighter1: winner.to_string(),
+                fighter2: loser.to_string(),
+                outcome: Some(BoutOutcome::Win),
+                winner: Some(winner.to_string()),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn predicts_each_event_before_seeing_its_results() {
+        let history = [win(1, "A", "B"), win(1, "A", "C"), win(2, "A", "D")];
+        let (report, picks) = backtest(&mut EloRatings::default(), &history);
+
+        assert_eq!(report.model, ELO_USER);
+        assert_eq!(report.bouts, 3);
+        // A's first win on event 1 doesn't inform the pick of A's second bout that night
+        assert_eq!(picks[1].1.fighter1_win_probability, 0.5);
+        assert!(picks[2].1.fighter1_win_probability > 0.5);
+        assert_eq!(report.accuracy, (0.5 + 0.5 + 1.0) / 3.0);
+        assert!(report.log_loss < 2f64.ln());
+
+        let calibrated = report.calibration.iter().map(|b| b.bouts).sum::<usize>();
+        assert_eq!(calibrated, 3);
+        assert_eq!(report.calibration[0].bouts, 3);
+        assert_eq!(report.calibration[0].observed, 1.0);
+    }
+
+    #[test]
+    fn draws_are_learned_from_but_not_scored() {
+        let mut draw = win(1, "A", "B");
+        draw.1.outcome = Some(BoutOutcome::Draw);
+        draw.1.winner = None;
+        let (report, picks) = backtest(&mut EloRatings::default(), &[draw]);
+        assert_eq!(report.bouts, 0);
+        assert!(picks.is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+use super::PredictionModel;
+use crate::db::{Bout, BoutOutcome};
+use std::collections::HashMap;
+
+/// The pseudo-user the Elo model's picks are recorded under.
+pub const ELO_USER: &str = "model:elo";
+
+pub const INITIAL_RATING: f64 = 1500.0;
+pub const K_FACTOR: f64 = 32.0;
+
+/// Elo ratings for every fighter seen in a run of results. Fighters with no recorded bouts sit at
+/// [`INITIAL_RATING`].
+#[derive(Debug, Clone)]
+pub struct EloRatings {
+    ratings: HashMap<String, f64>,
+    k: f64,
+}
+
+impl Default for EloRatings {
+    fn default() -> Self {
+        EloRatings::new(K_FACTOR)
+    }
+}
+
+impl EloRatings {
+    pub fn new(k: f64) -> Self {
+        EloRatings {
+            ratings: HashMap::new(),
+            k,
+        }
+    }
+
+    /// Ratings after replaying `bouts` in order. Bouts should be oldest first.
+    pub fn from_bouts<'a>(bouts: impl IntoIterator<Item = &'a Bout>) -> Self {
+        let mut ratings = EloRatings::default();
+        for bout in bouts {
+            ratings.update(bout);
+        }
+        ratings
+    }
+
+    pub fn rating(&self, fighter: &str) -> f64 {
+        self.ratings.get(fighter).copied().unwrap_or(INITIAL_RATING)
+    }
+
+    /// Probability that `fighter` beats `opponent`.
+    pub fn win_probability(&self, fighter: &str, opponent: &str) -> f64 {
+        let diff = self.rating(opponent) - self.rating(fighter);
+        1.0 / (1.0 + 10f64.powf(diff / 400.0))
+    }
+
+    /// Applies one result. Draws count as half a win each; no contests and undecided bouts are ignored.
+    pub fn update(&mut self, bout: &Bout) {
+        let score = match (bout.outcome, bout.winner.as_deref()) {
+            (Some(BoutOutcome::Win), Some(winner)) if winner == bout.fighter1 => 1.0,
+            (Some(BoutOutcome::Win), Some(_)) => 0.0,
+            (Some(BoutOutcome::Draw), _) => 0.5,
+            _ => return,
+        };
+        let expected = self.win_probability(&bout.fighter1, &bout.fighter2);
+        let change = self.k * (score - expected);
+        *self
+            .ratings
+            .entry(bout.fighter1.clone())
+            .or_insert(INITIAL_RATING) += change;
+        *self
+            .ratings
+            .entry(bout.fighter2.clone())
+            .or_insert(INITIAL_RATING) -= change;
+    }
+}
+
+impl PredictionModel for EloRatings {
+    fn user(&self) -> &'static str {
+        ELO_USER
+    }
+
+    fn observe(&mut self, bout: &Bout) {
+        self.update(bout);
+    }
+
+    fn predict(&self, bout: &Bout) -> f64 {
+        self.win_probability(&bout.fighter1, &bout.fighter2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn win(winner: &str, loser: &str) -> Bout {
+        Bout {
+            fighter1: winner.to_string(),
+            fighter2: loser.to_string(),
+            outcome: Some(BoutOutcome::Win),
+            winner: Some(winner.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn winners_gain_what_losers_lose() {
+        let ratings = EloRatings::from_bouts(&[win("A", "B")]);
+        assert_eq!(ratings.rating("A"), INITIAL_RATING + K_FACTOR / 2.0);
+        assert_eq!(ratings.rating("B"), INITIAL_RATING - K_FACTOR / 2.0);
+        assert!(ratings.win_probability("A", "B") > 0.5);
+        assert!(
+            (ratings.win_probability("A", "B") + ratings.win_probability("B", "A") - 1.0).abs()
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn unknown_fighters_are_even() {
+        let ratings = EloRatings::default();
+        assert_eq!(ratings.win_probability("X", "Y"), 0.5);
+    }
+
+    #[test]
+    fn draws_and_no_contests() {
+        let mut draw = win("A", "B");
+        draw.outcome = Some(BoutOutcome::Draw);
+        draw.winner = None;
+        let mut no_contest = draw.clone();
+        no_contest.outcome = Some(BoutOutcome::NoContest);
+        let ratings = EloRatings::from_bouts(&[draw, no_contest]);
+        assert_eq!(ratings.rating("A"), INITIAL_RATING);
+
+        // a draw against a stronger fighter still gains rating
+        let ratings = EloRatings::from_bouts(&[win("A", "C"), {
+            let mut b = win("A", "B");
+            b.outcome = Some(BoutOutcome::Draw);
+            b.winner = None;
+            b
+        }]);
+        assert!(ratings.rating("B") > INITIAL_RATING);
+    }
+}
@@ -0,0 +1,207 @@
+//! Statistical models that pick bouts, to compare our own picks against.
+
+mod backtest;
+pub use backtest::*;
+mod elo;
+pub use elo::*;
+mod record;
+pub use record::*;
+
+use crate::db::{self, dates::EventTiming, Bout};
+use serde::{Deserialize, Serialize};
+
+/// A way of picking bouts from past results.
+///
+/// Results are fed in with [`observe`](PredictionModel::observe), oldest first, and the model is
+/// only asked to predict bouts from events after every result it has seen.
+pub trait PredictionModel {
+    /// The pseudo-user the model's picks are recorded under.
+    fn user(&self) -> &'static str;
+
+    /// Learns from one decided bout.
+    fn observe(&mut self, bout: &Bout);
+
+    /// Probability that `bout.fighter1` beats `bout.fighter2`.
+    fn predict(&self, bout: &Bout) -> f64;
+}
+
+/// A fresh instance of every model, the one shown alongside our picks first.
+pub fn models() -> Vec<Box<dyn PredictionModel>> {
+    vec![
+        Box::new(EloRatings::default()),
+        Box::new(RecordModel::default()),
+    ]
+}
+
+/// A model's view of one bout.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelPick {
+    pub fighter1: String,
+    pub fighter2: String,
+    pub fighter1_win_probability: f64,
+}
+
+impl ModelPick {
+    /// (winner, loser) the model favours, or `None` for a coin flip.
+    pub fn favourite(&self) -> Option<(&str, &str)> {
+        match self.fighter1_win_probability {
+            p if p > 0.5 => Some((&self.fighter1, &self.fighter2)),
+            p if p < 0.5 => Some((&self.fighter2, &self.fighter1)),
+            _ => None,
+        }
+    }
+
+    /// The favourite's win probability.
+    pub fn confidence(&self) -> f64 {
+        self.fighter1_win_probability
+            .max(1.0 - self.fighter1_win_probability)
+    }
+}
+
+/// The model's picks for the event's recorded card, using only results from before the event.
+/// Nothing is stored; [`run_backtests`] records the picks under the model's pseudo-user.
+pub fn picks(
+    store: &db::Store,
+    event_id: usize,
+    model: &mut dyn PredictionModel,
+) -> rusqlite::Result<Vec<ModelPick>> {
+    let event_date = store.get_event_date(event_id)?;
+    for (_, bout) in
+        store.get_decided_bouts(Some(&event_date.format(db::dates::ISO_DATE).to_string()))?
+    {
+        model.observe(&bout);
+    }
+    let picks = store
+        .get_bouts(event_id)?
+        .into_iter()
+        .map(|bout| ModelPick {
+            fighter1_win_probability: model.predict(&bout),
+            fighter1: bout.fighter1,
+            fighter2: bout.fighter2,
+        })
+        .collect::<Vec<_>>();
+    Ok(picks)
+}
+
+/// Backtests every model over all recorded results, storing each report and recording the picks
+/// made along the way under the model's pseudo-user, so they can be scored against ours. Each
+/// model's picks for upcoming events are recorded too, to be scored once results come in.
+pub fn run_backtests(store: &db::Store) -> rusqlite::Result<Vec<db::BacktestReport>> {
+    let history = store.get_decided_bouts(None)?;
+    let today = db::dates::today();
+    let upcoming = store
+        .get_events(Some(&today.format(db::dates::ISO_DATE).to_string()), None)?
+        .into_iter()
+        .filter(|event| {
+            db::dates::parse_event_date(&event.date)
+                .is_ok_and(|date| EventTiming::of(date, today) == EventTiming::Upcoming)
+        })
+        .collect::<Vec<_>>();
+    let mut reports = Vec::new();
+    for mut model in models() {
+        let (report, picks) = backtest(model.as_mut(), &history);
+        for (event_id, pick) in &picks {
+            if let Some((winner, loser)) = pick.favourite() {
+                store.add_or_update_prediction_for_user(*event_id, winner, loser, model.user())?;
+            }
+        }
+        store.save_backtest(&report)?;
+        reports.push(report);
+    }
+    for event in &upcoming {
+        for mut model in models() {
+            for pick in self::picks(store, event.id, model.as_mut())? {
+                if let Some((winner, loser)) = pick.favourite() {
+                    store.add_or_update_prediction_for_user(
+                        event.id,
+                        winner,
+                        loser,
+                        model.user(),
+                    )?;
+                }
+            }
+        }
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Bout, BoutOutcome, Store};
+
+    fn bout(event_id: usize, fighter1: &str, fighter2: &str, winner: Option<&str>) -> Bout {
+        Bout {
+            event_id,
+            fighter1: fighter1.to_string(),
+            fighter2: fighter2.to_string(),
+            outcome: winner.map(|_| BoutOutcome::Win),
+            winner: winner.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn picks_upcoming_events_from_prior_results_only() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let past = store.add_event("UFC 1", "2024-01-13", "").unwrap();
+        store.record_bout(&bout(past, "A", "B", Some("A"))).unwrap();
+        let upcoming = store.add_event("UFC 99", "2999-01-01", "").unwrap();
+        store.upsert_bout(&bout(upcoming, "B", "A", None)).unwrap();
+        store.upsert_bout(&bout(upcoming, "C", "D", None)).unwrap();
+
+        let picks = super::picks(&store, upcoming, &mut EloRatings::default()).unwrap();
+        assert!(picks[0].fighter1_win_probability < 0.5);
+        assert_eq!(picks[0].favourite(), Some(("A", "B")));
+        assert_eq!(picks[1].favourite(), None);
+        // looking at picks doesn't record them
+        assert!(store
+            .get_predictions_for_user(upcoming, ELO_USER)
+            .unwrap()
+            .is_empty());
+
+        // the past event's own result isn't used to pick it
+        let picks = super::picks(&store, past, &mut EloRatings::default()).unwrap();
+        assert_eq!(picks[0].fighter1_win_probability, 0.5);
+
+        // the backtest job records the upcoming picks, coin flips left out
+        run_backtests(&store).unwrap();
+        assert_eq!(
+            store.get_predictions_for_user(upcoming, ELO_USER).unwrap(),
+            vec![("A".to_string(), "B".to_string())]
+        );
+    }
+
+    #[test]
+    fn backtests_are_stored_and_picks_recorded() {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let first = store.add_event("UFC 1", "2024-01-13", "").unwrap();
+        store
+            .record_bout(&bout(first, "A", "B", Some("A")))
+            .unwrap();
+        let second = store.add_event("UFC 2", "2024-02-10", "").unwrap();
+        store
+            .record_bout(&bout(second, "B", "A", Some("B")))
+            .unwrap();
+        store.add_or_update_prediction(second, "B", "A").unwrap();
+
+        let reports = run_backtests(&store).unwrap();
+        assert_eq!(reports.len(), models().len());
+        assert_eq!(store.get_backtests().unwrap().len(), reports.len());
+        assert_eq!(
+            store.get_predictions_for_user(second, ELO_USER).unwrap(),
+            vec![("A".to_string(), "B".to_string())]
+        );
+        // the coin flip on the first event isn't recorded as a pick
+        assert!(store
+            .get_predictions_for_user(first, ELO_USER)
+            .unwrap()
+            .is_empty());
+        let model = store
+            .score(&db::ScoreFilter::for_user(ELO_USER).shared_with(db::DEFAULT_USER))
+            .unwrap();
+        assert_eq!((model.correct, model.incorrect), (0, 1));
+    }
+}
@@ -0,0 +1,73 @@
+use super::PredictionModel;
+use crate::db::{Bout, BoutOutcome};
+use std::collections::HashMap;
+
+/// The pseudo-user the win-record model's picks are recorded under.
+pub const RECORD_USER: &str = "model:record";
+
+/// Picks whoever has won more of their recorded bouts. Win rates are smoothed as if every fighter
+/// started with one win and one loss, so debutants sit at even odds.
+#[derive(Debug, Clone, Default)]
+pub struct RecordModel {
+    /// Fighter to (wins, bouts). Draws count as half a win.
+    records: HashMap<String, (f64, f64)>,
+}
+
+impl RecordModel {
+    pub fn win_rate(&self, fighter: &str) -> f64 {
+        let (wins, bouts) = self.records.get(fighter).copied().unwrap_or_default();
+        (wins + 1.0) / (bouts + 2.0)
+    }
+
+    fn add(&mut self, fighter: &str, wins: f64) {
+        let record = self.records.entry(fighter.to_string()).or_default();
+        record.0 += wins;
+        record.1 += 1.0;
+    }
+}
+
+impl PredictionModel for RecordModel {
+    fn user(&self) -> &'static str {
+        RECORD_USER
+    }
+
+    fn observe(&mut self, bout: &Bout) {
+        let fighter1_wins = match (bout.outcome, bout.winner.as_deref()) {
+            (Some(BoutOutcome::Win), Some(winner)) if winner == bout.fighter1 => 1.0,
+            (Some(BoutOutcome::Win), Some(_)) => 0.0,
+            (Some(BoutOutcome::Draw), _) => 0.5,
+            _ => return,
+        };
+        self.add(&bout.fighter1, fighter1_wins);
+        self.add(&bout.fighter2, 1.0 - fighter1_wins);
+    }
+
+    fn predict(&self, bout: &Bout) -> f64 {
+        let fighter1 = self.win_rate(&bout.fighter1);
+        let fighter2 = self.win_rate(&bout.fighter2);
+        fighter1 / (fighter1 + fighter2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn better_record_is_favoured() {
+        let mut model = RecordModel::default();
+        let win = |winner: &str, loser: &str| Bout {
+            fighter1: winner.to_string(),
+            fighter2: loser.to_string(),
+            outcome: Some(BoutOutcome::Win),
+            winner: Some(winner.to_string()),
+            ..Default::default()
+        };
+        model.observe(&win("A", "B"));
+        model.observe(&win("A", "C"));
+        assert_eq!(model.win_rate("A"), 0.75);
+        assert_eq!(model.win_rate("D"), 0.5);
+        assert!(model.predict(&win("B", "A")) < 0.5);
+        assert_eq!(model.predict(&win("B", "C")), 0.5);
+    }
+}
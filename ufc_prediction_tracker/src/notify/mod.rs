@@ -0,0 +1,312 @@
+//! Notifications: reminders before an event locks, card changes under a user's picks, and how they
+//! did once results are in.
+//!
+//! Triggers queue notifications in the store as things happen, and a background watcher sends
+//! whatever is queued through every [`Sink`] the user has set up.
+
+mod sinks;
+pub use sinks::*;
+
+use crate::db::{self, dates, Bout, Notification, NotificationKind, ScoreFilter};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+/// How long before an event locks its reminder goes out.
+pub const LOCK_WARNING_HOURS: i64 = 24;
+/// Results for events older than this are taken to be imports, not news.
+pub const RESULTS_WINDOW_DAYS: i64 = 7;
+/// How often the watcher looks for locking events and sends what's queued.
+pub const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+/// Queues a reminder for every user who wants one about each event locking within
+/// [`LOCK_WARNING_HOURS`] of `now`, as the clock in the event's timezone shows it.
+pub fn queue_lock_reminders(store: &db::Store, now: DateTime<Utc>) -> rusqlite::Result<usize> {
+    let window = Duration::hours(LOCK_WARNING_HOURS);
+    // a day either side, since event dates are local to the event
+    let (from, to) = (
+        now.date_naive() - Duration::days(1),
+        (now + window).date_naive() + Duration::days(1),
+    );
+    let events = store.get_events(
+        Some(&from.format(dates::ISO_DATE).to_string()),
+        Some(&to.format(dates::ISO_DATE).to_string()),
+    )?;
+    let prefs = store.get_notification_prefs_for(NotificationKind::EventLock)?;
+    let mut queued = 0;
+    for event in events {
+        let now = event.local_time(now);
+        let Some(lock) = event
+            .lock_time()
+            .filter(|lock| now < *lock && *lock - now <= window)
+        else {
+            continue;
+        };
+        let card = store.get_bouts(event.id)?.len();
+        for prefs in &prefs {
+            let picked = store.get_predictions_for_user(event.id, &prefs.user)?.len();
+            let notification = Notification {
+                user: prefs.user.clone(),
+                kind: NotificationKind::EventLock,
+                event_id: event.id,
+                title: format!(
+                    "{} locks in {} hours",
+                    event.name,
+                    (lock - now).num_hours().max(1)
+                ),
+                body: format!("You've picked {} of {} bouts.", picked, card),
+                ..Default::default()
+            };
+            queued += store.queue_notification(&notification)? as usize;
+        }
+    }
+    Ok(queued)
+}
+
+/// Queues a notice for every pick on the event that isn't on its freshly scraped `card` any more.
+pub fn queue_card_changes(
+    store: &db::Store,
+    event_id: usize,
+    card: &[Bout],
+) -> rusqlite::Result<usize> {
+    if card.is_empty() {
+        return Ok(0);
+    }
+    let event = store.get_event(event_id)?;
+    let mut queued = 0;
+    for prefs in store.get_notification_prefs_for(NotificationKind::CardChanged)? {
+        for (winner, loser) in store.get_predictions_for_user(event_id, &prefs.user)? {
+            if card
+                .iter()
+                .any(|bout| bout.involves(&winner) && bout.involves(&loser))
+            {
+                continue;
+            }
+            let notification = Notification {
+                user: prefs.user.clone(),
+                kind: NotificationKind::CardChanged,
+                event_id,
+                key: format!("{}|{}", winner, loser),
+                title: format!("Card change at {}", event.name),
+                body: format!(
+                    "{} vs {}, which you picked {} to win, is no longer on the card.",
+                    winner, loser, winner
+                ),
+                ..Default::default()
+            };
+            queued += store.queue_notification(&notification)? as usize;
+        }
+    }
+    Ok(queued)
+}
+
+/// Queues each picking user's score once the event's results are recorded. Events more than
+/// [`RESULTS_WINDOW_DAYS`] before `today` are skipped, so a backfill doesn't notify anyone.
+pub fn queue_results(
+    store: &db::Store,
+    event_id: usize,
+    today: NaiveDate,
+) -> rusqlite::Result<usize> {
+    let event = store.get_event(event_id)?;
+    if store.get_event_date(event_id)? < today - Duration::days(RESULTS_WINDOW_DAYS) {
+        return Ok(0);
+    }
+    let mut queued = 0;
+    for prefs in store.get_notification_prefs_for(NotificationKind::ResultsIn)? {
+        let score = store.score(&ScoreFilter::for_user(&prefs.user).event(event_id))?;
+        if score.decided() == 0 {
+            continue;
+        }
+        let notification = Notification {
+            user: prefs.user.clone(),
+            kind: NotificationKind::ResultsIn,
+            event_id,
+            title: format!("Results are in for {}", event.name),
+            body: format!("You went {}/{}.", score.correct, score.decided()),
+            ..Default::default()
+        };
+        queued += store.queue_notification(&notification)? as usize;
+    }
+    Ok(queued)
+}
+
+/// Sends every queued notification through each sink. A notification is only attempted once:
+/// failures are logged rather than retried, so a broken webhook doesn't repeat itself forever.
+pub fn dispatch(store: &db::Store, sinks: &[Box<dyn Sink>]) -> rusqlite::Result<usize> {
+    let unsent = store.get_unsent_notifications()?;
+    for notification in &unsent {
+        let prefs = store.get_notification_prefs(&notification.user)?;
+        for sink in sinks {
+            if let Err(e) = sink.send(&prefs, notification) {
+                log::warn!(
+                    "Failed to send {} notification to {}: {}",
+                    sink.name(),
+                    prefs.user,
+                    e
+                );
+            }
+        }
+        store.mark_notification_sent(notification.id)?;
+    }
+    Ok(unsent.len())
+}
+
+/// Every sink this build can send through. Email needs the SMTP settings described at
+/// [`EmailSink::from_env`].
+pub fn sinks() -> Vec<Box<dyn Sink>> {
+    let mut sinks: Vec<Box<dyn Sink>> = vec![Box::new(WebhookSink::default())];
+    if let Some(email) = EmailSink::from_env() {
+        sinks.push(Box::new(email));
+    }
+    #[cfg(feature = "desktop")]
+    sinks.push(Box::new(DesktopSink));
+    sinks
+}
+
+/// Starts a thread that queues lock reminders and sends queued notifications every
+/// [`CHECK_INTERVAL`].
+pub fn spawn_watcher(store: db::Store) {
+    std::thread::spawn(move || {
+        let sinks = sinks();
+        loop {
+            if let Err(e) =
+                queue_lock_reminders(&store, Utc::now()).and_then(|_| dispatch(&store, &sinks))
+            {
+                log::error!("Notification check failed: {}", e);
+            }
+            std::thread::sleep(CHECK_INTERVAL);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{NotificationPrefs, Store};
+    use chrono::{NaiveDateTime, NaiveTime};
+    use std::sync::{Arc, Mutex};
+
+    fn store_with_card() -> (Store, usize) {
+        let store = Store::new(":memory:");
+        store.create_tables().unwrap();
+        let event = store.add_event("UFC 1", "2025-01-02", "").unwrap();
+        for (fighter1, fighter2) in [("A", "B"), ("C", "D")] {
+            store
+                .upsert_bout(&Bout {
+                    event_id: event,
+                    fighter1: fighter1.to_string(),
+                    fighter2: fighter2.to_string(),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        store
+            .set_notification_prefs(&NotificationPrefs::new("me"))
+            .unwrap();
+        store.add_or_update_prediction(event, "A", "B").unwrap();
+        store.add_or_update_prediction(event, "C", "D").unwrap();
+        (store, event)
+    }
+
+    fn at(raw: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc()
+    }
+
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Sink for Recorder {
+        fn name(&self) -> &'static str {
+            "recorder"
+        }
+
+        fn send(&self, _: &NotificationPrefs, notification: &Notification) -> SinkResult {
+            self.0.lock().unwrap().push(notification.title.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn reminds_once_within_a_day_of_lock() {
+        let (store, event) = store_with_card();
+        store
+            .set_event_start(
+                event,
+                Some(NaiveTime::from_hms_opt(18, 0, 0).unwrap()),
+                Some("UTC"),
+            )
+            .unwrap();
+        assert_eq!(
+            queue_lock_reminders(&store, at("2025-01-01 17:00")).unwrap(),
+            0
+        );
+        assert_eq!(
+            queue_lock_reminders(&store, at("2025-01-01 19:00")).unwrap(),
+            1
+        );
+        assert_eq!(
+            queue_lock_reminders(&store, at("2025-01-02 12:00")).unwrap(),
+            0
+        );
+        let queued = store.get_unsent_notifications().unwrap();
+        assert_eq!(queued[0].title, "UFC 1 locks in 23 hours");
+        assert_eq!(queued[0].body, "You've picked 2 of 2 bouts.");
+    }
+
+    #[test]
+    fn reminds_by_the_events_own_clock() {
+        let (store, event) = store_with_card();
+        // 18:00 in New York is 23:00 UTC
+        store
+            .set_event_start(
+                event,
+                Some(NaiveTime::from_hms_opt(18, 0, 0).unwrap()),
+                Some("America/New_York"),
+            )
+            .unwrap();
+        assert_eq!(
+            queue_lock_reminders(&store, at("2025-01-01 22:00")).unwrap(),
+            0
+        );
+        assert_eq!(
+            queue_lock_reminders(&store, at("2025-01-01 23:30")).unwrap(),
+            1
+        );
+        let queued = store.get_unsent_notifications().unwrap();
+        assert_eq!(queued[0].title, "UFC 1 locks in 23 hours");
+    }
+
+    #[test]
+    fn notices_picks_dropped_from_the_card() {
+        let (store, event) = store_with_card();
+        let new_card = [Bout {
+            event_id: event,
+            fighter1: "B".to_string(),
+            fighter2: "A".to_string(),
+            ..Default::default()
+        }];
+        assert_eq!(queue_card_changes(&store, event, &new_card).unwrap(), 1);
+        assert_eq!(queue_card_changes(&store, event, &new_card).unwrap(), 0);
+        assert_eq!(queue_card_changes(&store, event, &[]).unwrap(), 0);
+    }
+
+    #[test]
+    fn reports_results_through_every_sink() {
+        let (store, event) = store_with_card();
+        store.add_or_update_result(event, "A", "B").unwrap();
+        store.add_or_update_result(event, "D", "C").unwrap();
+        let long_after = dates::parse_event_date("2025-03-01").unwrap();
+        assert_eq!(queue_results(&store, event, long_after).unwrap(), 0);
+        let today = dates::parse_event_date("2025-01-03").unwrap();
+        assert_eq!(queue_results(&store, event, today).unwrap(), 1);
+        assert_eq!(
+            store.get_unsent_notifications().unwrap()[0].body,
+            "You went 1/2."
+        );
+
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let sinks: Vec<Box<dyn Sink>> = vec![Box::new(Recorder(sent.clone()))];
+        assert_eq!(dispatch(&store, &sinks).unwrap(), 1);
+        assert_eq!(dispatch(&store, &sinks).unwrap(), 0);
+        assert_eq!(*sent.lock().unwrap(), vec!["Results are in for UFC 1"]);
+    }
+}
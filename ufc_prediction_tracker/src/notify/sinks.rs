@@ -0,0 +1,126 @@
+use crate::db::{Notification, NotificationPrefs};
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use serde_json::json;
+use std::error::Error;
+use std::time::Duration;
+
+pub type SinkResult = Result<(), Box<dyn Error + Send + Sync>>;
+
+/// Somewhere notifications can be sent. Sinks are called from the watcher thread, so they block.
+pub trait Sink: Send {
+    fn name(&self) -> &'static str;
+
+    /// Sends the notification where the user's preferences say. Does nothing when the user hasn't
+    /// set this sink up.
+    fn send(&self, prefs: &NotificationPrefs, notification: &Notification) -> SinkResult;
+}
+
+/// POSTs each notification as JSON to the user's webhook URL.
+pub struct WebhookSink {
+    client: reqwest::blocking::Client,
+}
+
+impl Default for WebhookSink {
+    fn default() -> Self {
+        WebhookSink {
+            client: reqwest::blocking::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to build webhook client"),
+        }
+    }
+}
+
+impl Sink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn send(&self, prefs: &NotificationPrefs, notification: &Notification) -> SinkResult {
+        let Some(url) = prefs.webhook_url.as_deref() else {
+            return Ok(());
+        };
+        self.client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(
+                json!({
+                    "user": notification.user,
+                    "kind": notification.kind.as_str(),
+                    "event_id": notification.event_id,
+                    "title": notification.title,
+                    "body": notification.body,
+                })
+                .to_string(),
+            )
+            .send()?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Emails each notification to the user's address over SMTP.
+pub struct EmailSink {
+    transport: SmtpTransport,
+    from: Mailbox,
+}
+
+impl EmailSink {
+    /// Set up from `UFC_SMTP_HOST`, `UFC_SMTP_USER`, `UFC_SMTP_PASSWORD` and `UFC_SMTP_FROM`, or
+    /// `None` if any is missing or invalid.
+    pub fn from_env() -> Option<Self> {
+        let var = |name| std::env::var(name).ok().filter(|v: &String| !v.is_empty());
+        let transport = SmtpTransport::relay(&var("UFC_SMTP_HOST")?)
+            .ok()?
+            .credentials(Credentials::new(
+                var("UFC_SMTP_USER")?,
+                var("UFC_SMTP_PASSWORD")?,
+            ))
+            .build();
+        let from = var("UFC_SMTP_FROM")?.parse().ok()?;
+        Some(EmailSink { transport, from })
+    }
+}
+
+impl Sink for EmailSink {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    fn send(&self, prefs: &NotificationPrefs, notification: &Notification) -> SinkResult {
+        let Some(to) = prefs.email.as_deref() else {
+            return Ok(());
+        };
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to.parse()?)
+            .subject(&notification.title)
+            .body(notification.body.clone())?;
+        self.transport.send(&message)?;
+        Ok(())
+    }
+}
+
+/// Shows notifications on the machine the desktop app runs on, for users who turned it on.
+#[cfg(feature = "desktop")]
+pub struct DesktopSink;
+
+#[cfg(feature = "desktop")]
+impl Sink for DesktopSink {
+    fn name(&self) -> &'static str {
+        "desktop"
+    }
+
+    fn send(&self, prefs: &NotificationPrefs, notification: &Notification) -> SinkResult {
+        if !prefs.desktop {
+            return Ok(());
+        }
+        notify_rust::Notification::new()
+            .summary(&notification.title)
+            .body(&notification.body)
+            .show()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+use super::{selector, text_of, Diagnostic, Headers, Parsed};
+use crate::db::{Bout, BoutOutcome};
+
+const RESULT_HEADER: &str = "W/L";
+const FIGHTER_HEADER: &str = "Fighter";
+const WEIGHT_CLASS_HEADER: &str = "Weight class";
+const METHOD_HEADER: &str = "Method";
+const ROUND_HEADER: &str = "Round";
+const TIME_HEADER: &str = "Time";
+
+/// Parses the fight table on an event-details page. Completed events list the winner first, with a
+/// "win" flag; bouts that haven't happened yet come back with no outcome.
+///
+/// Columns are found by their header, falling back to where they've always been if one is missing.
+pub fn parse_event_card(html: &str, event_id: usize) -> Parsed<Vec<Bout>> {
+    let doc = scraper::Html::parse_document(html);
+    let headers = Headers::new(doc.select(&selector("thead th")));
+    let mut diagnostics = headers.check(&[
+        RESULT_HEADER,
+        FIGHTER_HEADER,
+        WEIGHT_CLASS_HEADER,
+        METHOD_HEADER,
+        ROUND_HEADER,
+        TIME_HEADER,
+    ]);
+    let column = |header: &str, usual: usize| headers.index_of(header).unwrap_or(usual);
+    let (weight_class_col, method_col, round_col, time_col) = (
+        column(WEIGHT_CLASS_HEADER, 6),
+        column(METHOD_HEADER, 7),
+        column(ROUND_HEADER, 8),
+        column(TIME_HEADER, 9),
+    );
+
+    let row_selector = selector("tbody tr.b-fight-details__table-row");
+    let col_selector = selector("td");
+    let text_selector = selector("p.b-fight-details__table-text");
+    let fighter_selector = selector("a.b-link");
+    let flag_selector = selector("i.b-flag__text");
+    let rows = doc.select(&row_selector).collect::<Vec<_>>();
+    // a table whose rows are all classed differently would otherwise read as an empty card
+    if rows.is_empty() && doc.select(&selector("tbody tr")).next().is_some() {
+        diagnostics.push(Diagnostic::page("No rows found"));
+    }
+    let mut bouts = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        if text_of(row).is_empty() {
+            continue;
+        }
+        let fighters = row
+            .select(&fighter_selector)
+            .filter(|a| {
+                a.value()
+                    .attr("href")
+                    .is_some_and(|href| href.contains("fighter-details"))
+            })
+            .map(|a| (text_of(a), a.value().attr("href").map(str::to_string)))
+            .collect::<Vec<_>>();
+        let Ok([(fighter1, fighter1_link), (fighter2, fighter2_link)]) =
+            <[_; 2]>::try_from(fighters)
+        else {
+            diagnostics.push(Diagnostic::row(i, "Doesn't link exactly two fighters"));
+            continue;
+        };
+        if fighter1.is_empty() || fighter2.is_empty() {
+            diagnostics.push(Diagnostic::row(i, "A fighter has no name"));
+            continue;
+        }
+        let cols = row.select(&col_selector).collect::<Vec<_>>();
+        // the first paragraph of a column, e.g. "Lightweight" or "KO/TKO"
+        let col_text = |i: usize| {
+            cols.get(i)
+                .and_then(|col| col.select(&text_selector).next())
+                .map(text_of)
+                .filter(|t| !t.is_empty())
+        };
+        // bouts still to come have no flag, or a "next" one
+        let outcome = match row
+            .select(&flag_selector)
+            .next()
+            .map(text_of)
+            .filter(|flag| !flag.is_empty() && flag != "next")
+        {
+            Some(flag) => match BoutOutcome::parse(&flag) {
+                Some(outcome) => Some(outcome),
+                None => {
+                    diagnostics.push(Diagnostic::row(i, format!("Unknown result {:?}", flag)));
+                    None
+                }
+            },
+            None => None,
+        };
+        let round = outcome.and(col_text(round_col));
+        let parsed_round = round.as_deref().and_then(|r| r.parse().ok());
+        if let (Some(round), None) = (&round, parsed_round) {
+            diagnostics.push(Diagnostic::row(
+                i,
+                format!("Round {:?} isn't a number", round),
+            ));
+        }
+        bouts.push(Bout {
+            event_id,
+            position: bouts.len(),
+            winner: (outcome == Some(BoutOutcome::Win)).then(|| fighter1.clone()),
+            fighter1,
+            fighter2,
+            fighter1_link,
+            fighter2_link,
+            weight_class: col_text(weight_class_col),
+            outcome,
+            method: outcome.and(col_text(method_col)),
+            round: parsed_round,
+            time: outcome.and(col_text(time_col)),
+        });
+    }
+    Parsed {
+        value: bouts,
+        diagnostics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPLETED_CARD: &str = r#"<table>
+        <thead class="b-fight-details__table-head">
+          <tr class="b-fight-details__table-row">
+            <th class="b-fight-details__table-col">W/L</th>
+            <th class="b-fight-details__table-col">Fighter</th>
+            <th class="b-fight-details__table-col">Kd</th>
+            <th class="b-fight-details__table-col">Str</th>
+            <th class="b-fight-details__table-col">Td</th>
+            <th class="b-fight-details__table-col">Sub</th>
+            <th class="b-fight-details__table-col">Weight class</th>
+            <th class="b-fight-details__table-col">Method</th>
+            <th class="b-fight-details__table-col">Round</th>
+            <th class="b-fight-details__table-col">Time</th>
+          </tr>
+        </thead>
+        <tbody class="b-fight-details__table-body">
+        <tr class="b-fight-details__table-row b-fight-details__table-row__hover js-fight-details-click">
+          <td class="b-fight-details__table-col"><p class="b-fight-details__table-text">
+            <a href="http://ufcstats.com/fight-details/1" class="b-flag b-flag_style_green"><i class="b-flag__inner"><i class="b-flag__text">win</i></i></a>
+          </p></td>
+          <td class="b-fight-details__table-col l-page_align_left">
+            <p class="b-fight-details__table-text"><a href="http://ufcstats.com/fighter-details/a" class="b-link b-link_style_black">Islam Makhachev</a></p>
+            <p class="b-fight-details__table-text"><a href="http://ufcstats.com/fighter-details/b" class="b-link b-link_style_black">Jack Della Maddalena</a></p>
+          </td>
+          <td class="b-fight-details__table-col"><p class="b-fight-details__table-text">0</p><p class="b-fight-details__table-text">0</p></td>
+          <td class="b-fight-details__table-col"><p class="b-fight-details__table-text">99</p><p class="b-fight-details__table-text">40</p></td>
+          <td class="b-fight-details__table-col"><p class="b-fight-details__table-text">4</p><p class="b-fight-details__table-text">0</p></td>
+          <td class="b-fight-details__table-col"><p class="b-fight-details__table-text">0</p><p class="b-fight-details__table-text">0</p></td>
+          <td class="b-fight-details__table-col l-page_align_left"><p class="b-fight-details__table-text">Welterweight</p></td>
+          <td class="b-fight-details__table-col l-page_align_left"><p class="b-fight-details__table-text">U-DEC</p><p class="b-fight-details__table-text"></p></td>
+          <td class="b-fight-details__table-col"><p class="b-fight-details__table-text">5</p></td>
+          <td class="b-fight-details__table-col"><p class="b-fight-details__table-text">5:00</p></td>
+        </tr>
+        <tr class="b-fight-details__table-row b-fight-details__table-row__hover js-fight-details-click">
+          <td class="b-fight-details__table-col"><p class="b-fight-details__table-text">
+            <a href="http://ufcstats.com/fight-details/2" class="b-flag b-flag_style_bordered"><i class="b-flag__inner"><i class="b-flag__text">draw</i></i></a>
+          </p></td>
+          <td class="b-fight-details__table-col l-page_align_left">
+            <p class="b-fight-details__table-text"><a href="http://ufcstats.com/fighter-details/c" class="b-link b-link_style_black">Fighter C</a></p>
+            <p class="b-fight-details__table-text"><a href="http://ufcstats.com/fighter-details/d" class="b-link b-link_style_black">Fighter D</a></p>
+          </td>
+        </tr>
+    </tbody></table>"#;
+
+    #[test]
+    fn parses_completed_bouts() {
+        let parsed = parse_event_card(COMPLETED_CARD, 7);
+        assert!(parsed.is_healthy(), "{:?}", parsed.diagnostics);
+        let bouts = parsed.value;
+        assert_eq!(bouts.len(), 2);
+        assert_eq!(bouts[0].event_id, 7);
+        assert_eq!(bouts[0].winner.as_deref(), Some("Islam Makhachev"));
+        assert_eq!(bouts[0].loser(), Some("Jack Della Maddalena"));
+        assert_eq!(bouts[0].weight_class.as_deref(), Some("Welterweight"));
+        assert_eq!(bouts[0].method.as_deref(), Some("U-DEC"));
+        assert_eq!(bouts[0].round, Some(5));
+        assert_eq!(bouts[0].time.as_deref(), Some("5:00"));
+        assert_eq!(bouts[1].position, 1);
+        assert_eq!(bouts[1].outcome, Some(BoutOutcome::Draw));
+        assert_eq!(bouts[1].winner, None);
+    }
+
+    #[test]
+    fn keeps_readable_rows_when_the_table_changes() {
+        let drifted = COMPLETED_CARD
+            .replace(">Method</th>", ">How</th>")
+            .replace(">draw<", ">split<")
+            .replace("fighter-details/d", "athlete/d");
+        let parsed = parse_event_card(&drifted, 7);
+        assert_eq!(parsed.value.len(), 1);
+        assert_eq!(parsed.value[0].method.as_deref(), Some("U-DEC"));
+        let messages = parsed
+            .diagnostics
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                "The table has no \"Method\" column",
+                "row 1: Doesn't link exactly two fighters"
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_rows_that_no_longer_match() {
+        let drifted = COMPLETED_CARD.replace(
+            "<tr class=\"b-fight-details__table-row b-fight-details",
+            "<tr class=\"b-fight-row b-fight-details",
+        );
+        let parsed = parse_event_card(&drifted, 7);
+        assert!(parsed.value.is_empty());
+        assert!(!parsed.is_healthy());
+        assert_eq!(parsed.diagnostics[0].to_string(), "No rows found");
+    }
+}
@@ -0,0 +1,246 @@
+use super::{Diagnostic, Parsed};
+use crate::db::{dates, Bout, BoutOutcome};
+use serde::{Deserialize, Serialize};
+
+/// Events from any promotion, as JSON, for cards ufcstats doesn't cover. `promotion` applies to
+/// every event that doesn't name its own.
+///
+/// ```json
+/// {
+///   "promotion": "PFL",
+///   "events": [{
+///     "name": "PFL World Tournament 2026: Semifinals",
+///     "date": "2026-08-01",
+///     "location": "Nashville, Tennessee, USA",
+///     "start_time": "21:00",
+///     "timezone": "America/Chicago",
+///     "bouts": [
+///       { "fighter1": "Fighter A", "fighter2": "Fighter B", "weight_class": "Lightweight" },
+///       { "fighter1": "Fighter C", "fighter2": "Fighter D", "winner": "Fighter D", "method": "KO/TKO", "round": 2, "time": "3:10" }
+///     ]
+///   }]
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CardFile {
+    pub promotion: Option<String>,
+    pub events: Vec<CardFileEvent>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CardFileEvent {
+    pub name: String,
+    /// YYYY-MM-DD, or as ufcstats writes dates.
+    pub date: String,
+    pub promotion: Option<String>,
+    /// The event's page on the promotion's site.
+    pub link: Option<String>,
+    pub location: Option<String>,
+    pub venue: Option<String>,
+    /// HH:MM local to `timezone`.
+    pub prelims_time: Option<String>,
+    /// HH:MM local to `timezone`.
+    pub start_time: Option<String>,
+    pub timezone: Option<String>,
+    /// Main event first.
+    #[serde(default)]
+    pub bouts: Vec<CardFileBout>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CardFileBout {
+    pub fighter1: String,
+    pub fighter2: String,
+    pub weight_class: Option<String>,
+    /// "win", "draw" or "nc"; a `winner` alone means a win.
+    pub outcome: Option<String>,
+    pub winner: Option<String>,
+    pub method: Option<String>,
+    pub round: Option<u32>,
+    pub time: Option<String>,
+}
+
+/// An event from a card file that's ready to store: its promotion settled, its date as YYYY-MM-DD
+/// and its bouts (with `event_id` still to fill in) checked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedEvent {
+    pub promotion: String,
+    pub event: CardFileEvent,
+    pub bouts: Vec<Bout>,
+}
+
+/// Reads a card file. Only malformed JSON is an error; events and bouts that don't make sense are
+/// left out with a diagnostic, and start times that don't parse are dropped.
+pub fn parse_card_file(text: &str) -> Result<Parsed<Vec<LoadedEvent>>, serde_json::Error> {
+    let file: CardFile = serde_json::from_str(text)?;
+    let mut diagnostics = Vec::new();
+    let mut events = Vec::new();
+    for (i, mut event) in file.events.into_iter().enumerate() {
+        let promotion = event
+            .promotion
+            .clone()
+            .or_else(|| file.promotion.clone())
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty());
+        let Some(promotion) = promotion else {
+            diagnostics.push(Diagnostic::row(i, "No promotion"));
+            continue;
+        };
+        if event.name.trim().is_empty() {
+            diagnostics.push(Diagnostic::row(i, "No event name"));
+            continue;
+        }
+        let Ok(date) = dates::parse_event_date(&event.date) else {
+            diagnostics.push(Diagnostic::row(
+                i,
+                format!("{}: unreadable date {:?}", event.name, event.date),
+            ));
+            continue;
+        };
+        event.date = date.format(dates::ISO_DATE).to_string();
+        for time in [&mut event.prelims_time, &mut event.start_time] {
+            if time
+                .as_deref()
+                .is_some_and(|raw| dates::parse_start_time(raw).is_err())
+            {
+                diagnostics.push(Diagnostic::row(
+                    i,
+                    format!("{}: unreadable time {:?}", event.name, time),
+                ));
+                *time = None;
+            }
+        }
+
+        let mut bouts = Vec::new();
+        for bout in std::mem::take(&mut event.bouts) {
+            match card_file_bout(bout, bouts.len()) {
+                Ok(bout) => bouts.push(bout),
+                Err(message) => {
+                    diagnostics.push(Diagnostic::row(i, format!("{}: {}", event.name, message)))
+                }
+            }
+        }
+        events.push(LoadedEvent {
+            promotion,
+            event,
+            bouts,
+        });
+    }
+    Ok(Parsed {
+        value: events,
+        diagnostics,
+    })
+}
+
+fn card_file_bout(bout: CardFileBout, position: usize) -> Result<Bout, String> {
+    let (fighter1, fighter2) = (bout.fighter1.trim(), bout.fighter2.trim());
+    if fighter1.is_empty() || fighter2.is_empty() || fighter1 == fighter2 {
+        return Err(format!(
+            "bout {:?} vs {:?} needs two fighters",
+            fighter1, fighter2
+        ));
+    }
+    let winner = bout.winner.as_deref().map(str::trim);
+    if winner.is_some_and(|w| w != fighter1 && w != fighter2) {
+        return Err(format!(
+            "{:?} didn't fight in {} vs {}",
+            winner.unwrap_or_default(),
+            fighter1,
+            fighter2
+        ));
+    }
+    let outcome = match (bout.outcome.as_deref(), winner) {
+        (Some(raw), _) => {
+            Some(BoutOutcome::parse(raw).ok_or_else(|| format!("unknown outcome {:?}", raw))?)
+        }
+        (None, Some(_)) => Some(BoutOutcome::Win),
+        (None, None) => None,
+    };
+    if (outcome == Some(BoutOutcome::Win)) != winner.is_some() {
+        return Err(format!(
+            "{} vs {}: a winner goes with a win, and only a win",
+            fighter1, fighter2
+        ));
+    }
+    Ok(Bout {
+        position,
+        fighter1: fighter1.to_string(),
+        fighter2: fighter2.to_string(),
+        weight_class: bout.weight_class,
+        outcome,
+        winner: winner.map(str::to_string),
+        method: bout.method,
+        round: bout.round,
+        time: bout.time,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_events_and_results() {
+        let text = r#"{
+            "promotion": "PFL",
+            "events": [{
+                "name": "PFL 1",
+                "date": "August 01, 2026",
+                "start_time": "21:00",
+                "bouts": [
+                    { "fighter1": "A", "fighter2": "B", "weight_class": "Lightweight" },
+                    { "fighter1": "C", "fighter2": "D", "winner": "D", "method": "KO/TKO", "round": 2 },
+                    { "fighter1": "E", "fighter2": "F", "outcome": "draw" }
+                ]
+            }, {
+                "name": "ONE 170",
+                "date": "2026-08-08",
+                "promotion": "ONE"
+            }]
+        }"#;
+        let parsed = parse_card_file(text).unwrap();
+        assert!(parsed.is_healthy(), "{:?}", parsed.diagnostics);
+        let [pfl, one] = &parsed.value[..] else {
+            panic!("expected two events");
+        };
+        assert_eq!(pfl.promotion, "PFL");
+        assert_eq!(pfl.event.date, "2026-08-01");
+        assert_eq!(pfl.bouts.len(), 3);
+        assert_eq!(pfl.bouts[1].position, 1);
+        assert_eq!(pfl.bouts[1].outcome, Some(BoutOutcome::Win));
+        assert_eq!(pfl.bouts[1].loser(), Some("C"));
+        assert_eq!(pfl.bouts[2].outcome, Some(BoutOutcome::Draw));
+        assert_eq!(one.promotion, "ONE");
+        assert!(one.bouts.is_empty());
+    }
+
+    #[test]
+    fn leaves_out_what_doesnt_make_sense() {
+        let text = r#"{
+            "events": [
+                { "name": "No promotion", "date": "2026-08-01" },
+                { "name": "Bellator 1", "date": "soon", "promotion": "Bellator" },
+                {
+                    "name": "Bellator 2",
+                    "date": "2026-09-01",
+                    "promotion": "Bellator",
+                    "start_time": "late",
+                    "bouts": [
+                        { "fighter1": "A", "fighter2": "A" },
+                        { "fighter1": "C", "fighter2": "D", "winner": "E" },
+                        { "fighter1": "G", "fighter2": "H", "outcome": "win" },
+                        { "fighter1": "I", "fighter2": "J" }
+                    ]
+                }
+            ]
+        }"#;
+        let parsed = parse_card_file(text).unwrap();
+        assert_eq!(parsed.value.len(), 1);
+        assert_eq!(parsed.value[0].event.start_time, None);
+        assert_eq!(parsed.value[0].bouts.len(), 1);
+        assert_eq!(parsed.value[0].bouts[0].position, 0);
+        assert_eq!(parsed.diagnostics.len(), 6);
+        assert!(parse_card_file("not json").is_err());
+    }
+}
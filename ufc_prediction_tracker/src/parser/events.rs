@@ -0,0 +1,148 @@
+use super::{selector, text_of, Diagnostic, Headers, Parsed};
+
+const NAME_HEADER: &str = "Name/date";
+const LOCATION_HEADER: &str = "Location";
+
+/// One row of a ufcstats events listing. `date` is as written on the page, e.g. "November 15, 2025".
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListedEvent {
+    pub name: String,
+    pub date: String,
+    pub link: String,
+    pub location: String,
+}
+
+/// Parses an upcoming or completed events listing. Blank spacer rows are skipped; any other row
+/// without an event link and date is reported.
+pub fn parse_event_listing(html: &str) -> Parsed<Vec<ListedEvent>> {
+    let doc = scraper::Html::parse_document(html);
+    let headers = Headers::new(doc.select(&selector("table.b-statistics__table-events thead th")));
+    let mut diagnostics = headers.check(&[NAME_HEADER, LOCATION_HEADER]);
+    let name_col = headers.index_of(NAME_HEADER).unwrap_or(0);
+    let location_col = headers.index_of(LOCATION_HEADER).unwrap_or(1);
+
+    let row_selector = selector("tbody tr.b-statistics__table-row");
+    let col_selector = selector("td.b-statistics__table-col");
+    let link_selector = selector("a.b-link");
+    let date_selector = selector("span.b-statistics__date");
+    let rows = doc.select(&row_selector).collect::<Vec<_>>();
+    if rows.is_empty() && doc.select(&selector("tbody tr")).next().is_some() {
+        diagnostics.push(Diagnostic::page("No rows found"));
+    }
+    let mut events = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        if text_of(row).is_empty() {
+            continue;
+        }
+        let cols = row.select(&col_selector).collect::<Vec<_>>();
+        let Some(first) = cols.get(name_col) else {
+            diagnostics.push(Diagnostic::row(i, "No name/date column"));
+            continue;
+        };
+        let link = first.select(&link_selector).next();
+        let href = link.and_then(|link| link.value().attr("href"));
+        let date = first.select(&date_selector).next().map(text_of);
+        let (Some(link), Some(href), Some(date)) = (link, href, date) else {
+            diagnostics.push(Diagnostic::row(i, "No event link and date"));
+            continue;
+        };
+        let name = text_of(link);
+        if name.is_empty() || date.is_empty() {
+            diagnostics.push(Diagnostic::row(i, "Empty event name or date"));
+            continue;
+        }
+        events.push(ListedEvent {
+            name,
+            date,
+            link: href.to_string(),
+            location: cols
+                .get(location_col)
+                .map(|col| text_of(*col))
+                .unwrap_or_default(),
+        });
+    }
+    Parsed {
+        value: events,
+        diagnostics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = r#"<table class="b-statistics__table-events">
+        <thead class="b-statistics__table-caption">
+          <tr class="b-statistics__table-row">
+            <th class="b-statistics__table-col">Name/date</th>
+            <th class="b-statistics__table-col">Location</th>
+          </tr>
+        </thead>
+        <tbody>
+        <tr class="b-statistics__table-row"><td class="b-statistics__table-col b-statistics__table-col_type_clear"></td></tr>
+        <tr class="b-statistics__table-row">
+          <td class="b-statistics__table-col">
+            <i class="b-statistics__table-content">
+              <a href="http://ufcstats.com/event-details/abc" class="b-link b-link_style_black">
+                UFC 322: Della Maddalena vs. Makhachev
+              </a>
+              <span class="b-statistics__date">
+                November 15, 2025
+              </span>
+            </i>
+          </td>
+          <td class="b-statistics__table-col b-statistics__table-col_style_big-top-padding">
+            New York City, New York, USA
+          </td>
+        </tr>
+    </tbody></table>"#;
+
+    #[test]
+    fn parses_listing_rows_with_location() {
+        let parsed = parse_event_listing(LISTING);
+        assert!(parsed.is_healthy(), "{:?}", parsed.diagnostics);
+        assert_eq!(
+            parsed.value,
+            vec![ListedEvent {
+                name: "UFC 322: Della Maddalena vs. Makhachev".to_string(),
+                date: "November 15, 2025".to_string(),
+                link: "http://ufcstats.com/event-details/abc".to_string(),
+                location: "New York City, New York, USA".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_changed_headers_and_unreadable_rows() {
+        let drifted = LISTING
+            .replace("Location</th>", "Venue</th>")
+            .replace("b-statistics__date", "b-statistics__when");
+        let parsed = parse_event_listing(&drifted);
+        assert!(parsed.value.is_empty());
+        let messages = parsed
+            .diagnostics
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                "The table has no \"Location\" column",
+                "row 1: No event link and date"
+            ]
+        );
+        assert!(!parse_event_listing("<html></html>").is_healthy());
+
+        let restyled = LISTING.replace("b-statistics__table-row", "b-events-row");
+        let parsed = parse_event_listing(&restyled);
+        assert!(parsed.value.is_empty());
+        assert_eq!(
+            parsed
+                .diagnostics
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["No rows found"]
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use super::{normalize, selector, text_of, Diagnostic, Parsed};
+use crate::db::{self, Fighter};
+use std::collections::HashMap;
+
+/// The attributes every fighter page lists, lowercased without the trailing colon. Their values can
+/// be "--" for unknown, but the labels are always there.
+const EXPECTED_ATTRIBUTES: [&str; 13] = [
+    "height",
+    "weight",
+    "reach",
+    "stance",
+    "dob",
+    "slpm",
+    "str. acc.",
+    "sapm",
+    "str. def",
+    "td avg.",
+    "td acc.",
+    "td def.",
+    "sub. avg.",
+];
+
+// "5' 10\"" / "70\"" / "48%" / "4.52" with "--" meaning unknown
+fn parse_stat(raw: &str) -> Option<f64> {
+    raw.trim().trim_end_matches(['%', '"']).trim().parse().ok()
+}
+
+/// Parses a fighter-details page: the header, the physical attributes and the career statistics.
+/// There's no fighter without a name.
+pub fn parse_fighter_page(html: &str, id: &str) -> Parsed<Option<Fighter>> {
+    let doc = scraper::Html::parse_document(html);
+    let first_text = |css: &str| {
+        doc.select(&selector(css))
+            .next()
+            .map(text_of)
+            .filter(|t| !t.is_empty())
+    };
+    let Some(name) = first_text("span.b-content__title-highlight") else {
+        return Parsed {
+            value: None,
+            diagnostics: vec![Diagnostic::page("No fighter name")],
+        };
+    };
+    let mut diagnostics = Vec::new();
+    let record = first_text("span.b-content__title-record")
+        .map(|r| r.trim_start_matches("Record:").trim().to_string());
+    if record.is_none() {
+        diagnostics.push(Diagnostic::page("No record"));
+    }
+    let nickname = first_text("p.b-content__Nickname");
+
+    // every attribute is an <li> like <li><i>Reach:</i> 70"</li>
+    let item_selector = selector("li.b-list__box-list-item");
+    let title_selector = selector("i.b-list__box-item-title");
+    let mut labels = Vec::new();
+    let attributes = doc
+        .select(&item_selector)
+        .filter_map(|item| {
+            let title = text_of(item.select(&title_selector).next()?);
+            let label = normalize(title.trim_end_matches(':'));
+            labels.push(label.clone());
+            let value = text_of(item);
+            let value = value.trim_start_matches(&title).trim().to_string();
+            (!label.is_empty() && !value.is_empty() && value != "--").then_some((label, value))
+        })
+        .collect::<HashMap<_, _>>();
+    let missing = EXPECTED_ATTRIBUTES
+        .iter()
+        .filter(|label| !labels.iter().any(|l| l == *label))
+        .copied()
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        diagnostics.push(Diagnostic::page(format!(
+            "No {} attributes",
+            missing.join(", ")
+        )));
+    }
+    let attr = |label: &str| attributes.get(label).cloned();
+    let stat = |label: &str| attributes.get(label).and_then(|v| parse_stat(v));
+
+    // the fight history lists the newest bout first; an upcoming bout has a "next" flag instead of a result
+    let recent_form = doc
+        .select(&selector("i.b-flag__text"))
+        .filter_map(|flag| match text_of(flag).as_str() {
+            "win" => Some('W'),
+            "loss" => Some('L'),
+            "draw" => Some('D'),
+            "nc" => Some('N'),
+            _ => None,
+        })
+        .take(db::RECENT_FORM_LEN)
+        .collect::<String>();
+
+    Parsed {
+        value: Some(Fighter {
+            id: id.to_string(),
+            name,
+            nickname,
+            record,
+            height: attr("height"),
+            weight: attr("weight"),
+            reach_inches: stat("reach"),
+            stance: attr("stance"),
+            dob: attr("dob")
+                .and_then(|d| db::dates::parse_event_date(&d).ok())
+                .map(|d| d.format(db::dates::ISO_DATE).to_string()),
+            slpm: stat("slpm"),
+            str_acc: stat("str. acc."),
+            sapm: stat("sapm"),
+            str_def: stat("str. def"),
+            td_avg: stat("td avg."),
+            td_acc: stat("td acc."),
+            td_def: stat("td def."),
+            sub_avg: stat("sub. avg."),
+            recent_form: Some(recent_form).filter(|f| !f.is_empty()),
+        }),
+        diagnostics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(label: &str, value: &str) -> String {
+        format!(
+            r#"<li class="b-list__box-list-item b-list__box-list-item_type_block">
+            <i class="b-list__box-item-title b-list__box-item-title_type_width">{label}:</i>
+            {value}
+          </li>"#
+        )
+    }
+
+    fn fighter_page(attributes: &[(&str, &str)]) -> String {
+        let items = attributes
+            .iter()
+            .map(|(label, value)| attribute(label, value))
+            .collect::<String>();
+        format!(
+            r#"<section>
+        <h2 class="b-content__title">
+          <span class="b-content__title-highlight">
+            Islam Makhachev
+          </span>
+          <span class="b-content__title-record">
+            Record: 27-1-0
+          </span>
+        </h2>
+        <p class="b-content__Nickname"></p>
+        <ul class="b-list__box-list">{items}</ul>
+        <table><tbody>
+          <tr class="b-fight-details__table-row"><td><p><a class="b-flag b-flag_style_bordered"><i class="b-flag__inner"><i class="b-flag__text">next</i></i></a></p></td></tr>
+          <tr class="b-fight-details__table-row"><td><p><a class="b-flag b-flag_style_red"><i class="b-flag__inner"><i class="b-flag__text">loss</i></i></a></p></td></tr>
+          <tr class="b-fight-details__table-row"><td><p><a class="b-flag b-flag_style_green"><i class="b-flag__inner"><i class="b-flag__text">win</i></i></a></p></td></tr>
+        </tbody></table>
+    </section>"#
+        )
+    }
+
+    const ATTRIBUTES: [(&str, &str); 13] = [
+        ("Height", "5' 10\""),
+        ("Weight", "155 lbs."),
+        ("Reach", "70\""),
+        ("STANCE", "Southpaw"),
+        ("DOB", "Oct 27, 1991"),
+        ("SLpM", "2.47"),
+        ("Str. Acc.", "59%"),
+        ("SApM", "1.47"),
+        ("Str. Def", "60%"),
+        ("TD Avg.", "3.17"),
+        ("TD Acc.", "60%"),
+        ("TD Def.", "90%"),
+        ("Sub. Avg.", "--"),
+    ];
+
+    #[test]
+    fn parses_fighter_profile() {
+        let parsed = parse_fighter_page(&fighter_page(&ATTRIBUTES), "abc");
+        assert!(parsed.is_healthy(), "{:?}", parsed.diagnostics);
+        let fighter = parsed.value.unwrap();
+        assert_eq!(fighter.name, "Islam Makhachev");
+        assert_eq!(fighter.record.as_deref(), Some("27-1-0"));
+        assert_eq!(fighter.nickname, None);
+        assert_eq!(fighter.height.as_deref(), Some("5' 10\""));
+        assert_eq!(fighter.reach_inches, Some(70.0));
+        assert_eq!(fighter.stance.as_deref(), Some("Southpaw"));
+        assert_eq!(fighter.dob.as_deref(), Some("1991-10-27"));
+        assert_eq!(fighter.slpm, Some(2.47));
+        assert_eq!(fighter.str_acc, Some(59.0));
+        assert_eq!(fighter.td_avg, Some(3.17));
+        assert_eq!(fighter.sub_avg, None);
+        assert_eq!(fighter.recent_form.as_deref(), Some("LW"));
+    }
+
+    #[test]
+    fn reports_missing_attributes() {
+        let parsed = parse_fighter_page(&fighter_page(&ATTRIBUTES[..11]), "abc");
+        assert_eq!(parsed.value.unwrap().td_avg, Some(3.17));
+        assert_eq!(
+            parsed.diagnostics,
+            vec![Diagnostic::page("No td def., sub. avg. attributes")]
+        );
+        assert_eq!(parse_fighter_page("<html></html>", "abc").value, None);
+    }
+}
@@ -0,0 +1,146 @@
+//! Parsers for the ufcstats pages we scrape: the events listings, an event's card and a fighter's
+//! profile.
+//!
+//! Parsing never fails outright. Each parser returns what it could read along with
+//! [`Diagnostic`]s for everything that didn't look as expected: a missing table header, a row that
+//! couldn't be read, a profile field that's gone. A page with diagnostics is taken to have changed
+//! structure, and the scraper records that as the page's parser health, so a redesign shows up as a
+//! warning rather than as picks quietly disappearing.
+//!
+//! Card files, for promotions ufcstats doesn't cover, are read the same way: see [`CardFile`].
+
+mod card;
+pub use card::*;
+mod card_file;
+pub use card_file::*;
+mod events;
+pub use events::*;
+mod fighter;
+pub use fighter::*;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Which kind of ufcstats page was parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PageKind {
+    EventListing,
+    EventCard,
+    Fighter,
+}
+
+impl PageKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PageKind::EventListing => "event_listing",
+            PageKind::EventCard => "event_card",
+            PageKind::Fighter => "fighter",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "event_listing" => Some(PageKind::EventListing),
+            "event_card" => Some(PageKind::EventCard),
+            "fighter" => Some(PageKind::Fighter),
+            _ => None,
+        }
+    }
+
+    /// How the page is described to users.
+    pub fn label(&self) -> &'static str {
+        match self {
+            PageKind::EventListing => "Events listing",
+            PageKind::EventCard => "Event card",
+            PageKind::Fighter => "Fighter profile",
+        }
+    }
+}
+
+/// Something on a page that didn't look as expected. `row` is the zero-based table row, where the
+/// problem is with one row.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub row: Option<usize>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn page(message: impl Into<String>) -> Self {
+        Diagnostic {
+            row: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn row(row: usize, message: impl Into<String>) -> Self {
+        Diagnostic {
+            row: Some(row),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.row {
+            Some(row) => write!(f, "row {}: {}", row, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// What a parser read from a page, and what it found wrong with it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parsed<T> {
+    pub value: T,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl<T> Parsed<T> {
+    /// Whether the page had the structure the parser expects.
+    pub fn is_healthy(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
+/// Table headers as written, mapped to their column index. Matching ignores case and spacing.
+struct Headers(Vec<String>);
+
+impl Headers {
+    fn new<'a>(headers: impl Iterator<Item = scraper::ElementRef<'a>>) -> Self {
+        Headers(headers.map(|th| normalize(&text_of(th))).collect())
+    }
+
+    fn index_of(&self, header: &str) -> Option<usize> {
+        let header = normalize(header);
+        self.0.iter().position(|h| *h == header)
+    }
+
+    /// A diagnostic for each `expected` header that isn't there.
+    fn check(&self, expected: &[&str]) -> Vec<Diagnostic> {
+        if self.0.is_empty() {
+            return vec![Diagnostic::page("The table has no header row")];
+        }
+        expected
+            .iter()
+            .filter(|header| self.index_of(header).is_none())
+            .map(|header| Diagnostic::page(format!("The table has no {:?} column", header)))
+            .collect()
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn text_of(el: scraper::ElementRef) -> String {
+    el.text().collect::<String>().trim().to_string()
+}
+
+fn selector(css: &str) -> scraper::Selector {
+    scraper::Selector::parse(css).expect("Invalid selector")
+}
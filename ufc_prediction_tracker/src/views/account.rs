@@ -0,0 +1,294 @@
+use crate::{
+    api,
+    db::{ApiToken, NotificationPrefs, User},
+};
+use dioxus::prelude::*;
+
+/// Signing in or registering, the signed-in user's API tokens, and notification settings.
+#[component]
+pub fn Account() -> Element {
+    let mut user = use_signal(|| None::<User>);
+    let mut name = use_signal(String::new);
+    let mut password = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+
+    let refresh = move || {
+        spawn(async move {
+            match api::get_current_user().await {
+                Ok(u) => user.set(u),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    };
+    use_effect(refresh);
+
+    let signed_in = user().filter(|u| u.id != 0);
+
+    rsx! {
+        div { class: "container mx-auto",
+            h1 { class: "text-2xl font-bold mb-4", "Account" }
+            if let Some(e) = error() {
+                div { class: "text-red-400 mb-4", "{e}" }
+            }
+            if let Some(u) = signed_in {
+                div { class: "flex justify-between items-center mb-8",
+                    div {
+                        "Signed in as "
+                        span { class: "font-bold", "{u.name}" }
+                        if u.is_admin {
+                            span { class: "text-gray-400", " (admin)" }
+                        }
+                    }
+                    button {
+                        class: "px-4 py-2 rounded-lg border border-gray-200",
+                        onclick: move |_| {
+                            spawn(async move {
+                                if let Err(e) = api::logout().await {
+                                    error.set(Some(e.to_string()));
+                                }
+                                refresh();
+                            });
+                        },
+                        "Sign out"
+                    }
+                }
+                ApiTokens {}
+            } else {
+                if user().is_some() {
+                    div { class: "text-gray-400 mb-4",
+                        "No accounts yet, so anyone who can reach the server can use it. The first account registered becomes the admin and keeps the picks made so far."
+                    }
+                }
+                div { class: "flex flex-col gap-4 max-w-md",
+                    input {
+                        class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                        placeholder: "Name",
+                        value: "{name}",
+                        oninput: move |e| name.set(e.value()),
+                    }
+                    input {
+                        class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                        r#type: "password",
+                        placeholder: "Password",
+                        value: "{password}",
+                        oninput: move |e| password.set(e.value()),
+                    }
+                    div { class: "flex gap-4",
+                        button {
+                            class: "flex-1 px-4 py-2 rounded-lg bg-blue-500 text-white font-bold",
+                            onclick: move |_| {
+                                error.set(None);
+                                spawn(async move {
+                                    match api::login(name(), password()).await {
+                                        Ok(u) => {
+                                            password.set(String::new());
+                                            user.set(Some(u));
+                                        }
+                                        Err(e) => error.set(Some(e.to_string())),
+                                    }
+                                });
+                            },
+                            "Sign in"
+                        }
+                        button {
+                            class: "flex-1 px-4 py-2 rounded-lg border border-gray-200",
+                            onclick: move |_| {
+                                error.set(None);
+                                spawn(async move {
+                                    match api::register(name(), password()).await {
+                                        Ok(u) => {
+                                            password.set(String::new());
+                                            user.set(Some(u));
+                                        }
+                                        Err(e) => error.set(Some(e.to_string())),
+                                    }
+                                });
+                            },
+                            "Register"
+                        }
+                    }
+                }
+            }
+            if user().is_some() {
+                NotificationSettings {}
+            }
+        }
+    }
+}
+
+/// The signed-in user's API tokens, for scripts calling the REST API.
+#[component]
+fn ApiTokens() -> Element {
+    let mut tokens = use_signal(Vec::<ApiToken>::new);
+    let mut label = use_signal(String::new);
+    // a new token is shown once, right after it's created
+    let mut created = use_signal(|| None::<String>);
+    let mut error = use_signal(|| None::<String>);
+
+    let refresh = move || {
+        spawn(async move {
+            match api::get_api_tokens().await {
+                Ok(t) => tokens.set(t),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    };
+    use_effect(refresh);
+
+    rsx! {
+        h2 { class: "text-xl font-bold mb-2", "API tokens" }
+        div { class: "text-gray-400 text-sm mb-2",
+            "To subscribe to upcoming events from a calendar app, give it /api/v1/calendar.ics?token= followed by a token."
+        }
+        div { class: "flex gap-4 items-center",
+            input {
+                class: "flex-1 rounded-lg border border-gray-200 px-4 py-2 text-black",
+                placeholder: "What the token is for",
+                value: "{label}",
+                oninput: move |e| label.set(e.value()),
+            }
+            button {
+                class: "px-4 py-2 rounded-lg bg-blue-500 text-white font-bold",
+                onclick: move |_| {
+                    error.set(None);
+                    spawn(async move {
+                        match api::create_api_token(label()).await {
+                            Ok(token) => {
+                                label.set(String::new());
+                                created.set(Some(token));
+                            }
+                            Err(e) => error.set(Some(e.to_string())),
+                        }
+                        refresh();
+                    });
+                },
+                "Create token"
+            }
+        }
+        if let Some(token) = created() {
+            div { class: "mt-4 rounded-lg border border-green-500 p-4",
+                div { class: "text-gray-400 text-sm", "Copy it now, it won't be shown again:" }
+                code { class: "break-all", "{token}" }
+            }
+        }
+        if let Some(e) = error() {
+            div { class: "text-red-400 mt-4", "{e}" }
+        }
+        ul { class: "divide-y divide-gray-200 mt-4",
+            for token in tokens() {
+                li { class: "flex justify-between items-center py-2",
+                    div {
+                        span { class: "font-bold", "{token.label}" }
+                        span { class: "text-gray-400 text-sm ml-4",
+                            "Created {token.created_at}"
+                            if let Some(used) = token.last_used_at.clone() {
+                                ", last used {used}"
+                            }
+                        }
+                    }
+                    button {
+                        class: "px-4 py-2 rounded-lg border border-gray-200 hover:bg-red-500",
+                        onclick: move |_| {
+                            spawn(async move {
+                                if let Err(e) = api::revoke_api_token(token.id).await {
+                                    error.set(Some(e.to_string()));
+                                }
+                                refresh();
+                            });
+                        },
+                        "Revoke"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Where and about what the current user is notified.
+#[component]
+fn NotificationSettings() -> Element {
+    let mut prefs = use_signal(|| None::<NotificationPrefs>);
+    let mut message = use_signal(|| None::<String>);
+
+    use_effect(move || {
+        spawn(async move {
+            match api::get_notification_prefs().await {
+                Ok(p) => prefs.set(Some(p)),
+                Err(e) => message.set(Some(e.to_string())),
+            }
+        });
+    });
+
+    let Some(current) = prefs() else {
+        return rsx! {};
+    };
+    let toggles = [
+        ("Before an event locks", current.event_lock),
+        ("When the card changes under a pick", current.card_changed),
+        ("When results are in", current.results_in),
+        ("On this desktop", current.desktop),
+    ];
+
+    rsx! {
+        h2 { class: "text-xl font-bold mt-8 mb-2", "Notifications" }
+        div { class: "flex flex-col gap-4 max-w-md",
+            input {
+                class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                placeholder: "Webhook URL",
+                value: current.webhook_url.clone().unwrap_or_default(),
+                oninput: move |e| {
+                    if let Some(p) = prefs.write().as_mut() {
+                        p.webhook_url = Some(e.value());
+                    }
+                },
+            }
+            input {
+                class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                r#type: "email",
+                placeholder: "Email",
+                value: current.email.clone().unwrap_or_default(),
+                oninput: move |e| {
+                    if let Some(p) = prefs.write().as_mut() {
+                        p.email = Some(e.value());
+                    }
+                },
+            }
+            for (i , (label , checked)) in toggles.into_iter().enumerate() {
+                label { class: "flex items-center gap-2",
+                    input {
+                        r#type: "checkbox",
+                        checked,
+                        onchange: move |e| {
+                            if let Some(p) = prefs.write().as_mut() {
+                                let flag = match i {
+                                    0 => &mut p.event_lock,
+                                    1 => &mut p.card_changed,
+                                    2 => &mut p.results_in,
+                                    _ => &mut p.desktop,
+                                };
+                                *flag = e.checked();
+                            }
+                        },
+                    }
+                    "{label}"
+                }
+            }
+            button {
+                class: "px-4 py-2 rounded-lg bg-blue-500 text-white font-bold",
+                onclick: move |_| {
+                    let Some(p) = prefs() else { return };
+                    spawn(async move {
+                        match api::set_notification_prefs(p).await {
+                            Ok(()) => message.set(Some("Saved".to_string())),
+                            Err(e) => message.set(Some(e.to_string())),
+                        }
+                    });
+                },
+                "Save"
+            }
+            if let Some(m) = message() {
+                div { class: "text-gray-400", "{m}" }
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use super::FighterNames;
+use crate::{
+    api,
+    db::{HttpMetrics, ImportProgress},
+};
+use dioxus::prelude::*;
+
+/// Imports completed events, bouts and results from ufcstats for a date range.
+#[component]
+pub fn Backfill() -> Element {
+    let mut from = use_signal(|| "2024-01-01".to_string());
+    let mut to = use_signal(|| chrono::Local::now().date_naive().to_string());
+    let mut progress = use_signal(|| None::<(ImportProgress, bool)>);
+    let mut metrics = use_signal(Vec::<HttpMetrics>::new);
+    let mut card_file = use_signal(String::new);
+    let mut card_file_report = use_signal(|| None::<(usize, Vec<String>)>);
+    let mut error = use_signal(|| None::<String>);
+
+    let refresh = move || {
+        spawn(async move {
+            match api::get_backfill_progress(from(), to()).await {
+                Ok(p) => progress.set(Some(p)),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+            if let Ok(m) = api::get_http_metrics().await {
+                metrics.set(m);
+            }
+        });
+    };
+    // reruns when either date changes
+    use_effect(move || {
+        let _ = (from(), to());
+        refresh();
+    });
+
+    rsx! {
+        div { class: "container mx-auto",
+            h1 { class: "text-2xl font-bold mb-4", "Import Past Events" }
+            div { class: "flex flex-col md:flex-row gap-4 items-center",
+                label { "From" }
+                input {
+                    class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    r#type: "date",
+                    value: "{from}",
+                    oninput: move |e| from.set(e.value()),
+                }
+                label { "To" }
+                input {
+                    class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    r#type: "date",
+                    value: "{to}",
+                    oninput: move |e| to.set(e.value()),
+                }
+                button {
+                    class: "px-4 py-2 rounded-lg bg-blue-500 text-white font-bold disabled:opacity-50",
+                    disabled: progress().is_some_and(|(_, running)| running),
+                    onclick: move |_| {
+                        error.set(None);
+                        spawn(async move {
+                            if let Err(e) = api::start_backfill(from(), to()).await {
+                                error.set(Some(e.to_string()));
+                            }
+                            refresh();
+                        });
+                    },
+                    "Import"
+                }
+                button {
+                    class: "px-4 py-2 rounded-lg border border-gray-200",
+                    onclick: move |_| refresh(),
+                    "Refresh"
+                }
+            }
+            if let Some(e) = error() {
+                div { class: "text-red-400 mt-4", "{e}" }
+            }
+            if let Some((p, running)) = progress() {
+                div { class: "mt-4 text-gray-400",
+                    if running {
+                        "Importing… "
+                    }
+                    "{p.done} of {p.total} events imported"
+                    if p.failed > 0 {
+                        ", {p.failed} failed (retried on the next import)"
+                    }
+                }
+            }
+            h2 { class: "text-xl font-bold mt-8 mb-2", "Card file" }
+            p { class: "text-sm text-gray-400 mb-2",
+                "Events from promotions ufcstats doesn't cover, as JSON. See the README for the format."
+            }
+            textarea {
+                class: "w-full h-40 rounded-lg border border-gray-200 px-4 py-2 text-black font-mono text-sm",
+                placeholder: "{{\"promotion\": \"PFL\", \"events\": [...]}}",
+                value: "{card_file}",
+                oninput: move |e| card_file.set(e.value()),
+            }
+            button {
+                class: "mt-2 px-4 py-2 rounded-lg bg-blue-500 text-white font-bold disabled:opacity-50",
+                disabled: card_file().trim().is_empty(),
+                onclick: move |_| {
+                    error.set(None);
+                    spawn(async move {
+                        match api::import_card_file(card_file()).await {
+                            Ok(report) => {
+                                card_file_report.set(Some(report));
+                                card_file.set(String::new());
+                            }
+                            Err(e) => error.set(Some(e.to_string())),
+                        }
+                    });
+                },
+                "Import card file"
+            }
+            if let Some((imported, problems)) = card_file_report() {
+                div { class: "mt-2 text-gray-400",
+                    "{imported} events imported"
+                    ul { class: "list-disc ml-6",
+                        for d in problems {
+                            li { "{d}" }
+                        }
+                    }
+                }
+            }
+            FighterNames {}
+            if !metrics().is_empty() {
+                h2 { class: "text-xl font-bold mt-8 mb-2", "Requests" }
+                table { class: "w-full text-left text-sm",
+                    thead {
+                        tr { class: "text-gray-400",
+                            th { "Site" }
+                            th { "Requests" }
+                            th { "Retries" }
+                            th { "Failed" }
+                            th { "Blocked by robots.txt" }
+                            th { "Last error" }
+                        }
+                    }
+                    tbody {
+                        for m in metrics() {
+                            tr {
+                                td { "{m.host}" }
+                                td { "{m.requests}" }
+                                td { "{m.retries}" }
+                                td { "{m.failures}" }
+                                td { "{m.disallowed}" }
+                                td { class: "text-gray-400", {m.last_error.clone().unwrap_or_default()} }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
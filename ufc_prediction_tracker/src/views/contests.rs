@@ -0,0 +1,296 @@
+use crate::{
+    api,
+    db::{Contest, ContestKind, ContestPick, Event, Standing},
+    Route,
+};
+use dioxus::prelude::*;
+use std::collections::HashMap;
+
+/// Every contest, and a form to start a new one.
+#[component]
+pub fn Contests() -> Element {
+    let mut contests = use_signal(Vec::<Contest>::new);
+    let mut name = use_signal(String::new);
+    let mut kind = use_signal(ContestKind::default);
+    let mut error = use_signal(|| None::<String>);
+
+    let refresh = move || {
+        spawn(async move {
+            match api::get_contests().await {
+                Ok(c) => contests.set(c),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    };
+    use_effect(refresh);
+
+    rsx! {
+        div { class: "container mx-auto",
+            h1 { class: "text-2xl font-bold mb-4", "Contests" }
+            div { class: "flex flex-col md:flex-row gap-4 items-center",
+                input {
+                    class: "flex-1 rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    placeholder: "Contest name",
+                    value: "{name}",
+                    oninput: move |e| name.set(e.value()),
+                }
+                select {
+                    class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    onchange: move |e| kind.set(ContestKind::parse(&e.value()).unwrap_or_default()),
+                    for k in [ContestKind::Survivor, ContestKind::Confidence] {
+                        option { value: k.as_str(), selected: kind() == k, "{k.as_str()}" }
+                    }
+                }
+                button {
+                    class: "px-4 py-2 rounded-lg bg-blue-500 text-white font-bold",
+                    onclick: move |_| {
+                        error.set(None);
+                        spawn(async move {
+                            match api::create_contest(name(), kind()).await {
+                                Ok(_) => name.set(String::new()),
+                                Err(e) => error.set(Some(e.to_string())),
+                            }
+                            refresh();
+                        });
+                    },
+                    "Create"
+                }
+            }
+            if let Some(e) = error() {
+                div { class: "text-red-400 mt-4", "{e}" }
+            }
+            ul { class: "divide-y divide-gray-200 rounded-lg border border-gray-200 shadow-md mt-4",
+                for contest in contests() {
+                    Link { to: Route::ContestPage { id: contest.id }, class: "block",
+                        li { class: "p-4 hover:bg-blue-500 cursor-pointer transition-colors flex justify-between",
+                            span { class: "font-bold", "{contest.name}" }
+                            span { class: "text-gray-400 text-sm", "{contest.kind.as_str()} pool" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A contest's standings, and picking for its upcoming events.
+#[component]
+pub fn ContestPage(id: usize) -> Element {
+    let mut contest = use_signal(|| None::<(Contest, Vec<Standing>)>);
+    let mut my_picks = use_signal(Vec::<ContestPick>::new);
+    let mut events = use_signal(Vec::<Event>::new);
+    let mut event = use_signal(|| None::<Event>);
+    let mut card = use_signal(Vec::<(String, String)>::new);
+    // confidence pools: bout index to (picked side, confidence as typed)
+    let mut draft = use_signal(HashMap::<usize, (usize, String)>::new);
+    let mut message = use_signal(|| None::<String>);
+
+    let refresh = move || {
+        spawn(async move {
+            match api::get_contest(id).await {
+                Ok(c) => contest.set(Some(c)),
+                Err(e) => message.set(Some(e.to_string())),
+            }
+            if let Ok(picks) = api::get_my_contest_picks(id).await {
+                my_picks.set(picks);
+            }
+        });
+    };
+    use_effect(move || {
+        refresh();
+        spawn(async move {
+            if let Ok(upcoming) = api::get_upcoming_events().await {
+                events.set(upcoming);
+            }
+        });
+    });
+    // loads the card, and any confidence picks already made on it, when the event changes
+    use_effect(move || {
+        let Some(selected) = event() else {
+            return;
+        };
+        spawn(async move {
+            match api::get_fights(selected.id).await {
+                Ok(fights) => {
+                    let mut picked = HashMap::new();
+                    for pick in my_picks.peek().iter().filter(|p| p.event_id == selected.id) {
+                        if let Some((i, (f1, _))) = fights
+                            .iter()
+                            .enumerate()
+                            .find(|(_, (f1, f2))| *f1 == pick.winner || *f2 == pick.winner)
+                        {
+                            let side = if *f1 == pick.winner { 0 } else { 1 };
+                            let confidence =
+                                pick.confidence.map(|c| c.to_string()).unwrap_or_default();
+                            picked.insert(i, (side, confidence));
+                        }
+                    }
+                    draft.set(picked);
+                    card.set(fights);
+                }
+                Err(e) => message.set(Some(e.to_string())),
+            }
+        });
+    });
+
+    let Some((info, standings)) = contest() else {
+        return rsx! {
+            div { class: "container mx-auto text-gray-400", "Loading contest…" }
+        };
+    };
+    let event_id = event().map(|e| e.id);
+    let survivor_pick = my_picks()
+        .into_iter()
+        .find(|p| Some(p.event_id) == event_id)
+        .map(|p| p.winner);
+    let used = my_picks()
+        .into_iter()
+        .filter(|p| Some(p.event_id) != event_id)
+        .map(|p| p.winner)
+        .collect::<Vec<_>>();
+
+    rsx! {
+        div { class: "container mx-auto",
+            h1 { class: "text-2xl font-bold", "{info.name}" }
+            div { class: "text-gray-400 mb-4",
+                match info.kind {
+                    ContestKind::Survivor => "Survivor pool: pick one fighter per event, never the same one twice. A loss knocks you out.",
+                    ContestKind::Confidence => "Confidence pool: give each pick a different confidence from 1 to the number of bouts. Correct picks score their confidence.",
+                }
+            }
+            table { class: "w-full rounded-lg border border-gray-200",
+                thead {
+                    tr { class: "text-gray-400 text-left",
+                        th { class: "p-2", "User" }
+                        th { class: "p-2", "Points" }
+                        th { class: "p-2", "Record" }
+                        if info.kind == ContestKind::Survivor {
+                            th { class: "p-2", "Status" }
+                        }
+                    }
+                }
+                tbody {
+                    for standing in standings {
+                        tr {
+                            td { class: "p-2", "{standing.user}" }
+                            td { class: "p-2 font-bold", "{standing.points}" }
+                            td { class: "p-2", "{standing.correct}-{standing.incorrect}" }
+                            if info.kind == ContestKind::Survivor {
+                                td { class: "p-2",
+                                    if standing.eliminated_at.is_some() {
+                                        span { class: "text-red-400", "Out" }
+                                    } else {
+                                        span { class: "text-green-500", "Alive" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            h2 { class: "text-xl font-bold mt-8 mb-2", "Make picks" }
+            select {
+                class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                onchange: move |e| {
+                    message.set(None);
+                    card.set(Vec::new());
+                    let picked = e.value().parse::<usize>().ok();
+                    event.set(events().into_iter().find(|ev| Some(ev.id) == picked));
+                },
+                option { value: "", "Choose an event" }
+                for ev in events() {
+                    option { value: "{ev.id}", selected: event_id == Some(ev.id), "{ev.name}" }
+                }
+            }
+            if let Some(m) = message() {
+                div { class: "text-gray-400 mt-2", "{m}" }
+            }
+            ul { class: "divide-y divide-gray-200 mt-4",
+                for (i , (f1 , f2)) in card().into_iter().enumerate() {
+                    li { class: "flex items-center gap-4 py-2",
+                        for (side , fighter) in [f1.clone(), f2.clone()].into_iter().enumerate() {
+                            button {
+                                class: format!(
+                                    "flex-1 px-4 py-2 rounded-lg border border-gray-200 disabled:opacity-30 {}",
+                                    if survivor_pick.as_ref() == Some(&fighter)
+                                        || draft().get(&i).is_some_and(|(s, _)| *s == side)
+                                    {
+                                        "bg-green-500 text-white"
+                                    } else {
+                                        "hover:bg-green-100"
+                                    },
+                                ),
+                                disabled: info.kind == ContestKind::Survivor && used.contains(&fighter),
+                                onclick: {
+                                    let fighter = fighter.clone();
+                                    move |_| {
+                                        message.set(None);
+                                        match info.kind {
+                                            ContestKind::Survivor => {
+                                                let fighter = fighter.clone();
+                                                let Some(event_id) = event_id else { return };
+                                                spawn(async move {
+                                                    if let Err(e) = api::make_survivor_pick(id, event_id, fighter).await {
+                                                        message.set(Some(e.to_string()));
+                                                    }
+                                                    refresh();
+                                                });
+                                            }
+                                            ContestKind::Confidence => {
+                                                let confidence = draft().get(&i).map(|(_, c)| c.clone()).unwrap_or_default();
+                                                draft.write().insert(i, (side, confidence));
+                                            }
+                                        }
+                                    }
+                                },
+                                "{fighter}"
+                            }
+                        }
+                        if info.kind == ContestKind::Confidence {
+                            input {
+                                class: "w-20 rounded-lg border border-gray-200 px-2 py-1 text-black",
+                                r#type: "number",
+                                min: "1",
+                                max: "{card().len()}",
+                                placeholder: "Conf.",
+                                value: draft().get(&i).map(|(_, c)| c.clone()).unwrap_or_default(),
+                                oninput: move |e| {
+                                    let side = draft().get(&i).map(|(s, _)| *s).unwrap_or(0);
+                                    draft.write().insert(i, (side, e.value()));
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+            if info.kind == ContestKind::Confidence && !card().is_empty() {
+                button {
+                    class: "mt-4 px-4 py-2 rounded-lg bg-blue-500 text-white font-bold",
+                    onclick: move |_| {
+                        let Some(event_id) = event_id else { return };
+                        let fights = card();
+                        let mut picks = Vec::new();
+                        for (i, (side, confidence)) in draft() {
+                            let Some((f1, f2)) = fights.get(i).cloned() else { continue };
+                            let Ok(confidence) = confidence.trim().parse::<u32>() else {
+                                message.set(Some(format!("Give {f1} vs {f2} a confidence")));
+                                return;
+                            };
+                            let (winner, loser) = if side == 0 { (f1, f2) } else { (f2, f1) };
+                            picks.push((winner, loser, confidence));
+                        }
+                        spawn(async move {
+                            match api::set_confidence_picks(id, event_id, picks).await {
+                                Ok(()) => message.set(Some("Picks saved".to_string())),
+                                Err(e) => message.set(Some(e.to_string())),
+                            }
+                            refresh();
+                        });
+                    },
+                    "Save picks"
+                }
+            }
+        }
+    }
+}
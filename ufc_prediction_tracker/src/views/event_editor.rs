@@ -0,0 +1,365 @@
+use crate::{
+    api,
+    db::{Bout, BoutOutcome, Event, Reconciliation, User, DEFAULT_PROMOTION},
+    Route,
+};
+use dioxus::prelude::*;
+
+/// For admins, creating an event by hand for a card that isn't on ufcstats or in a card file yet.
+#[component]
+pub fn NewEventForm() -> Element {
+    let mut user = use_signal(|| None::<User>);
+    let mut name = use_signal(String::new);
+    let mut date = use_signal(String::new);
+    let mut promotion = use_signal(|| DEFAULT_PROMOTION.to_string());
+    let mut error = use_signal(|| None::<String>);
+
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(u) = api::get_current_user().await {
+                user.set(u);
+            }
+        });
+    });
+    if !user().is_some_and(|u| u.is_admin) {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "rounded-lg border border-gray-200 p-4 my-4",
+            h2 { class: "text-xl font-bold mb-2", "Enter an event by hand" }
+            div { class: "flex flex-col md:flex-row gap-4 items-center",
+                input {
+                    class: "flex-1 rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    placeholder: "Event name",
+                    value: "{name}",
+                    oninput: move |e| name.set(e.value()),
+                }
+                input {
+                    class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    r#type: "date",
+                    value: "{date}",
+                    oninput: move |e| date.set(e.value()),
+                }
+                input {
+                    class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    placeholder: "Promotion",
+                    value: "{promotion}",
+                    oninput: move |e| promotion.set(e.value()),
+                }
+                button {
+                    class: "px-4 py-2 rounded-lg border border-gray-200",
+                    onclick: move |_| {
+                        error.set(None);
+                        spawn(async move {
+                            match api::create_manual_event(name(), date(), promotion()).await {
+                                Ok(event) => {
+                                    navigator()
+                                        .push(Route::Predict {
+                                            id: event.id,
+                                            link: event.link,
+                                        });
+                                }
+                                Err(e) => error.set(Some(e.to_string())),
+                            }
+                        });
+                    },
+                    "Create event"
+                }
+            }
+            if let Some(e) = error() {
+                div { class: "text-red-400 mt-2", "{e}" }
+            }
+        }
+    }
+}
+
+/// For admins, editing the card of an event entered by hand: adding, removing and reordering
+/// bouts, entering results, and merging the event into the scraped one once ufcstats has it.
+#[component]
+pub fn CardEditor(id: usize, on_change: EventHandler<()>) -> Element {
+    let mut user = use_signal(|| None::<User>);
+    let mut bouts = use_signal(Vec::<Bout>::new);
+    let mut fighter1 = use_signal(String::new);
+    let mut fighter2 = use_signal(String::new);
+    let mut weight_class = use_signal(String::new);
+    // how and when, for the next result entered
+    let mut method = use_signal(String::new);
+    let mut round = use_signal(String::new);
+    let mut time = use_signal(String::new);
+    let mut candidates = use_signal(|| None::<Vec<(Event, Reconciliation)>>);
+    let mut error = use_signal(|| None::<String>);
+
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(u) = api::get_current_user().await {
+                user.set(u);
+            }
+            match api::get_bouts(id).await {
+                Ok(b) => bouts.set(b),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    });
+    if !user().is_some_and(|u| u.is_admin) {
+        return rsx! {};
+    }
+
+    // stores the card an edit returns, and has the event page pick it up
+    let mut edited = move |card: Result<Vec<Bout>, ServerFnError>| match card {
+        Ok(card) => {
+            bouts.set(card);
+            on_change.call(());
+        }
+        Err(e) => error.set(Some(e.to_string())),
+    };
+    let mut set_result = move |bout: Bout, outcome: Option<BoutOutcome>, winner: Option<String>| {
+        error.set(None);
+        let result = Bout {
+            outcome,
+            winner,
+            method: Some(method()),
+            round: round().trim().parse().ok(),
+            time: Some(time()),
+            ..bout
+        };
+        spawn(async move {
+            edited(api::set_manual_result(result).await);
+        });
+    };
+    let count = bouts().len();
+
+    rsx! {
+        div { class: "rounded-lg border border-gray-200 p-4 my-4",
+            h2 { class: "text-xl font-bold mb-2", "Card" }
+            div { class: "flex flex-col md:flex-row gap-4 items-center mb-4",
+                input {
+                    class: "flex-1 rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    placeholder: "Fighter",
+                    value: "{fighter1}",
+                    oninput: move |e| fighter1.set(e.value()),
+                }
+                span { class: "text-gray-400", "vs" }
+                input {
+                    class: "flex-1 rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    placeholder: "Fighter",
+                    value: "{fighter2}",
+                    oninput: move |e| fighter2.set(e.value()),
+                }
+                input {
+                    class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    placeholder: "Weight class",
+                    value: "{weight_class}",
+                    oninput: move |e| weight_class.set(e.value()),
+                }
+                button {
+                    class: "px-4 py-2 rounded-lg border border-gray-200",
+                    onclick: move |_| {
+                        error.set(None);
+                        spawn(async move {
+                            let card = api::add_manual_bout(id, fighter1(), fighter2(), weight_class())
+                                .await;
+                            if card.is_ok() {
+                                fighter1.set(String::new());
+                                fighter2.set(String::new());
+                            }
+                            edited(card);
+                        });
+                    },
+                    "Add bout"
+                }
+            }
+            div { class: "flex flex-col md:flex-row gap-4 items-center mb-4",
+                span { class: "text-gray-400 text-sm", "Results are entered with" }
+                input {
+                    class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    placeholder: "Method",
+                    value: "{method}",
+                    oninput: move |e| method.set(e.value()),
+                }
+                input {
+                    class: "w-24 rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    placeholder: "Round",
+                    value: "{round}",
+                    oninput: move |e| round.set(e.value()),
+                }
+                input {
+                    class: "w-24 rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    placeholder: "Time",
+                    value: "{time}",
+                    oninput: move |e| time.set(e.value()),
+                }
+            }
+            ul { class: "divide-y divide-gray-200",
+                for (i , bout) in bouts().into_iter().enumerate() {
+                    li { class: "flex flex-wrap gap-2 justify-between items-center py-2",
+                        span { class: "flex-1",
+                            "{bout.fighter1} vs {bout.fighter2}"
+                            if let Some(w) = bout.weight_class.clone() {
+                                span { class: "text-gray-400 text-sm", " ({w})" }
+                            }
+                        }
+                        span { class: "text-gray-400 text-sm", {result_text(&bout)} }
+                        button {
+                            class: "px-2 py-1 rounded-lg border border-gray-200 disabled:opacity-50",
+                            disabled: i == 0,
+                            onclick: move |_| {
+                                error.set(None);
+                                spawn(async move {
+                                    edited(api::move_manual_bout(id, i, i - 1).await);
+                                });
+                            },
+                            "↑"
+                        }
+                        button {
+                            class: "px-2 py-1 rounded-lg border border-gray-200 disabled:opacity-50",
+                            disabled: i + 1 == count,
+                            onclick: move |_| {
+                                error.set(None);
+                                spawn(async move {
+                                    edited(api::move_manual_bout(id, i, i + 1).await);
+                                });
+                            },
+                            "↓"
+                        }
+                        button {
+                            class: "px-2 py-1 rounded-lg border border-gray-200",
+                            onclick: {
+                                let bout = bout.clone();
+                                move |_| set_result(
+                                    bout.clone(),
+                                    Some(BoutOutcome::Win),
+                                    Some(bout.fighter1.clone()),
+                                )
+                            },
+                            "{bout.fighter1} won"
+                        }
+                        button {
+                            class: "px-2 py-1 rounded-lg border border-gray-200",
+                            onclick: {
+                                let bout = bout.clone();
+                                move |_| set_result(
+                                    bout.clone(),
+                                    Some(BoutOutcome::Win),
+                                    Some(bout.fighter2.clone()),
+                                )
+                            },
+                            "{bout.fighter2} won"
+                        }
+                        button {
+                            class: "px-2 py-1 rounded-lg border border-gray-200",
+                            onclick: {
+                                let bout = bout.clone();
+                                move |_| set_result(bout.clone(), Some(BoutOutcome::Draw), None)
+                            },
+                            "Draw"
+                        }
+                        button {
+                            class: "px-2 py-1 rounded-lg border border-gray-200",
+                            onclick: {
+                                let bout = bout.clone();
+                                move |_| set_result(bout.clone(), Some(BoutOutcome::NoContest), None)
+                            },
+                            "NC"
+                        }
+                        if bout.outcome.is_some() {
+                            button {
+                                class: "px-2 py-1 rounded-lg border border-gray-200",
+                                onclick: {
+                                    let bout = bout.clone();
+                                    move |_| set_result(bout.clone(), None, None)
+                                },
+                                "Clear result"
+                            }
+                        }
+                        button {
+                            class: "px-2 py-1 rounded-lg border border-gray-200 hover:bg-red-500",
+                            onclick: move |_| {
+                                error.set(None);
+                                let (f1, f2) = (bout.fighter1.clone(), bout.fighter2.clone());
+                                spawn(async move {
+                                    edited(api::remove_manual_bout(id, f1, f2).await);
+                                });
+                            },
+                            "Remove"
+                        }
+                    }
+                }
+            }
+            if let Some(e) = error() {
+                div { class: "text-red-400 mt-2", "{e}" }
+            }
+            h3 { class: "text-lg font-bold mt-4 mb-2", "Merge into the scraped event" }
+            button {
+                class: "px-4 py-2 rounded-lg border border-gray-200",
+                onclick: move |_| {
+                    error.set(None);
+                    spawn(async move {
+                        match api::get_reconcile_candidates(id).await {
+                            Ok(c) => candidates.set(Some(c)),
+                            Err(e) => error.set(Some(e.to_string())),
+                        }
+                    });
+                },
+                "Look for it"
+            }
+            match candidates() {
+                None => rsx! {},
+                Some(c) if c.is_empty() => rsx! {
+                    div { class: "text-gray-400 mt-2",
+                        "No event from ufcstats within a day of this one shares a bout with it yet."
+                    }
+                },
+                Some(c) => rsx! {
+                    ul { class: "divide-y divide-gray-200 mt-2",
+                        for (event , reconciliation) in c {
+                            li { class: "flex justify-between items-center py-2",
+                                span {
+                                    "{event.name}, {event.display_date()}: {reconciliation.matched.len()} of {count} bouts match"
+                                    if !reconciliation.unmatched.is_empty() {
+                                        span { class: "text-yellow-400",
+                                            ", {reconciliation.unmatched.len()} aren't on its card and keep their picks as dropped bouts"
+                                        }
+                                    }
+                                }
+                                button {
+                                    class: "px-4 py-2 rounded-lg bg-blue-500 text-white font-bold",
+                                    onclick: move |_| {
+                                        error.set(None);
+                                        spawn(async move {
+                                            match api::reconcile_manual_event(id, event.id).await {
+                                                Ok(_) => {
+                                                    navigator().push(Route::Home {});
+                                                }
+                                                Err(e) => error.set(Some(e.to_string())),
+                                            }
+                                        });
+                                    },
+                                    "Merge"
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+pub(super) fn result_text(bout: &Bout) -> String {
+    let how = [
+        bout.method.clone(),
+        bout.round.map(|r| format!("R{}", r)),
+        bout.time.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ");
+    match (bout.outcome, &bout.winner) {
+        (Some(BoutOutcome::Win), Some(winner)) => format!("{} won {}", winner, how),
+        (Some(BoutOutcome::Draw), _) => format!("Draw {}", how),
+        (Some(BoutOutcome::NoContest), _) => format!("No contest {}", how),
+        _ => String::new(),
+    }
+}
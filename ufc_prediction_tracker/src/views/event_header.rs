@@ -0,0 +1,111 @@
+use crate::{
+    api,
+    db::{Event, User},
+};
+use dioxus::prelude::*;
+
+/// Event name, date and location shown above the bouts on the [`Predict`](super::Predict) and
+/// [`PastEvent`](super::PastEvent) pages.
+#[component]
+pub fn EventHeader(id: usize, title: String) -> Element {
+    let mut event = use_signal(|| None::<Event>);
+    let mut user = use_signal(|| None::<User>);
+    use_effect(move || {
+        spawn(async move {
+            match api::get_event(id).await {
+                Ok(e) => event.set(Some(e)),
+                Err(e) => log::error!("Failed to fetch event: {}", e),
+            }
+            if let Ok(u) = api::get_current_user().await {
+                user.set(u);
+            }
+        });
+    });
+
+    rsx! {
+        div { class: "mb-4",
+            h1 { class: "text-2xl font-bold", "{title}" }
+            if let Some(e) = event() {
+                div { class: "text-xl font-semibold", "{e.name}" }
+                div { class: "text-gray-400",
+                    "{e.display_date()}"
+                    if let Some(place) = e.place() {
+                        " · {place}"
+                    }
+                }
+                if e.prelims_time.is_some() || e.start_time.is_some() {
+                    div { class: "text-gray-400 text-sm",
+                        if let Some(prelims) = e.prelims_time.clone() {
+                            "Prelims {prelims} · "
+                        }
+                        if let Some(main) = e.start_time.clone() {
+                            "Main card {main}"
+                        }
+                        if let Some(tz) = e.timezone.clone() {
+                            " ({tz})"
+                        }
+                    }
+                }
+                if user().is_some_and(|u| u.is_admin) {
+                    EventTimes { event: e, on_change: move |e| event.set(Some(e)) }
+                }
+            }
+        }
+    }
+}
+
+/// Setting when the prelims and main card start and the venue, for admins.
+#[component]
+fn EventTimes(event: Event, on_change: EventHandler<Event>) -> Element {
+    let mut prelims = use_signal(|| event.prelims_time.clone().unwrap_or_default());
+    let mut main = use_signal(|| event.start_time.clone().unwrap_or_default());
+    let mut timezone = use_signal(|| event.timezone.clone().unwrap_or_default());
+    let mut venue = use_signal(|| event.venue.clone().unwrap_or_default());
+    let mut error = use_signal(|| None::<String>);
+
+    rsx! {
+        div { class: "flex flex-col md:flex-row gap-4 items-center mt-2",
+            input {
+                class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                placeholder: "Prelims (HH:MM)",
+                value: "{prelims}",
+                oninput: move |e| prelims.set(e.value()),
+            }
+            input {
+                class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                placeholder: "Main card (HH:MM)",
+                value: "{main}",
+                oninput: move |e| main.set(e.value()),
+            }
+            input {
+                class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                placeholder: "Timezone, e.g. America/New_York",
+                value: "{timezone}",
+                oninput: move |e| timezone.set(e.value()),
+            }
+            input {
+                class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                placeholder: "Venue",
+                value: "{venue}",
+                oninput: move |e| venue.set(e.value()),
+            }
+            button {
+                class: "px-4 py-2 rounded-lg border border-gray-200",
+                onclick: move |_| {
+                    error.set(None);
+                    spawn(async move {
+                        match api::set_event_times(event.id, prelims(), main(), timezone(), venue())
+                            .await {
+                            Ok(e) => on_change.call(e),
+                            Err(e) => error.set(Some(e.to_string())),
+                        }
+                    });
+                },
+                "Save"
+            }
+        }
+        if let Some(e) = error() {
+            div { class: "text-red-400 mt-2", "{e}" }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    api,
+    db::{Bout, BoutOutcome, Event, Fighter, FighterPickStats},
+    Route,
+};
+use dioxus::prelude::*;
+
+/// A fighter's ufcstats profile alongside their recorded bouts and how our picks on them went.
+#[component]
+pub fn FighterProfile(id: String) -> Element {
+    let mut profile = use_signal(|| None::<(Fighter, Vec<(Event, Bout)>, FighterPickStats)>);
+    let mut error = use_signal(|| None::<String>);
+    use_effect(move || {
+        let id = id.clone();
+        spawn(async move {
+            match api::get_fighter_profile(id).await {
+                Ok(p) => profile.set(Some(p)),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    });
+
+    let Some((fighter, bouts, picks)) = profile() else {
+        return rsx! {
+            div { class: "container mx-auto text-gray-400",
+                {error().unwrap_or_else(|| "Loading…".to_string())}
+            }
+        };
+    };
+    let stat = |value: Option<f64>, suffix: &str| {
+        value
+            .map(|v| format!("{v}{suffix}"))
+            .unwrap_or_else(|| "--".to_string())
+    };
+    let text = |value: Option<String>| value.unwrap_or_else(|| "--".to_string());
+    let attributes = [
+        ("Record", text(fighter.record.clone())),
+        ("Height", text(fighter.height.clone())),
+        ("Reach", stat(fighter.reach_inches, "\"")),
+        ("Stance", text(fighter.stance.clone())),
+        (
+            "Age",
+            fighter
+                .age()
+                .map(|a| a.to_string())
+                .unwrap_or_else(|| "--".to_string()),
+        ),
+        ("Recent form", text(fighter.recent_form.clone())),
+    ];
+    let career = [
+        ("Strikes landed / min", stat(fighter.slpm, "")),
+        ("Striking accuracy", stat(fighter.str_acc, "%")),
+        ("Strikes absorbed / min", stat(fighter.sapm, "")),
+        ("Striking defence", stat(fighter.str_def, "%")),
+        ("Takedowns / 15 min", stat(fighter.td_avg, "")),
+        ("Takedown accuracy", stat(fighter.td_acc, "%")),
+        ("Takedown defence", stat(fighter.td_def, "%")),
+        ("Submissions / 15 min", stat(fighter.sub_avg, "")),
+    ];
+
+    rsx! {
+        div { class: "container mx-auto",
+            h1 { class: "text-2xl font-bold", "{fighter.name}" }
+            if let Some(nickname) = fighter.nickname.clone() {
+                div { class: "text-gray-400 italic", "\"{nickname}\"" }
+            }
+            div { class: "grid grid-cols-2 md:grid-cols-6 gap-4 mt-4",
+                for (label, value) in attributes {
+                    div { class: "rounded-lg border border-gray-200 p-4",
+                        div { class: "text-sm text-gray-400", "{label}" }
+                        div { class: "text-xl font-bold", "{value}" }
+                    }
+                }
+            }
+            h2 { class: "text-xl font-bold mt-6", "Career statistics" }
+            div { class: "grid grid-cols-2 md:grid-cols-4 gap-4 mt-2",
+                for (label, value) in career {
+                    div { class: "rounded-lg border border-gray-200 p-4",
+                        div { class: "text-sm text-gray-400", "{label}" }
+                        div { class: "text-xl font-bold", "{value}" }
+                    }
+                }
+            }
+            h2 { class: "text-xl font-bold mt-6", "Our picks" }
+            div { class: "text-gray-400 mt-2",
+                "Picked to win {picks.picked_for} times, picked against {picks.picked_against} times. "
+                "Right about them {picks.correct} of {picks.correct + picks.incorrect} decided picks."
+            }
+            h2 { class: "text-xl font-bold mt-6", "Recorded bouts" }
+            if bouts.is_empty() {
+                div { class: "text-gray-400 mt-2", "No bouts recorded yet. Import past events to fill this in." }
+            }
+            ul { class: "divide-y rounded-lg border border-gray-200 shadow-md mt-2",
+                for (event, bout) in bouts {
+                    li { class: "p-4 flex justify-between items-center gap-4",
+                        span { class: "font-semibold",
+                            {
+                                let opponent = if bout.fighter1 == fighter.name {
+                                    bout.fighter2.clone()
+                                } else {
+                                    bout.fighter1.clone()
+                                };
+                                format!("vs. {opponent}")
+                            }
+                        }
+                        span { class: "text-gray-400", "{event.name} · {event.display_date()}" }
+                        span { class: "font-bold",
+                            {
+                                match (bout.outcome, bout.winner.as_deref()) {
+                                    (Some(BoutOutcome::Win), Some(w)) if w == fighter.name => "W".to_string(),
+                                    (Some(BoutOutcome::Win), _) => "L".to_string(),
+                                    (Some(BoutOutcome::Draw), _) => "D".to_string(),
+                                    (Some(BoutOutcome::NoContest), _) => "NC".to_string(),
+                                    (None, _) => "Upcoming".to_string(),
+                                }
+                            }
+                            if let Some(method) = bout.method.clone() {
+                                span { class: "text-gray-400 font-normal ml-2", "{method}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A small link to a fighter's profile page, shown under their name on a card.
+#[component]
+pub fn FighterProfileLink(id: Option<String>) -> Element {
+    rsx! {
+        if let Some(id) = id {
+            Link {
+                class: "text-xs text-gray-400 hover:text-blue-400 mt-1",
+                to: Route::FighterProfile { id },
+                "Profile"
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{api, db::FighterAlias};
+use dioxus::prelude::*;
+
+/// Merging spellings of a fighter's name, so results and picks under either are scored together:
+/// the pairs that look alike, a form for any other pair, and the spellings merged so far.
+#[component]
+pub fn FighterNames() -> Element {
+    let mut suggestions = use_signal(Vec::<(String, String)>::new);
+    let mut aliases = use_signal(Vec::<FighterAlias>::new);
+    let mut from = use_signal(String::new);
+    let mut to = use_signal(String::new);
+    let mut merged = use_signal(|| None::<usize>);
+    let mut error = use_signal(|| None::<String>);
+
+    let refresh = move || {
+        spawn(async move {
+            match api::suggest_fighter_merges().await {
+                Ok(s) => suggestions.set(s),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+            if let Ok(a) = api::get_fighter_aliases().await {
+                aliases.set(a);
+            }
+        });
+    };
+    use_effect(move || {
+        refresh();
+    });
+    let mut merge = move |from: String, to: String| {
+        error.set(None);
+        spawn(async move {
+            match api::merge_fighter_names(from, to).await {
+                Ok(rewritten) => merged.set(Some(rewritten)),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+            refresh();
+        });
+    };
+
+    rsx! {
+        h2 { class: "text-xl font-bold mt-8 mb-2", "Fighter names" }
+        p { class: "text-sm text-gray-400 mb-2",
+            "Merging a spelling rewrites every bout, result and pick under it, and later cards that use it are stored under the other."
+        }
+        div { class: "flex flex-col md:flex-row gap-4 items-center",
+            input {
+                class: "flex-1 rounded-lg border border-gray-200 px-4 py-2 text-black",
+                placeholder: "Spelling to merge",
+                value: "{from}",
+                oninput: move |e| from.set(e.value()),
+            }
+            span { class: "text-gray-400", "into" }
+            input {
+                class: "flex-1 rounded-lg border border-gray-200 px-4 py-2 text-black",
+                placeholder: "Spelling to keep",
+                value: "{to}",
+                oninput: move |e| to.set(e.value()),
+            }
+            button {
+                class: "px-4 py-2 rounded-lg border border-gray-200",
+                onclick: move |_| {
+                    merge(from(), to());
+                    from.set(String::new());
+                    to.set(String::new());
+                },
+                "Merge"
+            }
+        }
+        if let Some(e) = error() {
+            div { class: "text-red-400 mt-2", "{e}" }
+        }
+        if let Some(rewritten) = merged() {
+            div { class: "text-gray-400 mt-2", "{rewritten} rows rewritten" }
+        }
+        if !suggestions().is_empty() {
+            ul { class: "divide-y divide-gray-200 mt-4",
+                for (a , b) in suggestions() {
+                    li { class: "flex gap-4 justify-between items-center py-2",
+                        span { class: "flex-1", "{a} / {b}" }
+                        button {
+                            class: "px-4 py-2 rounded-lg border border-gray-200",
+                            onclick: {
+                                let (a, b) = (a.clone(), b.clone());
+                                move |_| merge(b.clone(), a.clone())
+                            },
+                            "Keep {a}"
+                        }
+                        button {
+                            class: "px-4 py-2 rounded-lg border border-gray-200",
+                            onclick: {
+                                let (a, b) = (a.clone(), b.clone());
+                                move |_| merge(a.clone(), b.clone())
+                            },
+                            "Keep {b}"
+                        }
+                    }
+                }
+            }
+        }
+        ul { class: "text-sm text-gray-400 mt-2",
+            for alias in aliases() {
+                li { "{alias.alias} → {alias.name} ({alias.created_at})" }
+            }
+        }
+    }
+}
@@ -1,51 +1,93 @@
-use crate::{api, Route};
+use super::{NewEventForm, ParserHealthBanner};
+use crate::{
+    api::{self, ical},
+    db::Event,
+    Route,
+};
 use dioxus::prelude::*;
-use log;
 
 #[component]
 pub fn Home() -> Element {
-    let mut event_list = use_signal(|| Vec::<(String, String, usize)>::new());
+    let mut event_list = use_signal(Vec::<Event>::new);
+    let mut country = use_signal(|| None::<String>);
+    let mut promotion = use_signal(|| None::<String>);
 
     // Use an effect to fetch and update events on mount
     use_effect(move || {
         spawn({
             async move {
                 match api::get_upcoming_events().await {
-                    Ok(events) => {
-                        for event in &events {
-                            println!("Upcoming Event: {}-{}-{}", event.0, event.1, event.2);
-                        }
-                        event_list.write().extend(events.into_iter())
-                    }
+                    Ok(events) => event_list.write().extend(events),
                     Err(e) => println!("Failed to add upcoming events: {}", e),
                 }
             }
         });
     });
+    let mut countries = event_list()
+        .iter()
+        .filter_map(|event| event.country.clone())
+        .collect::<Vec<_>>();
+    countries.sort();
+    countries.dedup();
+    let mut promotions = event_list()
+        .iter()
+        .map(|event| event.promotion.clone())
+        .collect::<Vec<_>>();
+    promotions.sort();
+    promotions.dedup();
     rsx! {
         div { class: "container mx-auto",
-            h1 { class: "text-2xl font-bold", "Events" }
-            div { class: "my-4" }
+            ParserHealthBanner {}
+            div { class: "flex justify-between items-center",
+                h1 { class: "text-2xl font-bold", "Events" }
+                a {
+                    class: "ml-auto mr-4 px-4 py-2 rounded-lg border border-gray-200",
+                    href: "/api/v1/calendar.ics",
+                    download: ical::FILE_NAME,
+                    "Add to calendar"
+                }
+                if promotions.len() > 1 {
+                    select {
+                        class: "rounded-lg border border-gray-200 px-4 py-2 mr-4 text-black",
+                        onchange: move |e| promotion.set(Some(e.value()).filter(|p| !p.is_empty())),
+                        option { value: "", "All promotions" }
+                        for p in promotions {
+                            option { value: "{p}", selected: promotion() == Some(p.clone()), "{p}" }
+                        }
+                    }
+                }
+                select {
+                    class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    onchange: move |e| country.set(Some(e.value()).filter(|c| !c.is_empty())),
+                    option { value: "", "All countries" }
+                    for c in countries {
+                        option { value: "{c}", selected: country() == Some(c.clone()), "{c}" }
+                    }
+                }
+            }
+            NewEventForm {}
             ul { class: "divide-y divide-gray-200 rounded-lg border border-gray-200 shadow-md mt-4",
                 {
                     event_list()
-                        .iter()
+                        .into_iter()
+                        .filter(|event| country().is_none() || event.country == country())
+                        .filter(|event| {
+                            promotion().is_none_or(|p| event.promotion == p)
+                        })
                         .map(|event| {
-                            let mut title_date = event
-                                .0
-                                .trim_matches(|char| char == '|')
-                                .split("||");
-                            let title = title_date.next().expect("");
-                            let date = title_date.next().expect("");
+                            let date = event.display_date();
+                            let place = event.place().unwrap_or_default();
                             rsx! {
                                 Link {
                                     to: Route::Predict {
-                                        id: event.2,
-                                        link: event.1.clone(),
+                                        id: event.id,
+                                        link: event.link.clone(),
                                     },
                                     class: "block",
                                     li { class: "p-4 text-black-100 hover:bg-blue-500 cursor-pointer transition-colors w-full flex justify-between items-center gap-4",
-                                        div { class: "flex-1 rounded-l-lg px-4 py-2 font-bold", "{title}" }
+                                        div { class: "rounded-l-lg px-4 py-2 text-gray-400 text-sm", "{event.promotion}" }
+                                        div { class: "flex-1 px-4 py-2 font-bold", "{event.name}" }
+                                        div { class: "px-4 py-2 text-gray-400 text-sm", "{place}" }
                                         div { class: "rounded-r-lg px-4 py-2 text-gray-600 text-sm", "{date}" }
                                     }
                                 }
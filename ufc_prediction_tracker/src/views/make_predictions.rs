@@ -1,21 +1,47 @@
-use crate::api;
+use super::{
+    CardEditor, EventHeader, FighterProfileLink, PickCardLinks, TaleOfTheTape, TicketBuilder,
+};
+use crate::{api, db, model::ModelPick};
 use dioxus::prelude::*;
+use std::collections::{HashMap, HashSet};
 
 #[component]
 pub fn Predict(id: usize, link: String) -> Element {
     let mut fights = use_signal(|| Vec::<(String, String)>::new());
     let mut selected = use_signal(|| Vec::<Option<usize>>::new());
+    let mut fighter_ids = use_signal(HashMap::<String, String>::new);
+    // bouts whose tale of the tape is expanded
+    let mut expanded = use_signal(HashSet::<usize>::new);
+    let mut model_picks = use_signal(Vec::<ModelPick>::new);
+    let mut favourites = use_signal(Vec::<ModelPick>::new);
+    // the picks as last saved, to tell what's changed since
+    let mut saved = use_signal(Vec::<Option<usize>>::new);
+    // the bout keyboard picks apply to
+    let mut focused = use_signal(|| 0usize);
+    let mut error = use_signal(|| None::<String>);
+    // bumped when the card is edited, to load it again
+    let mut revision = use_signal(|| 0usize);
+    let event_id = id;
     let id = use_signal(|| id);
-    // println!("{link} link");
-    // println!("{id} id");
+    let is_manual = link == db::MANUAL_LINK;
     use_effect(move || {
-        let link = link.clone();
-
+        revision();
         spawn(async move {
-            match api::get_fights(link).await {
+            match api::get_fights(event_id).await {
                 Ok(fights_vec) => {
-                    selected.write().resize(fights_vec.len(), None);
-                    fights.write().extend(fights_vec.clone());
+                    if let Ok(ids) = api::get_fighter_ids(event_id).await {
+                        fighter_ids.set(ids);
+                    }
+                    match api::get_model_picks(event_id).await {
+                        Ok(picks) => model_picks.set(picks),
+                        Err(e) => log::error!("Failed to fetch model picks: {}", e),
+                    }
+                    match api::get_favourites(event_id).await {
+                        Ok(picks) => favourites.set(picks),
+                        Err(e) => log::error!("Failed to fetch favourites: {}", e),
+                    }
+                    selected.set(vec![None; fights_vec.len()]);
+                    fights.set(fights_vec.clone());
                     spawn(async move {
                         let fights_vec_clone = fights_vec.clone();
                         match api::get_predictions(*id.read()).await {
@@ -28,6 +54,7 @@ pub fn Predict(id: usize, link: String) -> Element {
                                         sel[i] = if pred.0 == *f1 { Some(0) } else { Some(1) };
                                     }
                                 }
+                                saved.set(sel.clone());
                             }
                             Err(e) => log::error!("Failed to fetch predictions: {}", e),
                         }
@@ -37,10 +64,148 @@ pub fn Predict(id: usize, link: String) -> Element {
             }
         });
     });
+    let picks = fights()
+        .into_iter()
+        .zip(selected())
+        .filter_map(|((f1, f2), sel)| match sel? {
+            0 => Some((f1, f2)),
+            _ => Some((f2, f1)),
+        })
+        .collect::<Vec<_>>();
+    let unsaved = selected()
+        .iter()
+        .zip(saved().iter().chain(std::iter::repeat(&None)))
+        .filter(|(now, before)| now != before)
+        .count();
+
+    // `None` un-picks the bout
+    let mut pick = move |i: usize, side: Option<usize>| {
+        if let Some(sel) = selected.write().get_mut(i) {
+            *sel = side;
+        }
+        focused.set(i);
+    };
+    // fills in every bout the helper has an opinion on, leaving the rest as they are
+    let mut pick_all = move |sides: Vec<Option<usize>>| {
+        for (sel, side) in selected.write().iter_mut().zip(sides) {
+            if side.is_some() {
+                *sel = side;
+            }
+        }
+    };
+    let mut save = move || {
+        error.set(None);
+        let sel = selected();
+        let picks = fights()
+            .into_iter()
+            .zip(sel.clone())
+            .filter_map(|((f1, f2), side)| match side? {
+                0 => Some((f1, f2)),
+                _ => Some((f2, f1)),
+            })
+            .collect::<Vec<_>>();
+        // bouts picked when last saved and un-picked since
+        let removed = fights()
+            .into_iter()
+            .zip(sel.iter().zip(saved()))
+            .filter(|(_, (now, before))| now.is_none() && before.is_some())
+            .map(|(fight, _)| fight)
+            .collect::<Vec<_>>();
+        spawn(async move {
+            match api::predict_many(event_id, picks, removed).await {
+                Ok(_) => saved.set(sel),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    };
+    let clear = move |_| {
+        error.set(None);
+        spawn(async move {
+            match api::clear_predictions(event_id).await {
+                Ok(_) => {
+                    let none = vec![None; fights().len()];
+                    selected.set(none.clone());
+                    saved.set(none);
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    };
+    let onkeydown = move |e: KeyboardEvent| {
+        let count = fights().len();
+        if count == 0 {
+            return;
+        }
+        let i = focused().min(count - 1);
+        match e.key() {
+            Key::ArrowDown => focused.set((i + 1).min(count - 1)),
+            Key::ArrowUp => focused.set(i.saturating_sub(1)),
+            Key::ArrowLeft => pick(i, Some(0)),
+            Key::ArrowRight => pick(i, Some(1)),
+            Key::Character(c) if c == "1" => pick(i, Some(0)),
+            Key::Character(c) if c == "2" => pick(i, Some(1)),
+            Key::Character(c) if c == "0" => pick(i, None),
+            Key::Backspace | Key::Delete => pick(i, None),
+            Key::Enter => save(),
+            _ => return,
+        }
+        e.prevent_default();
+    };
+
     rsx! {
         div { class: "container mx-auto",
-            h1 { class: "text-2xl font-bold mb-4", "Predict Fights" }
-            ul { class: "divide-y divide-gray-200 rounded-lg border border-gray-200 shadow-md mt-4",
+            EventHeader { id: event_id, title: "Predict Fights" }
+            PickCardLinks { id: event_id, label: "Share your pick sheet" }
+            if is_manual {
+                CardEditor { id: event_id, on_change: move |_| revision += 1 }
+            }
+            div { class: "flex flex-wrap gap-4 items-center",
+                button {
+                    class: "px-4 py-2 rounded-lg border border-gray-200",
+                    onclick: move |_| pick_all(sides_of(&fights(), &favourites())),
+                    "Pick all favourites"
+                }
+                button {
+                    class: "px-4 py-2 rounded-lg border border-gray-200",
+                    onclick: move |_| pick_all(sides_of(&fights(), &model_picks())),
+                    "Pick all model picks"
+                }
+                button {
+                    class: "px-4 py-2 rounded-lg border border-gray-200",
+                    onclick: move |_| {
+                        let seed = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64;
+                        pick_all(random_sides(fights().len(), seed).into_iter().map(Some).collect());
+                    },
+                    "Randomize"
+                }
+                div { class: "flex-1" }
+                if unsaved > 0 {
+                    span { class: "text-yellow-400", "{unsaved} unsaved" }
+                } else {
+                    span { class: "text-gray-400", "All picks saved" }
+                }
+                button {
+                    class: "px-4 py-2 rounded-lg bg-blue-500 text-white font-bold disabled:opacity-50",
+                    disabled: unsaved == 0,
+                    onclick: move |_| save(),
+                    "Save picks"
+                }
+                button {
+                    class: "px-4 py-2 rounded-lg border border-gray-200 hover:bg-red-500",
+                    onclick: clear,
+                    "Clear picks"
+                }
+            }
+            div { class: "text-gray-400 text-sm mt-2",
+                "Click the card, then use ↑/↓ to move between bouts, ←/→ or 1/2 to pick, Backspace or 0 to un-pick, and Enter to save. Clicking a picked fighter again un-picks them."
+            }
+            if let Some(e) = error() {
+                div { class: "text-red-400 mt-2", "{e}" }
+            }
+            ul {
+                class: "divide-y divide-gray-200 rounded-lg border border-gray-200 shadow-md mt-4 focus:outline-none",
+                tabindex: 0,
+                onkeydown,
                 {
                     fights()
                         .iter()
@@ -49,60 +214,148 @@ pub fn Predict(id: usize, link: String) -> Element {
                             let sel = selected().get(i).cloned().unwrap_or(None);
                             let f1_btn = f1.clone();
                             let f2_btn = f2.clone();
-                            let f1_btn2 = f1.clone();
-                            let f2_btn2 = f2.clone();
+                            let ids = (fighter_ids().get(f1).cloned(), fighter_ids().get(f2).cloned());
+                            let is_expanded = expanded().contains(&i);
+                            let ring = if focused() == i { "ring-2 ring-blue-400" } else { "" };
+                            let model_pick = model_picks()
+                                .iter()
+                                .find(|p| {
+                                    (p.fighter1 == *f1 && p.fighter2 == *f2)
+                                        || (p.fighter1 == *f2 && p.fighter2 == *f1)
+                                })
+                                .and_then(|p| {
+                                    p.favourite().map(|(w, _)| (w.to_string(), p.confidence() * 100.0))
+                                });
                             rsx! {
-                                li { class: "p-6 grid grid-cols-3 items-center gap-4 justify-items-center hover:bg-gray-50 transition-all duration-200 w-full border border-gray-100 shadow-sm rounded-lg my-4", // add justify-items-center
-                                    button {
-                                        class: format!(
-                                            "{} border border-gray-200 px-6 py-3 rounded-lg text-xl font-bold shadow-md transition-colors duration-200 w-full min-w-0 min-h-[56px] flex items-center justify-center ",
-                                            if sel == Some(0) {
-                                                "bg-green-500 text-white scale-105"
-                                            } else {
-                                                "bg-gray-200 text-gray-700 hover:bg-green-100"
-                                            },
-                                        ),
-                                        style: "width: 100%; max-width:40vw",
-                                        onclick: move |_| {
-                                            selected.write()[i] = Some(0);
-                                            let winner = f1_btn.clone();
-                                            let loser = f2_btn.clone();
-                                            let event_id = id;
-                                            spawn(async move {
-                                                let _ = api::predict(*event_id.read(), winner, loser).await;
-                                            });
-                                        },
-                                        "{f1_btn}"
+                                li { class: "p-6 grid grid-cols-3 items-center gap-4 justify-items-center hover:bg-gray-50 transition-all duration-200 w-full border border-gray-100 shadow-sm rounded-lg my-4 {ring}", // add justify-items-center
+                                    div { class: "flex flex-col items-center w-full",
+                                        button {
+                                            class: format!(
+                                                "{} border border-gray-200 px-6 py-3 rounded-lg text-xl font-bold shadow-md transition-colors duration-200 w-full min-w-0 min-h-[56px] flex items-center justify-center ",
+                                                if sel == Some(0) {
+                                                    "bg-green-500 text-white scale-105"
+                                                } else {
+                                                    "bg-gray-200 text-gray-700 hover:bg-green-100"
+                                                },
+                                            ),
+                                            style: "width: 100%; max-width:40vw",
+                                            onclick: move |_| pick(i, if sel == Some(0) { None } else { Some(0) }),
+                                            "{f1_btn}"
+                                        }
+                                        FighterProfileLink { id: fighter_ids().get(f1).cloned() }
                                     }
-                                    span { class: "mx-6 text-3xl font-extrabold text-gray-400 text-center w-20vw flex items-center justify-center",
+                                    span { class: "mx-6 text-3xl font-extrabold text-gray-400 text-center w-20vw flex flex-col items-center justify-center",
                                         "vs"
+                                        if let Some((favourite, confidence)) = model_pick.clone() {
+                                            span { class: "block text-xs font-normal text-blue-400 mt-1",
+                                                "Model: {favourite} {confidence:.0}%"
+                                            }
+                                        }
                                     }
-                                    button {
-                                        class: format!(
-                                            "border border-gray-200 px-6 py-3 rounded-lg text-xl font-bold shadow-md transition-colors duration-200 w-full min-w-0 min-h-[56px] flex items-center justify-center {}",
-                                            if sel == Some(1) {
-                                                "bg-green-500 text-white scale-105"
-                                            } else {
-                                                "bg-gray-200 text-gray-700 hover:bg-green-100"
-                                            },
-                                        ),
-                                        style: "width: 100%; min-width: 0; max-width: 40vw; ",
-                                        onclick: move |_| {
-                                            selected.write()[i] = Some(1);
-                                            let loser = f1_btn2.clone();
-                                            let winner = f2_btn2.clone();
-                                            let event_id = id;
-                                            spawn(async move {
-                                                let _ = api::predict(*event_id.read(), winner, loser).await;
-                                            });
-                                        },
-                                        "{f2_btn2}"
+                                    div { class: "flex flex-col items-center w-full",
+                                        button {
+                                            class: format!(
+                                                "border border-gray-200 px-6 py-3 rounded-lg text-xl font-bold shadow-md transition-colors duration-200 w-full min-w-0 min-h-[56px] flex items-center justify-center {}",
+                                                if sel == Some(1) {
+                                                    "bg-green-500 text-white scale-105"
+                                                } else {
+                                                    "bg-gray-200 text-gray-700 hover:bg-green-100"
+                                                },
+                                            ),
+                                            style: "width: 100%; min-width: 0; max-width: 40vw; ",
+                                            onclick: move |_| pick(i, if sel == Some(1) { None } else { Some(1) }),
+                                            "{f2_btn}"
+                                        }
+                                        FighterProfileLink { id: fighter_ids().get(f2).cloned() }
+                                    }
+                                    if let (Some(fighter1_id), Some(fighter2_id)) = ids {
+                                        div { class: "col-span-3 w-full flex flex-col items-center",
+                                            button {
+                                                class: "text-sm text-gray-400 hover:text-blue-400",
+                                                onclick: move |_| {
+                                                    if !expanded.write().remove(&i) {
+                                                        expanded.write().insert(i);
+                                                    }
+                                                },
+                                                if is_expanded {
+                                                    "Hide tale of the tape"
+                                                } else {
+                                                    "Tale of the tape"
+                                                }
+                                            }
+                                            if is_expanded {
+                                                TaleOfTheTape { fighter1_id, fighter2_id }
+                                            }
+                                        }
                                     }
                                 }
                             }
                         })
                 }
             }
+            TicketBuilder { event_id, picks }
         }
     }
 }
+
+/// Which side of each fight the helper's picks favour: 0 for the first fighter, 1 for the second,
+/// `None` where it has no pick.
+fn sides_of(fights: &[(String, String)], picks: &[ModelPick]) -> Vec<Option<usize>> {
+    fights
+        .iter()
+        .map(|(f1, f2)| {
+            let (winner, _) = picks
+                .iter()
+                .find(|p| {
+                    (p.fighter1 == *f1 && p.fighter2 == *f2)
+                        || (p.fighter1 == *f2 && p.fighter2 == *f1)
+                })?
+                .favourite()?;
+            Some(if winner == f1 { 0 } else { 1 })
+        })
+        .collect()
+}
+
+/// A coin flip per fight from a small xorshift generator, so randomizing doesn't need an RNG that
+/// works in every renderer.
+fn random_sides(count: usize, seed: u64) -> Vec<usize> {
+    let mut state = seed | 1;
+    (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 32) as usize % 2
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn helper_picks_map_onto_the_card_either_way_round() {
+        let fights = vec![
+            ("A".to_string(), "B".to_string()),
+            ("C".to_string(), "D".to_string()),
+            ("E".to_string(), "F".to_string()),
+        ];
+        let pick = |fighter1: &str, fighter2: &str, p: f64| ModelPick {
+            fighter1: fighter1.to_string(),
+            fighter2: fighter2.to_string(),
+            fighter1_win_probability: p,
+        };
+        let picks = [
+            pick("A", "B", 0.7),
+            pick("D", "C", 0.8),
+            pick("E", "F", 0.5),
+        ];
+        assert_eq!(sides_of(&fights, &picks), vec![Some(0), Some(1), None]);
+
+        let sides = random_sides(14, 42);
+        assert_eq!(sides.len(), 14);
+        assert!(sides.iter().all(|side| *side < 2));
+        assert_eq!(sides, random_sides(14, 42));
+    }
+}
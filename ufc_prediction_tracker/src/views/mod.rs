@@ -14,9 +14,52 @@ pub use home::Home;
 mod navbar;
 pub use navbar::Navbar;
 
+mod event_header;
+use event_header::EventHeader;
+
+mod fighter;
+pub use fighter::FighterProfile;
+use fighter::FighterProfileLink;
+
+mod tale_of_the_tape;
+use tale_of_the_tape::TaleOfTheTape;
+
+mod tickets;
+use tickets::{TicketBuilder, TicketList};
+
+mod parser_health;
+use parser_health::ParserHealthBanner;
+
+mod pick_card_links;
+use pick_card_links::PickCardLinks;
+
+mod result_corrections;
+use result_corrections::ResultCorrections;
+
+mod event_editor;
+use event_editor::{CardEditor, NewEventForm};
+
 mod make_predictions;
 pub use make_predictions::Predict;
 
 mod past_predictions;
 pub use past_predictions::PastEvent;
 pub use past_predictions::PastPredictions;
+
+mod fighter_names;
+use fighter_names::FighterNames;
+
+mod backfill;
+pub use backfill::Backfill;
+
+mod models;
+pub use models::Models;
+
+mod contests;
+pub use contests::{ContestPage, Contests};
+
+mod account;
+pub use account::Account;
+
+mod stats;
+pub use stats::Stats;
@@ -0,0 +1,99 @@
+use crate::{
+    api,
+    db::{BacktestReport, Score},
+};
+use dioxus::prelude::*;
+
+/// Backtest results for every prediction model, next to how we did on the same bouts.
+#[component]
+pub fn Models() -> Element {
+    let mut reports = use_signal(Vec::<(BacktestReport, Score, Score)>::new);
+    let mut running = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    let refresh = move || {
+        spawn(async move {
+            match api::get_backtests().await {
+                Ok(r) => reports.set(r),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    };
+    use_effect(refresh);
+
+    rsx! {
+        div { class: "container mx-auto",
+            div { class: "flex justify-between items-center mb-4",
+                h1 { class: "text-2xl font-bold", "Models" }
+                button {
+                    class: "px-4 py-2 rounded-lg bg-blue-500 text-white font-bold disabled:opacity-50",
+                    disabled: running(),
+                    onclick: move |_| {
+                        error.set(None);
+                        running.set(true);
+                        spawn(async move {
+                            if let Err(e) = api::run_backtests().await {
+                                error.set(Some(e.to_string()));
+                            }
+                            running.set(false);
+                            refresh();
+                        });
+                    },
+                    if running() {
+                        "Running…"
+                    } else {
+                        "Run backtest"
+                    }
+                }
+            }
+            if let Some(e) = error() {
+                div { class: "text-red-400 mb-4", "{e}" }
+            }
+            if reports().is_empty() {
+                div { class: "text-gray-400",
+                    "No backtests yet. Import past events, then run a backtest to replay every model over them."
+                }
+            }
+            for (report, model, ours) in reports() {
+                div { class: "rounded-lg border border-gray-200 p-4 mb-4",
+                    div { class: "flex justify-between items-center",
+                        h2 { class: "text-xl font-bold", "{report.model}" }
+                        if let Some(run_at) = report.run_at.clone() {
+                            span { class: "text-sm text-gray-400", "Run {run_at}" }
+                        }
+                    }
+                    div { class: "flex flex-wrap gap-8 mt-2",
+                        div { "{report.bouts} bouts" }
+                        div { "Accuracy {report.accuracy * 100.0:.1}%" }
+                        div { "Log loss {report.log_loss:.3}" }
+                    }
+                    if model.decided() > 0 {
+                        div { class: "mt-2 text-gray-400",
+                            "On the {model.decided()} decided bouts we both picked: model {model.correct}, you {ours.correct}"
+                        }
+                    }
+                    table { class: "mt-4 text-sm w-full",
+                        thead {
+                            tr { class: "text-gray-400 text-left",
+                                th { "Confidence" }
+                                th { "Bouts" }
+                                th { "Predicted" }
+                                th { "Favourite won" }
+                            }
+                        }
+                        tbody {
+                            for bucket in report.calibration.iter().filter(|b| b.bouts > 0) {
+                                tr {
+                                    td { "{bucket.lower * 100.0:.0}–{bucket.upper * 100.0:.0}%" }
+                                    td { "{bucket.bouts}" }
+                                    td { "{bucket.mean_predicted * 100.0:.1}%" }
+                                    td { "{bucket.observed * 100.0:.1}%" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -16,6 +16,11 @@ pub fn Navbar() -> Element {
         div { id: "navbar",
             Link { to: Route::Home {}, "Home" }
             Link { to: Route::PastPredictions {}, "Past Predictions" }
+            Link { to: Route::Stats {}, "Stats" }
+            Link { to: Route::Backfill {}, "Import" }
+            Link { to: Route::Models {}, "Models" }
+            Link { to: Route::Contests {}, "Contests" }
+            Link { to: Route::Account {}, "Account" }
         }
 
         // The `Outlet` component is used to render the next component inside the layout. In this case, it will render either
@@ -0,0 +1,50 @@
+use crate::{api, db::ParserHealth, parser::PageKind};
+use dioxus::prelude::*;
+
+/// A warning for each kind of ufcstats page that stopped parsing cleanly, with what the parser
+/// found wrong. Nothing is shown while every page parses.
+#[component]
+pub fn ParserHealthBanner() -> Element {
+    let mut unhealthy = use_signal(Vec::<ParserHealth>::new);
+
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(health) = api::get_parser_health().await {
+                unhealthy.set(health.into_iter().filter(|h| !h.healthy).collect());
+            }
+        });
+    });
+
+    if unhealthy().is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "rounded-lg border border-yellow-500 p-4 my-4",
+            h2 { class: "font-bold text-yellow-500",
+                "ufcstats pages have changed, so some events, bouts or fighters may be missing"
+            }
+            for h in unhealthy() {
+                div { class: "mt-2 text-sm",
+                    {
+                        let label = PageKind::parse(&h.page).map(|k| k.label()).unwrap_or("Page");
+                        let since = h
+                            .last_healthy_at
+                            .as_deref()
+                            .map(|at| format!(", last parsed cleanly {}", at))
+                            .unwrap_or_default();
+                        rsx! {
+                            a { class: "font-bold", href: "{h.url}", "{label}" }
+                            span { class: "text-gray-400", " checked {h.checked_at}{since}" }
+                        }
+                    }
+                    ul { class: "list-disc ml-6 text-gray-400",
+                        for d in h.diagnostics.iter() {
+                            li { "{d}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,11 @@
 use std::collections::HashMap;
 
-use crate::{api, Route};
+use super::{EventHeader, FighterProfileLink, PickCardLinks, ResultCorrections, TicketList};
+use crate::{
+    api,
+    db::{PastEventQuery, Score, DEFAULT_PAGE_SIZE},
+    Route,
+};
 use dioxus::prelude::*;
 
 #[component]
@@ -8,16 +13,44 @@ pub fn PastPredictions() -> Element {
     let mut events = use_signal(|| Vec::<(usize, String, String, String)>::new()); // (id, name, date)
     let mut correct = use_signal(|| 0 as i64);
     let mut incorrect = use_signal(|| 0 as i64);
+    let mut scores = use_signal(HashMap::<usize, Score>::new);
+    let mut user_scores = use_signal(Vec::<(String, Score)>::new);
+    let mut search = use_signal(String::new);
+    let mut year = use_signal(|| None::<i32>);
+    let mut years = use_signal(Vec::<i32>::new);
+    let mut country = use_signal(|| None::<String>);
+    let mut countries = use_signal(Vec::<String>::new);
+    let mut promotion = use_signal(|| None::<String>);
+    let mut promotions = use_signal(Vec::<String>::new);
+    let mut page = use_signal(|| 0usize);
+    let mut page_count = use_signal(|| 0usize);
+
+    // reruns whenever the search, year or page signals change
     use_effect(move || {
-        // let mut correct = correct.clone();
-        // let mut incorrect = incorrect.clone();
+        let query = PastEventQuery {
+            search: Some(search()).filter(|s| !s.trim().is_empty()),
+            year: year(),
+            country: country(),
+            promotion: promotion(),
+            page: page(),
+            ..Default::default()
+        };
         spawn(async move {
-            // Fetch all events with predictions
-            if let Ok(predicted_events) = api::get_events_with_predictions().await {
-                // Filter for past events (date < today, date in mm/dd/yyyy)
-                let past_events = predicted_events.into_iter().collect::<Vec<_>>();
-                events.write().clear();
-                events.write().extend(past_events);
+            if let Ok(result) = api::get_past_events(query).await {
+                page_count.set(result.page_count(DEFAULT_PAGE_SIZE));
+                events.set(result.events);
+            }
+        });
+    });
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(y) = api::get_past_event_years().await {
+                years.set(y);
+            }
+        });
+        spawn(async move {
+            if let Ok(c) = api::get_event_countries().await {
+                countries.set(c);
             }
         });
         spawn(async move {
@@ -30,23 +63,101 @@ pub fn PastPredictions() -> Element {
                 *correct.write() = c;
             }
         });
+        spawn(async move {
+            if let Ok(p) = api::get_promotions().await {
+                promotions.set(p);
+            }
+        });
+        spawn(async move {
+            if let Ok(event_scores) = api::get_event_scores().await {
+                scores.set(event_scores.into_iter().collect());
+            }
+        });
+    });
+
+    // reruns when the promotion changes
+    use_effect(move || {
+        let promotion = promotion();
+        spawn(async move {
+            if let Ok(s) = api::get_user_scores(promotion).await {
+                user_scores.set(s);
+            }
+        });
     });
 
     rsx! [
         div { class: "container mx-auto",
+            div { class: "flex flex-wrap gap-4",
+                for (user, score) in user_scores() {
+                    div { class: "rounded-lg border border-gray-200 px-4 py-2",
+                        div { class: "text-sm text-gray-400", "{user}" }
+                        ScoreBadge { score }
+                        if let Some(accuracy) = score.accuracy() {
+                            span { class: "text-sm text-gray-400 ml-2", "{accuracy * 100.0:.0}%" }
+                        }
+                    }
+                }
+            }
+            div { class: "flex flex-col md:flex-row gap-4 mt-4",
+                input {
+                    class: "flex-1 rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    placeholder: "Search events",
+                    value: "{search}",
+                    oninput: move |e| {
+                        search.set(e.value());
+                        page.set(0);
+                    },
+                }
+                select {
+                    class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    onchange: move |e| {
+                        year.set(e.value().parse().ok());
+                        page.set(0);
+                    },
+                    option { value: "", "All years" }
+                    for y in years() {
+                        option { value: "{y}", selected: year() == Some(y), "{y}" }
+                    }
+                }
+                select {
+                    class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                    onchange: move |e| {
+                        country.set(Some(e.value()).filter(|c| !c.is_empty()));
+                        page.set(0);
+                    },
+                    option { value: "", "All countries" }
+                    for c in countries() {
+                        option { value: "{c}", selected: country() == Some(c.clone()), "{c}" }
+                    }
+                }
+                if promotions().len() > 1 {
+                    select {
+                        class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                        onchange: move |e| {
+                            promotion.set(Some(e.value()).filter(|p| !p.is_empty()));
+                            page.set(0);
+                        },
+                        option { value: "", "All promotions" }
+                        for p in promotions() {
+                            option { value: "{p}", selected: promotion() == Some(p.clone()), "{p}" }
+                        }
+                    }
+                }
+            }
             ul { class: "divide-y  rounded-lg border border-gray-200 shadow-md mt-4",
                 {
                     events()
                         .iter()
-                        .map(|(id, name, date, link)| {
+                        .map(|(id, name, date, _)| {
+                            let score = scores().get(id).copied();
                             rsx! {
                                 Link {
-                                    to: Route::PastEvent {
-                                        id: id.clone(),
-                                        link: link.clone(),
-                                    },
+                                    to: Route::PastEvent { id: id.clone() },
                                     li { class: "p-4 flex flex-col md:flex-row md:justify-between items-start md:items-center cursor-pointer hover:bg-gray-100 transition",
                                         span { class: "font-semibold text-lg", "{name}" }
+                                        if let Some(score) = score {
+                                            ScoreBadge { score }
+                                        }
                                         span { class: "text-gray-500 ml-2", "{date}" }
                                     }
                                 }
@@ -54,43 +165,81 @@ pub fn PastPredictions() -> Element {
                         })
                 }
             }
-                // (if events().is_empty() {
-        //     rsx!(div { class: "text-gray-400 mt-8 text-center", "No past predictions found." })
-        // } else {
-        //     rsx!()
-        // })
+            if events().is_empty() {
+                div { class: "text-gray-400 mt-8 text-center", "No past predictions found." }
+            }
+            if page_count() > 1 {
+                div { class: "flex justify-center items-center gap-4 mt-4",
+                    button {
+                        class: "px-4 py-2 rounded-lg border border-gray-200 disabled:opacity-50",
+                        disabled: page() == 0,
+                        onclick: move |_| page -= 1,
+                        "Previous"
+                    }
+                    span { class: "text-gray-400", "Page {page() + 1} of {page_count}" }
+                    button {
+                        class: "px-4 py-2 rounded-lg border border-gray-200 disabled:opacity-50",
+                        disabled: page() + 1 >= page_count(),
+                        onclick: move |_| page += 1,
+                        "Next"
+                    }
+                }
+            }
+            TicketList {}
         }
     ]
 }
 
 #[component]
-pub fn PastEvent(id: usize, link: String) -> Element {
+fn ScoreBadge(score: Score) -> Element {
+    let color = match score.accuracy() {
+        Some(accuracy) if accuracy >= 0.5 => "text-green-400",
+        Some(_) => "text-red-400",
+        None => "text-gray-400",
+    };
+    rsx! {
+        span { class: "text-sm font-bold {color}",
+            "{score.correct}-{score.incorrect}"
+            if score.pending > 0 {
+                span { class: "text-gray-400 font-normal ml-1", "({score.pending} pending)" }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn PastEvent(id: usize) -> Element {
     let mut fights: Signal<Vec<(String, String)>> = use_signal(|| Vec::<(String, String)>::new());
     let mut predictions: Signal<Vec<(String, String)>> =
         use_signal(|| Vec::<(String, String)>::new());
     let mut correctMap: Signal<HashMap<(String, String), bool>> =
         use_signal(|| HashMap::<(String, String), bool>::new());
+    let mut event_score = use_signal(|| None::<(i64, i64)>);
+    let mut fighter_ids = use_signal(HashMap::<String, String>::new);
+    // bumped after an admin corrects a result, to reload the card
+    let mut revision = use_signal(|| 0usize);
     use_effect(move || {
+        revision();
         // let mut fights = fights.clone();
         // let mut predictions = predictions.clone();
         // let mut correctMap = correctMap.clone();
-        let link = link.clone();
         spawn(async move {
-            if let Ok(result) = api::scrape_results(link, id).await {
+            if let Ok(result) = api::get_event_results(id).await {
                 fights.write().clear();
                 fights.write().extend(result);
             }
+            if let Ok(ids) = api::get_fighter_ids(id).await {
+                fighter_ids.set(ids);
+            }
             if let Ok(predicted_fights) = api::get_predictions(id).await {
                 predictions.write().clear();
                 predictions.write().extend(predicted_fights);
             }
+            correctMap.write().clear();
             fights.read().iter().for_each(|(f1, f2)| {
                 let f1: String = f1.clone();
                 let f2 = f2.clone();
                 spawn(async move {
-                    if let Ok(_resultid) = api::add_result(id, f1.clone(), f2.clone()).await {
-                        println!("result added");
-                    }
                     if let Some(pred) = predictions
                         .read()
                         .iter()
@@ -104,7 +253,10 @@ pub fn PastEvent(id: usize, link: String) -> Element {
                         }
                     }
                 });
-            })
+            });
+            if let Ok(counts) = api::get_prediction_correctness_for_event(id).await {
+                event_score.set(Some(counts));
+            }
         });
         spawn(async move {
             if let Ok(predicted_fights) = api::get_predictions(id).await {
@@ -116,15 +268,22 @@ pub fn PastEvent(id: usize, link: String) -> Element {
 
     rsx! {
         div { class: "container mx-auto",
-            h1 { class: "text-2xl font-bold mb-4", "Event Results" }
+            EventHeader { id, title: "Event Results" }
+            if let Some((c, i)) = event_score() {
+                div { class: "text-lg text-gray-400", "You went {c}/{c + i}" }
+            }
+            PickCardLinks { id, label: "Share your results card" }
             ul { class: "rounded-lg shadow-md mt-4",
                 {fights().iter().map(|(winner, loser)| rsx! {
                     li { class: "p-6 grid grid-cols-3 items-center gap-4 justify-items-center w-full border shadow-sm rounded-lg my-4",
-                        button {
-                            class: "bg-green-500 text-white border border-gray-200 px-6 py-3 rounded-lg text-xl font-bold shadow-md w-full min-w-0 min-h-[56px] flex items-center justify-center cursor-not-allowed",
-                            style: "width: 100%; max-width:40vw",
-                            disabled: true,
-                            "{winner}"
+                        div { class: "flex flex-col items-center w-full",
+                            button {
+                                class: "bg-green-500 text-white border border-gray-200 px-6 py-3 rounded-lg text-xl font-bold shadow-md w-full min-w-0 min-h-[56px] flex items-center justify-center cursor-not-allowed",
+                                style: "width: 100%; max-width:40vw",
+                                disabled: true,
+                                "{winner}"
+                            }
+                            FighterProfileLink { id: fighter_ids().get(winner).cloned() }
                         }
                         span { class: "mx-6 text-3xl font-extrabold text-gray-400 text-center w-20vw flex flex-col items-center justify-center",
                             "vs"
@@ -160,30 +319,34 @@ pub fn PastEvent(id: usize, link: String) -> Element {
                                 }
                             }
                         }
-                        button {
-                            class: format!(
-                                "bg-gray-200  {} text-gray-700 px-6 py-3 rounded-lg text-xl font-bold shadow-md w-full min-w-0 min-h-[56px] flex items-center justify-center cursor-not-allowed",
-                                {
-                                    let correct_map_ref = correctMap();
-                                    let val = correct_map_ref.get(&(winner.clone(), loser.clone()));
-                                    if let Some(value) = val {
-                                        if *value == true {
-                                            "border-1 text-gray-700"
+                        div { class: "flex flex-col items-center w-full",
+                            button {
+                                class: format!(
+                                    "bg-gray-200  {} text-gray-700 px-6 py-3 rounded-lg text-xl font-bold shadow-md w-full min-w-0 min-h-[56px] flex items-center justify-center cursor-not-allowed",
+                                    {
+                                        let correct_map_ref = correctMap();
+                                        let val = correct_map_ref.get(&(winner.clone(), loser.clone()));
+                                        if let Some(value) = val {
+                                            if *value == true {
+                                                "border-1 text-gray-700"
+                                            } else {
+                                                "border-5 text-red-500"
+                                            }
                                         } else {
-                                            "border-5 text-red-500"
+                                            "1"
                                         }
-                                    } else {
-                                        "1"
-                                    }
-                                },
-                            ),
-                            style: "width: 100%; min-width: 0; max-width: 40vw; ",
-                            disabled: false,
-                            "{loser}"
+                                    },
+                                ),
+                                style: "width: 100%; min-width: 0; max-width: 40vw; ",
+                                disabled: false,
+                                "{loser}"
+                            }
+                            FighterProfileLink { id: fighter_ids().get(loser).cloned() }
                         }
                     }
                 })}
             }
+            ResultCorrections { id, on_change: move |_| revision += 1 }
         }
     }
 }
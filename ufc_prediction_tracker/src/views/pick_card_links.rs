@@ -0,0 +1,24 @@
+use dioxus::prelude::*;
+
+/// Download links for the current user's pick sheet, which becomes a results card once the
+/// event's results are in.
+#[component]
+pub fn PickCardLinks(id: usize, label: String) -> Element {
+    rsx! {
+        div { class: "flex gap-4 items-center text-sm mb-4",
+            span { class: "text-gray-400", "{label}" }
+            a {
+                class: "px-4 py-2 rounded-lg border border-gray-200",
+                href: "/api/pick_card.png?event_id={id}",
+                download: "picks-{id}.png",
+                "PNG"
+            }
+            a {
+                class: "px-4 py-2 rounded-lg border border-gray-200",
+                href: "/api/pick_card.svg?event_id={id}",
+                download: "picks-{id}.svg",
+                "SVG"
+            }
+        }
+    }
+}
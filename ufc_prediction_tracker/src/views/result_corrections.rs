@@ -0,0 +1,155 @@
+use super::event_editor::result_text;
+use crate::{
+    api,
+    db::{Bout, BoutOutcome, ResultOverride, User},
+};
+use dioxus::prelude::*;
+
+/// The corrections made to an event's results, and for admins, correcting a bout to a win, draw or
+/// no contest, or fetching the card from ufcstats again.
+#[component]
+pub fn ResultCorrections(id: usize, on_change: EventHandler<()>) -> Element {
+    let mut user = use_signal(|| None::<User>);
+    let mut bouts = use_signal(Vec::<Bout>::new);
+    let mut overrides = use_signal(Vec::<ResultOverride>::new);
+    let mut reason = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+
+    let refresh = move || {
+        spawn(async move {
+            if let Ok(o) = api::get_result_overrides(id).await {
+                overrides.set(o);
+            }
+            if let Ok(b) = api::get_bouts(id).await {
+                bouts.set(b);
+            }
+        });
+    };
+    let mut correct = move |bout: Bout, outcome: BoutOutcome, winner: Option<String>| {
+        error.set(None);
+        let result = Bout {
+            outcome: Some(outcome),
+            winner,
+            ..bout
+        };
+        spawn(async move {
+            match api::override_result(result, reason()).await {
+                Ok(_) => {
+                    reason.set(String::new());
+                    on_change.call(());
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            }
+            refresh();
+        });
+    };
+    use_effect(move || {
+        refresh();
+        spawn(async move {
+            if let Ok(u) = api::get_current_user().await {
+                user.set(u);
+            }
+        });
+    });
+
+    let is_admin = user().is_some_and(|u| u.is_admin);
+    if !is_admin && overrides().is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "rounded-lg border border-gray-200 p-4 my-4",
+            h2 { class: "text-xl font-bold mb-2", "Corrections" }
+            if is_admin {
+                div { class: "flex flex-col md:flex-row gap-4 items-center mb-4",
+                    input {
+                        class: "flex-1 rounded-lg border border-gray-200 px-4 py-2 text-black",
+                        placeholder: "Reason for the correction",
+                        value: "{reason}",
+                        oninput: move |e| reason.set(e.value()),
+                    }
+                    button {
+                        class: "px-4 py-2 rounded-lg border border-gray-200",
+                        onclick: move |_| {
+                            error.set(None);
+                            spawn(async move {
+                                match api::resync_event_results(id).await {
+                                    Ok(()) => on_change.call(()),
+                                    Err(e) => error.set(Some(e.to_string())),
+                                }
+                            });
+                        },
+                        "Fetch results again"
+                    }
+                }
+                ul { class: "divide-y divide-gray-200",
+                    for bout in bouts() {
+                        li { class: "flex flex-wrap gap-2 justify-between items-center py-2",
+                            span { class: "flex-1", "{bout.fighter1} vs {bout.fighter2}" }
+                            span { class: "text-gray-400 text-sm", {result_text(&bout)} }
+                            button {
+                                class: "px-2 py-1 rounded-lg border border-gray-200",
+                                onclick: {
+                                    let bout = bout.clone();
+                                    move |_| correct(
+                                        bout.clone(),
+                                        BoutOutcome::Win,
+                                        Some(bout.fighter1.clone()),
+                                    )
+                                },
+                                "{bout.fighter1} won"
+                            }
+                            button {
+                                class: "px-2 py-1 rounded-lg border border-gray-200",
+                                onclick: {
+                                    let bout = bout.clone();
+                                    move |_| correct(
+                                        bout.clone(),
+                                        BoutOutcome::Win,
+                                        Some(bout.fighter2.clone()),
+                                    )
+                                },
+                                "{bout.fighter2} won"
+                            }
+                            button {
+                                class: "px-2 py-1 rounded-lg border border-gray-200",
+                                onclick: {
+                                    let bout = bout.clone();
+                                    move |_| correct(bout.clone(), BoutOutcome::Draw, None)
+                                },
+                                "Draw"
+                            }
+                            button {
+                                class: "px-2 py-1 rounded-lg border border-gray-200",
+                                onclick: {
+                                    let bout = bout.clone();
+                                    move |_| correct(bout.clone(), BoutOutcome::NoContest, None)
+                                },
+                                "No contest"
+                            }
+                        }
+                    }
+                }
+            }
+            if let Some(e) = error() {
+                div { class: "text-red-400 mt-2", "{e}" }
+            }
+            ul { class: "text-sm text-gray-400 mt-2",
+                for o in overrides() {
+                    li {
+                        "{o.created_at}: {o.admin} recorded "
+                        match o.outcome {
+                            BoutOutcome::Win => format!("{} def. {}", o.winner, o.loser),
+                            BoutOutcome::Draw => format!("{} vs {}, a draw", o.winner, o.loser),
+                            BoutOutcome::NoContest => format!("{} vs {}, no contest", o.winner, o.loser),
+                        }
+                        if let Some(previous) = o.previous_winner.clone() {
+                            " (was {previous})"
+                        }
+                        ", {o.reason}"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use crate::{
+    api,
+    db::{self, Score},
+    Route,
+};
+use dioxus::prelude::*;
+
+const CHART_WIDTH: f64 = 600.0;
+const CHART_HEIGHT: f64 = 200.0;
+const CHART_PADDING: f64 = 30.0;
+const BAR_HEIGHT: f64 = 24.0;
+// room for the label left of each bar and the caption right of it
+const BAR_LABEL_WIDTH: f64 = 160.0;
+const BAR_CAPTION_WIDTH: f64 = 140.0;
+const LINE: &str = "#3b82f6";
+const MUTED: &str = "#9ca3af";
+
+/// The current user's numbers: how often they're right overall, over time, by weight class and
+/// by how bouts end, who they back most, and their best and worst events.
+#[component]
+pub fn Stats() -> Element {
+    let mut stats = use_signal(|| None::<db::Stats>);
+    let mut promotions = use_signal(Vec::<String>::new);
+    let mut promotion = use_signal(|| None::<String>);
+    let mut error = use_signal(|| None::<String>);
+
+    use_effect(move || {
+        spawn(async move {
+            if let Ok(p) = api::get_promotions().await {
+                promotions.set(p);
+            }
+        });
+    });
+    // reruns when the promotion changes
+    use_effect(move || {
+        let promotion = promotion();
+        spawn(async move {
+            match api::get_stats(promotion).await {
+                Ok(s) => stats.set(Some(s)),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    });
+
+    rsx! {
+        div { class: "container mx-auto",
+            div { class: "flex justify-between items-center",
+                h1 { class: "text-2xl font-bold", "Stats" }
+                if promotions().len() > 1 {
+                    select {
+                        class: "rounded-lg border border-gray-200 px-4 py-2 text-black",
+                        onchange: move |e| promotion.set(Some(e.value()).filter(|p| !p.is_empty())),
+                        option { value: "", "All promotions" }
+                        for p in promotions() {
+                            option { value: "{p}", selected: promotion() == Some(p.clone()), "{p}" }
+                        }
+                    }
+                }
+            }
+            if let Some(e) = error() {
+                div { class: "text-red-400 mt-4", "{e}" }
+            }
+            if let Some(stats) = stats() {
+                div { class: "grid grid-cols-2 md:grid-cols-5 gap-4 my-4",
+                    Tile {
+                        label: "Accuracy",
+                        value: stats.overall.accuracy().map_or("–".to_string(), percent),
+                    }
+                    Tile { label: "Correct", value: "{stats.overall.correct}" }
+                    Tile { label: "Incorrect", value: "{stats.overall.incorrect}" }
+                    Tile { label: "Pending", value: "{stats.overall.pending}" }
+                    Tile { label: "Events picked", value: "{stats.events}" }
+                }
+                if stats.overall.decided() == 0 {
+                    div { class: "text-gray-400", "No results for your picks yet." }
+                } else {
+                    h2 { class: "text-xl font-bold mt-8 mb-2", "Accuracy over time" }
+                    AccuracyChart { months: stats.by_month.clone() }
+                    div { class: "grid md:grid-cols-2 gap-8",
+                        div {
+                            h2 { class: "text-xl font-bold mt-8 mb-2", "By weight class" }
+                            BarChart { rows: accuracy_rows(&stats.by_weight_class) }
+                        }
+                        div {
+                            h2 { class: "text-xl font-bold mt-8 mb-2", "By method" }
+                            BarChart { rows: accuracy_rows(&stats.by_method) }
+                        }
+                    }
+                    h2 { class: "text-xl font-bold mt-8 mb-2", "Most picked fighters" }
+                    BarChart { rows: pick_rows(&stats.most_picked) }
+                    div { class: "grid md:grid-cols-2 gap-8",
+                        div {
+                            h2 { class: "text-xl font-bold mt-8 mb-2", "Best events" }
+                            EventList { events: stats.best_events.clone() }
+                        }
+                        if !stats.worst_events.is_empty() {
+                            div {
+                                h2 { class: "text-xl font-bold mt-8 mb-2", "Worst events" }
+                                EventList { events: stats.worst_events.clone() }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn Tile(label: String, value: String) -> Element {
+    rsx! {
+        div { class: "rounded-lg border border-gray-200 px-4 py-2",
+            div { class: "text-sm text-gray-400", "{label}" }
+            div { class: "text-2xl font-bold", "{value}" }
+        }
+    }
+}
+
+/// Accuracy per month as a line, with 50% marked.
+#[component]
+fn AccuracyChart(months: Vec<(String, Score)>) -> Element {
+    let decided = months
+        .into_iter()
+        .filter_map(|(month, score)| Some((month, score.accuracy()?)))
+        .collect::<Vec<_>>();
+    let points = chart_points(
+        &decided.iter().map(|(_, a)| *a).collect::<Vec<_>>(),
+        CHART_WIDTH,
+        CHART_HEIGHT,
+    );
+    let polyline = points
+        .iter()
+        .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let half = CHART_HEIGHT / 2.0;
+    let bottom = CHART_HEIGHT - CHART_PADDING;
+    let labelled = decided
+        .iter()
+        .zip(&points)
+        .enumerate()
+        // every month on short charts, otherwise about six of them
+        .filter(|(i, _)| i % decided.len().div_ceil(6).max(1) == 0)
+        .map(|(_, ((month, accuracy), point))| (month.clone(), *accuracy, *point))
+        .collect::<Vec<_>>();
+
+    rsx! {
+        svg {
+            class: "w-full",
+            view_box: "0 0 {CHART_WIDTH} {CHART_HEIGHT}",
+            line {
+                x1: "{CHART_PADDING}",
+                y1: "{half}",
+                x2: "{CHART_WIDTH - CHART_PADDING}",
+                y2: "{half}",
+                stroke: MUTED,
+                stroke_dasharray: "4 4",
+            }
+            text {
+                x: "0",
+                y: "{half + 4.0}",
+                fill: MUTED,
+                font_size: "10",
+                "50%"
+            }
+            polyline {
+                points: "{polyline}",
+                fill: "none",
+                stroke: LINE,
+                stroke_width: "2",
+            }
+            for (x , y) in points.iter().copied() {
+                circle { cx: "{x}", cy: "{y}", r: "3", fill: LINE }
+            }
+            for (month , accuracy , (x , y)) in labelled {
+                text {
+                    x: "{x}",
+                    y: "{bottom + 20.0}",
+                    fill: MUTED,
+                    font_size: "10",
+                    text_anchor: "middle",
+                    "{month}"
+                }
+                text {
+                    x: "{x}",
+                    y: "{y - 8.0}",
+                    fill: MUTED,
+                    font_size: "10",
+                    text_anchor: "middle",
+                    {percent(accuracy)}
+                }
+            }
+        }
+    }
+}
+
+/// One horizontal bar per row, as (label, fraction of the full width, caption).
+#[component]
+fn BarChart(rows: Vec<(String, f64, String)>) -> Element {
+    if rows.is_empty() {
+        return rsx! {
+            div { class: "text-gray-400", "Nothing to show yet." }
+        };
+    }
+    let height = rows.len() as f64 * BAR_HEIGHT;
+    let bar_width = CHART_WIDTH - BAR_LABEL_WIDTH - BAR_CAPTION_WIDTH;
+
+    rsx! {
+        svg { class: "w-full", view_box: "0 0 {CHART_WIDTH} {height}",
+            for (i , (label , fraction , caption)) in rows.into_iter().enumerate() {
+                text {
+                    x: "0",
+                    y: "{i as f64 * BAR_HEIGHT + 16.0}",
+                    fill: "currentColor",
+                    font_size: "12",
+                    "{label}"
+                }
+                rect {
+                    x: "{BAR_LABEL_WIDTH}",
+                    y: "{i as f64 * BAR_HEIGHT + 4.0}",
+                    width: "{(fraction.clamp(0.0, 1.0) * bar_width).max(1.0):.1}",
+                    height: "{BAR_HEIGHT - 8.0}",
+                    rx: "3",
+                    fill: LINE,
+                }
+                text {
+                    x: "{BAR_LABEL_WIDTH + bar_width + 8.0}",
+                    y: "{i as f64 * BAR_HEIGHT + 16.0}",
+                    fill: MUTED,
+                    font_size: "12",
+                    "{caption}"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn EventList(events: Vec<(db::Event, Score)>) -> Element {
+    rsx! {
+        ul { class: "divide-y divide-gray-200 rounded-lg border border-gray-200",
+            for (event , score) in events {
+                Link { to: Route::PastEvent { id: event.id }, class: "block",
+                    li { class: "p-4 hover:bg-blue-500 flex justify-between items-center gap-4",
+                        div { class: "flex-1",
+                            div { class: "font-bold", "{event.name}" }
+                            div { class: "text-sm text-gray-400", {event.display_date()} }
+                        }
+                        div { class: "text-sm text-gray-400",
+                            "{score.correct} of {score.decided()}, "
+                            {score.accuracy().map(percent).unwrap_or_default()}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn percent(fraction: f64) -> String {
+    format!("{:.0}%", fraction * 100.0)
+}
+
+/// Bars for accuracy, for the groups that have decided picks.
+fn accuracy_rows(scores: &[(String, Score)]) -> Vec<(String, f64, String)> {
+    scores
+        .iter()
+        .filter_map(|(label, score)| {
+            let accuracy = score.accuracy()?;
+            Some((
+                label.clone(),
+                accuracy,
+                format!("{} of {}", percent(accuracy), score.decided()),
+            ))
+        })
+        .collect()
+}
+
+/// Bars for how often each fighter was picked, against the most picked.
+fn pick_rows(picked: &[(String, Score)]) -> Vec<(String, f64, String)> {
+    let total = |score: &Score| score.decided() + score.pending;
+    let most = picked
+        .iter()
+        .map(|(_, s)| total(s))
+        .max()
+        .unwrap_or(1)
+        .max(1);
+    picked
+        .iter()
+        .map(|(fighter, score)| {
+            let mut caption = format!("{} picks", total(score));
+            if let Some(accuracy) = score.accuracy() {
+                caption.push_str(&format!(", {} right", percent(accuracy)));
+            }
+            (fighter.clone(), total(score) as f64 / most as f64, caption)
+        })
+        .collect()
+}
+
+/// Where each value between 0 and 1 goes on a chart of the given size: spread evenly left to right
+/// inside the padding, 1 at the top. A single value sits in the middle.
+fn chart_points(values: &[f64], width: f64, height: f64) -> Vec<(f64, f64)> {
+    let inner_width = width - 2.0 * CHART_PADDING;
+    let inner_height = height - 2.0 * CHART_PADDING;
+    let step = match values.len() {
+        0 | 1 => 0.0,
+        n => inner_width / (n - 1) as f64,
+    };
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let x = if values.len() == 1 {
+                width / 2.0
+            } else {
+                CHART_PADDING + i as f64 * step
+            };
+            (
+                x,
+                CHART_PADDING + (1.0 - value.clamp(0.0, 1.0)) * inner_height,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn places_points_inside_the_padding() {
+        assert_eq!(
+            chart_points(&[1.0, 0.5, 0.0], 200.0, 100.0),
+            vec![(30.0, 30.0), (100.0, 50.0), (170.0, 70.0)]
+        );
+        assert_eq!(chart_points(&[0.25], 200.0, 100.0), vec![(100.0, 60.0)]);
+        assert!(chart_points(&[], 200.0, 100.0).is_empty());
+    }
+
+    #[test]
+    fn scales_pick_bars_to_the_most_picked() {
+        let score = |correct, incorrect, pending| Score {
+            correct,
+            incorrect,
+            pending,
+        };
+        let rows = pick_rows(&[
+            ("A".to_string(), score(3, 1, 0)),
+            ("B".to_string(), score(0, 0, 2)),
+        ]);
+        assert_eq!(
+            rows[0],
+            ("A".to_string(), 1.0, "4 picks, 75% right".to_string())
+        );
+        assert_eq!(rows[1], ("B".to_string(), 0.5, "2 picks".to_string()));
+    }
+}
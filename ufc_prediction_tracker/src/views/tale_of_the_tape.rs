@@ -0,0 +1,93 @@
+use crate::{api, db::Fighter};
+use dioxus::prelude::*;
+
+// which side a row favours; `None` when it can't be compared
+fn advantage(left: Option<f64>, right: Option<f64>, higher_is_better: bool) -> Option<usize> {
+    let (left, right) = (left?, right?);
+    if left == right {
+        return None;
+    }
+    Some(if (left > right) == higher_is_better {
+        0
+    } else {
+        1
+    })
+}
+
+/// Side-by-side comparison of the two fighters in a bout, shown when a bout row in
+/// [`Predict`](super::Predict) is expanded.
+#[component]
+pub fn TaleOfTheTape(fighter1_id: String, fighter2_id: String) -> Element {
+    let mut matchup = use_signal(|| None::<(Fighter, Fighter)>);
+    let mut error = use_signal(|| None::<String>);
+    use_effect(move || {
+        let (fighter1_id, fighter2_id) = (fighter1_id.clone(), fighter2_id.clone());
+        spawn(async move {
+            match api::get_matchup(fighter1_id, fighter2_id).await {
+                Ok(m) => matchup.set(Some(m)),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    });
+
+    let Some((left, right)) = matchup() else {
+        return rsx! {
+            div { class: "text-gray-400 text-sm text-center",
+                {error().unwrap_or_else(|| "Loading fighter stats…".to_string())}
+            }
+        };
+    };
+    let number = |v: Option<f64>, suffix: &str| {
+        v.map(|v| format!("{v}{suffix}"))
+            .unwrap_or_else(|| "--".to_string())
+    };
+    let text = |v: &Option<String>| v.clone().unwrap_or_else(|| "--".to_string());
+    let age = |f: &Fighter| f.age().map(f64::from);
+    let rows = [
+        ("Record", text(&left.record), text(&right.record), None),
+        (
+            "Reach",
+            number(left.reach_inches, "\""),
+            number(right.reach_inches, "\""),
+            advantage(left.reach_inches, right.reach_inches, true),
+        ),
+        (
+            "Age",
+            number(age(&left), ""),
+            number(age(&right), ""),
+            advantage(age(&left), age(&right), false),
+        ),
+        (
+            "Strikes landed / min",
+            number(left.slpm, ""),
+            number(right.slpm, ""),
+            advantage(left.slpm, right.slpm, true),
+        ),
+        (
+            "Takedowns / 15 min",
+            number(left.td_avg, ""),
+            number(right.td_avg, ""),
+            advantage(left.td_avg, right.td_avg, true),
+        ),
+        (
+            "Recent form",
+            text(&left.recent_form),
+            text(&right.recent_form),
+            None,
+        ),
+    ];
+
+    rsx! {
+        table { class: "w-full text-center text-sm",
+            tbody {
+                for (label, l, r, better) in rows {
+                    tr { class: "border-t border-gray-100",
+                        td { class: if better == Some(0) { "py-1 font-bold text-green-400" } else { "py-1" }, "{l}" }
+                        td { class: "py-1 text-gray-400", "{label}" }
+                        td { class: if better == Some(1) { "py-1 font-bold text-green-400" } else { "py-1" }, "{r}" }
+                    }
+                }
+            }
+        }
+    }
+}
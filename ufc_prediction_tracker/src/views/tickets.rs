@@ -0,0 +1,173 @@
+use crate::{
+    api,
+    db::{self, Settlement, Ticket, TicketLeg},
+};
+use dioxus::prelude::*;
+use std::collections::HashMap;
+
+/// The ticket being built. It's kept across pages so legs can come from several events.
+static DRAFT: GlobalSignal<Vec<TicketLeg>> = Signal::global(Vec::new);
+
+fn settlement_class(status: Settlement) -> &'static str {
+    match status {
+        Settlement::Pending => "text-gray-400",
+        Settlement::Won => "text-green-500",
+        Settlement::Lost => "text-red-400",
+        Settlement::Void => "text-yellow-500",
+    }
+}
+
+/// Adds the event's picks to the draft ticket at entered odds, and places it.
+#[component]
+pub fn TicketBuilder(event_id: usize, picks: Vec<(String, String)>) -> Element {
+    // odds typed in per pick winner, before the pick is added
+    let mut odds = use_signal(HashMap::<String, String>::new);
+    let mut stake = use_signal(|| "10".to_string());
+    let mut message = use_signal(|| None::<String>);
+
+    let on_ticket = |winner: &str, loser: &str| {
+        DRAFT.read().iter().any(|leg| {
+            leg.event_id == event_id
+                && [&leg.winner, &leg.loser].contains(&&winner.to_string())
+                && [&leg.winner, &leg.loser].contains(&&loser.to_string())
+        })
+    };
+    let draft = DRAFT();
+    let combined = db::combined_odds(&draft);
+    let payout = stake().parse::<f64>().ok().map(|s| s * combined);
+
+    rsx! {
+        div { class: "rounded-lg border border-gray-200 shadow-md mt-4 p-4",
+            h2 { class: "text-xl font-bold mb-2", "Ticket" }
+            for (winner , loser) in picks.into_iter().filter(|(w, l)| !on_ticket(w, l)) {
+                div { class: "flex items-center gap-4 my-2",
+                    span { class: "flex-1", "{winner} over {loser}" }
+                    input {
+                        class: "w-24 rounded-lg border border-gray-200 px-2 py-1 text-black",
+                        placeholder: "Odds",
+                        value: odds().get(&winner).cloned().unwrap_or_default(),
+                        oninput: {
+                            let winner = winner.clone();
+                            move |e: FormEvent| {
+                                odds.write().insert(winner.clone(), e.value());
+                            }
+                        },
+                    }
+                    button {
+                        class: "px-4 py-1 rounded-lg border border-gray-200 hover:bg-blue-500",
+                        onclick: move |_| {
+                            match odds().get(&winner).and_then(|o| o.trim().parse::<f64>().ok()) {
+                                Some(o) if o > 1.0 => {
+                                    message.set(None);
+                                    DRAFT.write().push(TicketLeg {
+                                        event_id,
+                                        winner: winner.clone(),
+                                        loser: loser.clone(),
+                                        odds: o,
+                                        ..Default::default()
+                                    });
+                                }
+                                _ => message.set(Some("Enter decimal odds above 1.0, e.g. 1.85".to_string())),
+                            }
+                        },
+                        "Add"
+                    }
+                }
+            }
+            if !draft.is_empty() {
+                ul { class: "divide-y divide-gray-200 mt-4",
+                    for (i , leg) in draft.iter().cloned().enumerate() {
+                        li { class: "flex items-center gap-4 py-2",
+                            span { class: "flex-1", "{leg.winner} over {leg.loser}" }
+                            span { class: "text-gray-400", "@ {leg.odds:.2}" }
+                            button {
+                                class: "text-sm text-red-400",
+                                onclick: move |_| {
+                                    DRAFT.write().remove(i);
+                                },
+                                "Remove"
+                            }
+                        }
+                    }
+                }
+                div { class: "flex flex-wrap items-center gap-4 mt-4",
+                    span { "Combined odds {combined:.2}" }
+                    label { "Stake" }
+                    input {
+                        class: "w-24 rounded-lg border border-gray-200 px-2 py-1 text-black",
+                        value: "{stake}",
+                        oninput: move |e| stake.set(e.value()),
+                    }
+                    if let Some(payout) = payout {
+                        span { class: "text-gray-400", "Pays {payout:.2}" }
+                    }
+                    button {
+                        class: "px-4 py-2 rounded-lg bg-blue-500 text-white font-bold",
+                        onclick: move |_| {
+                            let Ok(stake) = stake().trim().parse::<f64>() else {
+                                message.set(Some("Stake must be a number".to_string()));
+                                return;
+                            };
+                            spawn(async move {
+                                match api::create_ticket(stake, DRAFT()).await {
+                                    Ok(_) => {
+                                        DRAFT.write().clear();
+                                        message.set(Some("Ticket placed".to_string()));
+                                    }
+                                    Err(e) => message.set(Some(e.to_string())),
+                                }
+                            });
+                        },
+                        "Place ticket"
+                    }
+                }
+            }
+            if let Some(m) = message() {
+                div { class: "text-gray-400 mt-2", "{m}" }
+            }
+        }
+    }
+}
+
+/// Every ticket we've placed, with how each leg went.
+#[component]
+pub fn TicketList() -> Element {
+    let mut tickets = use_signal(Vec::<Ticket>::new);
+    use_effect(move || {
+        spawn(async move {
+            match api::get_tickets().await {
+                Ok(t) => tickets.set(t),
+                Err(e) => log::error!("Failed to fetch tickets: {}", e),
+            }
+        });
+    });
+
+    rsx! {
+        if !tickets().is_empty() {
+            h2 { class: "text-xl font-bold mt-8", "Tickets" }
+            ul { class: "divide-y divide-gray-200 rounded-lg border border-gray-200 shadow-md mt-4",
+                for ticket in tickets() {
+                    li { class: "p-4",
+                        div { class: "flex justify-between items-center",
+                            span { class: "font-bold",
+                                "{ticket.legs.len()}-leg ticket, {ticket.stake:.2} @ {ticket.combined_odds():.2}"
+                            }
+                            span { class: settlement_class(ticket.status),
+                                match ticket.payout {
+                                    Some(payout) => format!("{} {payout:.2}", ticket.status.as_str()),
+                                    None => ticket.status.as_str().to_string(),
+                                }
+                            }
+                        }
+                        for leg in ticket.legs.iter() {
+                            div { class: "flex justify-between text-sm",
+                                span { "{leg.winner} over {leg.loser} @ {leg.odds:.2}" }
+                                span { class: settlement_class(leg.status), "{leg.status.as_str()}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}